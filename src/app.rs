@@ -1,13 +1,44 @@
+use crate::builder::{self, BuilderInput, ThemeBuilder};
+use crate::checker::{self, Checker, LinkStatus};
+use crate::color::{AA_LARGE, AA_TEXT, AAA_TEXT, ColorDepth, ContrastReport, Deficiency};
+use crate::config::Config;
+use crate::duplicates;
+use crate::events::AppEvent;
+use crate::export;
+use crate::highlight::CodeSample;
+use crate::history::{self, History, Snapshot};
 use crate::models::ThemeEntry;
+use crate::omarchy;
+use crate::tags::{self, Taxonomy};
+use crate::tasks::{AppMessage, TaskKind, TaskManager, TaskMessage};
+use crate::terminal::{self, GraphicsProtocol};
+use crate::wallpaper::{Wallpaper, WallpaperCache};
+use crate::workspace::{self, Checkout, GitAction, Workspace};
 use crate::{
     HttpClient,
     models::{
-        AppState, FocusArea, ListState, PreviewState, ReadmeContent, SearchResult, TabState,
-        ThemeApplicator, ThemeBrowserState,
+        AppState, CommandPanel, CommandPanelState, DuplicateBrowserState, FocusArea, ListState,
+        PreviewState, ReadmeContent, RepositoryEntry, SearchResult, SidebarView, TabState,
+        TagBrowserState, ThemeApplicator, ThemeBrowserState, UiLayout, YankFormat,
     },
 };
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Direction, Position};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Two clicks on the same row within this interval count as a double-click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Live preview waits this long after the cursor stops before fetching a theme
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Bounds (in percent of the width) for dragging the sidebar divider
+pub const DEFAULT_SIDEBAR_WIDTH: u16 = 20;
+const MIN_SIDEBAR_WIDTH: u16 = 10;
+const MAX_SIDEBAR_WIDTH: u16 = 50;
 
 pub struct App {
     pub state: AppState,
@@ -26,11 +57,54 @@ pub struct App {
     pub theme_applicator: ThemeApplicator,
     pub preview_state: PreviewState,
     pub theme_entries: Vec<ThemeEntry>,
+    pub tasks: TaskManager,
+    pub spinner_frame: usize,
+    pub layout: UiLayout,
+    pub sidebar_width: u16,
+    /// `y` was pressed and the next key picks what to copy
+    pub yank_pending: bool,
+    /// One-shot feedback shown in the footer until the next key press
+    pub status_message: Option<String>,
+    /// Where the clone action checks repositories out
+    pub workspace: Workspace,
+    pub command_panel: Option<CommandPanel>,
+    /// Theme builder screen, opened from the theme browser
+    pub builder: Option<ThemeBuilder>,
+    pub config: Config,
+    /// Colors beyond this depth are approximated when drawing
+    pub color_depth: ColorDepth,
+    /// Snippets for the editor preview: one per language, then the user's own file
+    pub code_samples: Vec<CodeSample>,
+    /// Wallpapers of previewed themes, decoded off the UI loop
+    pub wallpapers: WallpaperCache,
+    /// How wallpapers are drawn; half blocks work everywhere
+    pub graphics: GraphicsProtocol,
+    pub sidebar_view: SidebarView,
+    /// Sections whose subsections are folded away in the sidebar
+    pub collapsed: HashSet<usize>,
+    pub tag_browser: TagBrowserState,
+    pub duplicates: DuplicateBrowserState,
+    /// Tags entries are matched against
+    pub taxonomy: Taxonomy,
+    /// GitHub topics by lowercase `owner/repo`, when enabled
+    pub topics: HashMap<String, Vec<String>>,
+    /// Results of the last link check, by entry URL
+    pub link_status: HashMap<String, LinkStatus>,
+    /// README as of the last visit, which new content is compared against
+    pub baseline: Option<Snapshot>,
+    /// URL keys of entries added since the baseline
+    pub new_entries: HashSet<String>,
+    /// Where visits are recorded; unset until [`App::start_visit`]
+    history: Option<History>,
+    /// Where settings are saved; unset when running without a config file
+    config_path: Option<PathBuf>,
+    dragging_divider: bool,
+    last_click: Option<(Instant, u16)>,
 }
 
 impl App {
-    pub async fn new(client: HttpClient) -> Result<Self> {
-        let mut app = Self {
+    pub fn new(client: HttpClient, tasks: TaskManager) -> Self {
+        Self {
             state: AppState::Loading,
             tabs: Vec::new(),
             current_tab: 0,
@@ -52,22 +126,157 @@ impl App {
                 search_query: String::new(),
                 filtered_themes: Vec::new(),
                 filtered_selected: None,
+                pinned_theme: None,
+                compare_split: Direction::Horizontal,
+                compare_diff: false,
+                loaded_themes: HashMap::new(),
+                contrast: HashMap::new(),
+                simulation: None,
+                min_contrast: None,
+                sort_by_contrast: false,
+                sample_index: 0,
+                palette_editor: None,
             },
             theme_browser_mode: false,
             theme_applicator: ThemeApplicator::default(),
             preview_state: PreviewState::default(),
             theme_entries: Vec::new(),
+            tasks,
+            spinner_frame: 0,
+            layout: UiLayout::default(),
+            sidebar_width: DEFAULT_SIDEBAR_WIDTH,
+            yank_pending: false,
+            status_message: None,
+            workspace: Workspace::default(),
+            command_panel: None,
+            builder: None,
+            config: Config::default(),
+            color_depth: ColorDepth::TrueColor,
+            code_samples: CodeSample::builtins(),
+            wallpapers: WallpaperCache::default(),
+            graphics: GraphicsProtocol::HalfBlocks,
+            sidebar_view: SidebarView::default(),
+            collapsed: HashSet::new(),
+            tag_browser: TagBrowserState::default(),
+            duplicates: DuplicateBrowserState::default(),
+            taxonomy: Taxonomy::default(),
+            topics: HashMap::new(),
+            link_status: HashMap::new(),
+            baseline: None,
+            new_entries: HashSet::new(),
+            history: None,
+            config_path: None,
+            dragging_divider: false,
+            last_click: None,
+        }
+    }
+
+    /// An app already showing `content`, for tests that don't need its task messages
+    #[cfg(test)]
+    pub fn with_readme(content: ReadmeContent) -> Self {
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = Self::new(HttpClient::new(), tasks);
+        app.handle_message(AppMessage::Readme(Ok(content)));
+        app
+    }
+
+    /// Compare README content against what was current at the previous visit
+    pub fn start_visit(&mut self) {
+        let history = self.client.history().clone();
+        self.baseline = history.last_visit().and_then(|time| history.at(time));
+        self.history = Some(history);
+    }
+
+    /// Start loading the README in the background; existing content stays navigable
+    pub fn load_readme(&mut self, force_refresh: bool) {
+        if self.readme_content.is_none() {
+            self.state = AppState::Loading;
+        }
+
+        let client = self.client.clone();
+        self.tasks.spawn(TaskKind::Readme, async move {
+            AppMessage::Readme(client.fetch_readme(force_refresh).await)
+        });
+    }
+
+    /// Whether a README fetch is in flight
+    pub fn is_refreshing(&self) -> bool {
+        self.tasks.is_running(TaskKind::Readme)
+    }
+
+    /// Apply a message of a background task, unless the task was replaced or cancelled since
+    pub fn handle_task_message(&mut self, TaskMessage { id, message }: TaskMessage) {
+        let kind = message.kind();
+        let current = if message.is_final() {
+            self.tasks.finish(kind, id)
+        } else {
+            self.tasks.is_current(kind, id)
         };
+        if current {
+            self.handle_message(message);
+        }
+    }
 
-        app.load_readme(false).await?;
-        Ok(app)
+    /// Apply the result of a background task
+    pub fn handle_message(&mut self, message: AppMessage) {
+        match message {
+            AppMessage::Readme(result) => self.apply_readme(result),
+            AppMessage::ThemeList(result) => self.apply_theme_entries(result),
+            AppMessage::ThemeColors { name, result } => self.apply_theme_colors(name, result),
+            AppMessage::ThemesPrefetched(themes) => {
+                for (name, theme) in themes {
+                    self.theme_loaded(name, theme);
+                }
+            }
+            AppMessage::CachedThemes(themes) => {
+                for (name, theme) in themes {
+                    // Colors fetched meanwhile are newer than the cache
+                    if !self.theme_browser.loaded_themes.contains_key(&name) {
+                        self.theme_loaded(name, theme);
+                    }
+                }
+                self.prefetch_adjacent_themes();
+            }
+            AppMessage::ImagePalette(result) => self.apply_image_palette(result),
+            AppMessage::Wallpaper { name, result } => match result {
+                Ok(wallpaper) => self.wallpapers.insert(name, wallpaper.map(|w| *w)),
+                Err(e) => self.status_message = Some(format!("No wallpaper for {name}: {e:#}")),
+            },
+            AppMessage::Topics(Ok(topics)) => {
+                self.topics = topics;
+                self.retag_entries();
+            }
+            AppMessage::Topics(Err(e)) => {
+                self.status_message = Some(format!("No GitHub topics: {e:#}"));
+            }
+            AppMessage::LinkCheck(statuses) => {
+                self.status_message = Some(checker::summary(&statuses));
+                self.link_status = statuses;
+                // Moved repositories may turn out to be listed under their new name too
+                self.refresh_duplicates();
+            }
+            AppMessage::CommandOutput(line) => {
+                if let Some(panel) = self.command_panel.as_mut() {
+                    panel.log.push(line);
+                }
+            }
+            AppMessage::CommandFinished { action, result } => {
+                self.apply_command_result(action, result)
+            }
+        }
     }
 
-    pub async fn load_readme(&mut self, force_refresh: bool) -> Result<()> {
-        self.state = AppState::Loading;
+    fn apply_readme(&mut self, result: Result<ReadmeContent>) {
+        match result {
+            Ok(mut content) => {
+                // Cached READMEs may have been tagged by an older taxonomy
+                tags::tag_entries(&mut content, &self.taxonomy, &self.topics);
+                self.tag_browser.counts = tags::tag_counts(&content);
+                if self.config.github_topics {
+                    self.load_topics(&content);
+                }
+                self.show_changes(&mut content);
 
-        match self.client.fetch_readme(force_refresh).await {
-            Ok(content) => {
                 self.tabs = content
                     .sections
                     .iter()
@@ -90,6 +299,16 @@ impl App {
                     })
                     .collect();
 
+                // Section indices may have shifted, so keep folds by their place in the outline
+                let folded: HashSet<String> = self
+                    .readme_content
+                    .as_ref()
+                    .map(|old| self.collapsed.iter().map(|&i| old.breadcrumb(i)).collect())
+                    .unwrap_or_default();
+                self.collapsed = (0..content.sections.len())
+                    .filter(|&i| folded.contains(&content.breadcrumb(i)))
+                    .collect();
+
                 // Validate current_tab is within bounds
                 if self.current_tab >= self.tabs.len() {
                     self.current_tab = 0;
@@ -101,17 +320,177 @@ impl App {
 
                 self.readme_content = Some(content);
                 self.state = AppState::Ready;
+                self.tag_counts_changed();
+                self.refresh_duplicates();
             }
-            Err(e) => {
+            // Keep showing the previous content if only a refresh failed
+            Err(e) if self.readme_content.is_none() => {
                 self.state = AppState::Error(e.to_string());
             }
+            Err(_) => {}
         }
+    }
 
-        Ok(())
+    /// Mark entries added since the last visit and list every change in a section of its own
+    fn show_changes(&mut self, content: &mut ReadmeContent) {
+        let now = history::now();
+        if let Some(history) = &self.history
+            && let Err(e) = history.mark_visit(now)
+        {
+            self.status_message = Some(format!("Could not record this visit: {e:#}"));
+        }
+        let Some(baseline) = &self.baseline else {
+            return;
+        };
+
+        let changes = history::diff(baseline, &Snapshot::of(content, now));
+        self.new_entries = changes
+            .added
+            .iter()
+            .map(|entry| duplicates::url_key(&entry.url))
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        self.status_message = Some(format!(
+            "Since {}: {}",
+            history::format_date(baseline.taken_at),
+            changes.summary()
+        ));
+        content.prepend_section(history::changes_section(&changes, baseline.taken_at));
+    }
+
+    /// Fetch the GitHub topics of every repository in the README
+    fn load_topics(&mut self, content: &ReadmeContent) {
+        let mut repos: Vec<String> = content
+            .sections
+            .iter()
+            .flat_map(|section| &section.entries)
+            .filter(|entry| entry.url.starts_with("https://github.com/"))
+            .filter_map(|entry| entry.owner_repo())
+            .map(|repo| repo.to_lowercase())
+            .collect();
+        repos.sort();
+        repos.dedup();
+
+        let client = self.client.clone();
+        self.tasks.spawn(TaskKind::Topics, async move {
+            AppMessage::Topics(client.fetch_topics(repos).await)
+        });
+    }
+
+    /// Check every entry URL in the background, badging the broken ones when done
+    fn check_links(&mut self) {
+        let Some(content) = &self.readme_content else {
+            return;
+        };
+        if self.tasks.is_running(TaskKind::LinkCheck) {
+            self.status_message = Some("Link check already running".to_string());
+            return;
+        }
+        let urls: Vec<String> = content
+            .sections
+            .iter()
+            .flat_map(|section| &section.entries)
+            .map(|entry| entry.url.clone())
+            .collect();
+        self.status_message = Some(format!("Checking {} links…", urls.len()));
+
+        let client = self.client.clone();
+        self.tasks.spawn(TaskKind::LinkCheck, async move {
+            AppMessage::LinkCheck(Checker::new(client).check_all(urls).await)
+        });
+    }
+
+    /// Tag every entry again, e.g. once GitHub topics arrive
+    fn retag_entries(&mut self) {
+        if let Some(content) = self.readme_content.as_mut() {
+            tags::tag_entries(content, &self.taxonomy, &self.topics);
+            self.tag_browser.counts = tags::tag_counts(content);
+            self.tag_counts_changed();
+        }
+    }
+
+    /// Keep the tag selection in range and drop an active tag nothing carries anymore
+    fn tag_counts_changed(&mut self) {
+        let counts = &self.tag_browser.counts;
+        self.tag_browser.selected = self
+            .tag_browser
+            .selected
+            .min(counts.len().saturating_sub(1));
+        if let Some(active) = &self.tag_browser.active
+            && !counts.iter().any(|(tag, _)| tag == active)
+        {
+            self.tag_browser.active = None;
+        }
+    }
+
+    /// Cancel an in-flight README fetch; returns whether anything was cancelled
+    fn cancel_readme_load(&mut self) -> bool {
+        if !self.tasks.cancel(TaskKind::Readme) {
+            return false;
+        }
+
+        if self.readme_content.is_none() {
+            self.state = AppState::Error("Loading cancelled".to_string());
+        }
+        true
+    }
+
+    /// Dispatch an event; returns whether the UI needs to be redrawn
+    pub async fn handle_event(&mut self, event: AppEvent) -> Result<bool> {
+        match event {
+            AppEvent::Tick => Ok(self.on_tick()),
+            AppEvent::Key(key) if key.kind != KeyEventKind::Release => {
+                self.handle_key_event(key).await?;
+                Ok(true)
+            }
+            AppEvent::Key(_) => Ok(false),
+            AppEvent::Mouse(mouse) => Ok(self.handle_mouse_event(mouse)),
+            AppEvent::Resize(width, height) => {
+                self.handle_resize(width, height);
+                Ok(true)
+            }
+            AppEvent::Paste(text) => Ok(self.handle_paste(&text)),
+            // Some terminals drop the screen contents while unfocused
+            AppEvent::Focus(gained) => Ok(gained),
+            AppEvent::Message(message) => {
+                self.handle_task_message(message);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Insert pasted text into whichever search box is active
+    fn handle_paste(&mut self, text: &str) -> bool {
+        let text = text.replace(['\n', '\r'], " ");
+
+        if let Some(input) = self.active_builder().and_then(|b| b.input.as_mut()) {
+            input.text_mut().push_str(text.trim());
+            true
+        } else if self.theme_browser_mode && self.theme_browser.search_mode {
+            self.theme_browser.search_query.push_str(&text);
+            self.update_theme_search_filter();
+            true
+        } else if !self.theme_browser_mode && self.search_mode {
+            self.search_query.push_str(&text);
+            self.perform_search();
+            true
+        } else {
+            false
+        }
     }
 
     pub async fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
-        if self.theme_browser_mode {
+        self.status_message = None;
+
+        if self.yank_pending {
+            self.handle_yank_input(key);
+        } else if self.command_panel.is_some() {
+            self.handle_command_panel_input(key);
+        } else if self.builder.is_some() || self.theme_browser.palette_editor.is_some() {
+            self.handle_builder_input(key);
+        } else if self.theme_browser_mode {
             self.handle_theme_browser_input(key).await?;
         } else if self.search_mode {
             self.handle_search_input(key).await?;
@@ -132,33 +511,21 @@ impl App {
                 self.search_selection = None;
             }
             KeyCode::Enter => {
-                // If we have a selected search result, open its GitHub URL
-                if let Some(selected_idx) = self.search_selection
-                    && let Some(result) = self.search_results.get(selected_idx)
-                    && let Some(url) = &result.github_url
-                {
-                    self.open_url(url);
-                }
-                self.search_mode = false;
-                self.search_selection = None;
+                self.open_selected_search_result();
             }
-            KeyCode::Char('j') | KeyCode::Down => {
-                // Navigate down in search results
-                if !self.search_results.is_empty() {
-                    self.search_selection = Some(match self.search_selection {
-                        Some(idx) if idx + 1 < self.search_results.len() => idx + 1,
-                        Some(_) | None => 0, // Wrap to beginning
-                    });
-                }
+            // Navigate down in search results
+            KeyCode::Char('j') | KeyCode::Down if !self.search_results.is_empty() => {
+                self.search_selection = Some(match self.search_selection {
+                    Some(idx) if idx + 1 < self.search_results.len() => idx + 1,
+                    Some(_) | None => 0, // Wrap to beginning
+                });
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                // Navigate up in search results
-                if !self.search_results.is_empty() {
-                    self.search_selection = Some(match self.search_selection {
-                        Some(idx) if idx > 0 => idx - 1,
-                        Some(_) | None => self.search_results.len() - 1, // Wrap to end
-                    });
-                }
+            // Navigate up in search results
+            KeyCode::Char('k') | KeyCode::Up if !self.search_results.is_empty() => {
+                self.search_selection = Some(match self.search_selection {
+                    Some(idx) if idx > 0 => idx - 1,
+                    Some(_) | None => self.search_results.len() - 1, // Wrap to end
+                });
             }
             KeyCode::Backspace => {
                 self.search_query.pop();
@@ -202,7 +569,7 @@ impl App {
             // j/k keys - Navigate within current focus area
             KeyCode::Char('j') | KeyCode::Down => match self.focus_area {
                 FocusArea::Sidebar => {
-                    self.next_tab();
+                    self.sidebar_next();
                 }
                 FocusArea::Content => {
                     self.list_next();
@@ -210,7 +577,7 @@ impl App {
             },
             KeyCode::Char('k') | KeyCode::Up => match self.focus_area {
                 FocusArea::Sidebar => {
-                    self.previous_tab();
+                    self.sidebar_previous();
                 }
                 FocusArea::Content => {
                     self.list_previous();
@@ -218,7 +585,7 @@ impl App {
             },
             // Reload
             KeyCode::Char('r' | 'R') => {
-                self.load_readme(true).await?;
+                self.load_readme(true);
             }
             // Open GitHub
             KeyCode::Char('g' | 'G') => {
@@ -235,9 +602,49 @@ impl App {
                 self.search_query.clear();
                 self.search_results.clear();
             }
+            // Yank - the next key picks the format
+            KeyCode::Char('y') if self.selected_entry().is_some() => {
+                self.yank_pending = true;
+            }
+            // Clone (or update) the selected repository into the workspace
+            KeyCode::Char('c')
+                if self.selected_entry().is_some()
+                    && !key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.start_clone();
+            }
             // Theme Browser
             KeyCode::Char('t' | 'T') => {
-                self.open_theme_browser().await?;
+                self.open_theme_browser();
+            }
+            // Fold and unfold subsections in the sidebar
+            KeyCode::Char(' ') if self.sidebar_view == SidebarView::Sections => {
+                self.toggle_section();
+            }
+            KeyCode::Left if self.folds_sections() => {
+                self.collapse_section();
+            }
+            KeyCode::Right if self.folds_sections() => {
+                self.expand_section();
+            }
+            // Check every entry for dead links and archived repositories
+            KeyCode::Char('C') => {
+                self.check_links();
+            }
+            // Tag browser in the sidebar
+            KeyCode::Char('#') => {
+                self.toggle_tag_browser();
+            }
+            // Entries listed more than once, in the sidebar
+            KeyCode::Char('D') => {
+                self.toggle_duplicates_view();
+            }
+            // Step through the entries carrying the active tag
+            KeyCode::Char('n') if self.tag_browser.active.is_some() => {
+                self.jump_to_tagged(true);
+            }
+            KeyCode::Char('N') if self.tag_browser.active.is_some() => {
+                self.jump_to_tagged(false);
             }
             // Legacy scroll support (for paragraph fallback)
             KeyCode::PageUp => {
@@ -255,15 +662,13 @@ impl App {
             // Enter key - Open selected repository URL (only in content area)
             KeyCode::Enter => match self.focus_area {
                 FocusArea::Content => {
-                    // Get the currently selected repository entry and open its URL
-                    if let Some(tab) = self.tabs.get(self.current_tab)
-                        && let Some(selected_idx) = tab.list_state.selected_index
-                        && let Some(ref readme) = self.readme_content
-                        && let Some(section) = readme.sections.get(tab.section_index)
-                        && let Some(entry) = section.entries.get(selected_idx)
-                    {
-                        self.open_url(&entry.url);
-                    }
+                    self.open_selected_entry();
+                }
+                FocusArea::Sidebar if self.sidebar_view == SidebarView::Tags => {
+                    self.activate_selected_tag();
+                }
+                FocusArea::Sidebar if self.sidebar_view == SidebarView::Duplicates => {
+                    self.jump_to_duplicate();
                 }
                 FocusArea::Sidebar => {
                     // No action for Enter in sidebar - could be extended in the future
@@ -276,8 +681,10 @@ impl App {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.quit = true;
             }
+            // Cancel a pending refresh first, otherwise clear search results
+            KeyCode::Esc if self.cancel_readme_load() => {}
+            KeyCode::Esc if self.tag_browser.active.take().is_some() => {}
             KeyCode::Esc => {
-                // Clear search results if any
                 self.search_results.clear();
             }
             _ => {}
@@ -285,258 +692,1266 @@ impl App {
         Ok(())
     }
 
-    pub fn next_tab(&mut self) {
-        if !self.tabs.is_empty() {
-            // Validate current index before changing selection
-            if self.current_tab < self.tabs.len() {
-                self.tabs[self.current_tab].selected = false;
-            }
-            self.current_tab = (self.current_tab + 1) % self.tabs.len();
-            self.tabs[self.current_tab].selected = true;
-        }
-    }
+    /// Handle a mouse event; returns whether the UI needs to be redrawn
+    pub fn handle_mouse_event(&mut self, mouse: MouseEvent) -> bool {
+        let (column, row) = (mouse.column, mouse.row);
 
-    pub fn previous_tab(&mut self) {
-        if !self.tabs.is_empty() {
-            // Validate current index before changing selection
-            if self.current_tab < self.tabs.len() {
-                self.tabs[self.current_tab].selected = false;
+        match mouse.kind {
+            // The command panel is keyboard driven; keep clicks from reaching the list below
+            MouseEventKind::Down(MouseButton::Left) if self.command_panel.is_some() => false,
+            // So is the builder, and the palette editor over the theme list
+            MouseEventKind::Down(MouseButton::Left) if self.active_builder().is_some() => false,
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp
+                if self.active_builder().is_some() =>
+            {
+                if let Some(builder) = self.active_builder() {
+                    match mouse.kind {
+                        MouseEventKind::ScrollDown => builder.select_next(),
+                        _ => builder.select_previous(),
+                    }
+                }
+                true
             }
-            if self.current_tab == 0 {
-                self.current_tab = self.tabs.len() - 1;
-            } else {
-                self.current_tab -= 1;
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp
+                if self.command_panel.is_some() =>
+            {
+                self.scroll_command_log(mouse.kind == MouseEventKind::ScrollUp)
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let double_click = self.register_click(row);
+                if self.theme_browser_mode {
+                    self.click_theme_browser(column, row, double_click)
+                } else if self.search_mode {
+                    self.click_search_popup(column, row, double_click)
+                } else {
+                    self.click_main_view(column, row, double_click)
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.dragging_divider => {
+                self.resize_sidebar_to(column);
+                true
             }
-            self.tabs[self.current_tab].selected = true;
+            MouseEventKind::Up(MouseButton::Left) => std::mem::take(&mut self.dragging_divider),
+            MouseEventKind::ScrollDown => self.scroll(true),
+            MouseEventKind::ScrollUp => self.scroll(false),
+            _ => false,
         }
     }
 
-    pub fn page_up(&mut self) {
-        // Keep for potential future use with large lists
-        for _ in 0..5 {
-            self.list_previous();
-        }
+    /// Record a click and report whether it completes a double-click on the same row
+    fn register_click(&mut self, row: u16) -> bool {
+        let now = Instant::now();
+        let double_click = matches!(
+            self.last_click,
+            Some((at, last_row)) if last_row == row && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+        );
+        // A double-click consumes the pair so a third click starts over
+        self.last_click = (!double_click).then_some((now, row));
+        double_click
     }
 
-    pub fn page_down(&mut self) {
-        // Keep for potential future use with large lists
-        for _ in 0..5 {
-            self.list_next();
+    fn click_main_view(&mut self, column: u16, row: u16, double_click: bool) -> bool {
+        if self.layout.is_divider(column, row) {
+            self.dragging_divider = true;
+            return true;
         }
-    }
 
-    pub fn list_next(&mut self) {
-        if self.current_tab < self.tabs.len()
-            && let Some(tab) = self.tabs.get_mut(self.current_tab)
-            && let Some(ref readme) = self.readme_content
-            && let Some(section) = readme.sections.get(tab.section_index)
-            && !section.entries.is_empty()
-        {
-            tab.list_state.select_next(section.entries.len());
+        if let Some(index) = UiLayout::hit(&self.layout.tabs, column, row) {
+            self.focus_area = FocusArea::Sidebar;
+            match self.sidebar_view {
+                SidebarView::Sections => {
+                    if let Some(&tab) = self.visible_tabs().get(index) {
+                        self.select_tab(tab);
+                        if double_click {
+                            self.toggle_section();
+                        }
+                    }
+                }
+                SidebarView::Tags => {
+                    self.tag_browser.selected = index;
+                    if double_click {
+                        self.activate_selected_tag();
+                    }
+                }
+                SidebarView::Duplicates => {
+                    if index != self.duplicates.selected {
+                        self.duplicates.selected = index;
+                        self.duplicates.member = 0;
+                    }
+                    if double_click {
+                        self.jump_to_duplicate();
+                    }
+                }
+            }
+            return true;
         }
-    }
 
-    pub fn list_previous(&mut self) {
-        if self.current_tab < self.tabs.len()
-            && let Some(tab) = self.tabs.get_mut(self.current_tab)
-            && let Some(ref readme) = self.readme_content
-            && let Some(section) = readme.sections.get(tab.section_index)
-            && !section.entries.is_empty()
-        {
-            tab.list_state.select_previous(section.entries.len());
+        if let Some(entry_index) = UiLayout::hit(&self.layout.links, column, row) {
+            self.focus_area = FocusArea::Content;
+            self.select_entry(entry_index);
+            self.open_selected_entry();
+            return true;
         }
-    }
 
-    pub fn list_first(&mut self) {
-        if self.current_tab < self.tabs.len()
-            && let Some(tab) = self.tabs.get_mut(self.current_tab)
-        {
-            tab.list_state.select_first();
-            tab.scroll_offset = 0;
+        if let Some(entry_index) = UiLayout::hit(&self.layout.entries, column, row) {
+            self.focus_area = FocusArea::Content;
+            self.select_entry(entry_index);
+            if double_click {
+                self.open_selected_entry();
+            }
+            return true;
         }
-    }
 
-    pub fn list_last(&mut self) {
-        if self.current_tab < self.tabs.len()
-            && let Some(tab) = self.tabs.get_mut(self.current_tab)
-            && let Some(ref readme) = self.readme_content
-            && let Some(section) = readme.sections.get(tab.section_index)
-            && !section.entries.is_empty()
-        {
-            tab.list_state.select(Some(section.entries.len() - 1));
+        // Clicking empty space in a pane still moves focus there
+        let position = Position::new(column, row);
+        if self.layout.sidebar.contains(position) {
+            self.focus_area = FocusArea::Sidebar;
+            true
+        } else if self.layout.content.contains(position) {
+            self.focus_area = FocusArea::Content;
+            true
+        } else {
+            false
         }
     }
 
-    pub fn perform_search(&mut self) {
-        let Some(ref readme) = self.readme_content else {
-            return;
-        };
+    fn click_search_popup(&mut self, column: u16, row: u16, double_click: bool) -> bool {
+        let inside_popup = self
+            .layout
+            .popup
+            .is_some_and(|popup| popup.contains(Position::new(column, row)));
 
-        if self.search_query.trim().is_empty() {
+        if !inside_popup {
+            // Clicking outside dismisses the popup like Esc
+            self.search_mode = false;
+            self.search_query.clear();
             self.search_results.clear();
             self.search_selection = None;
-        } else {
-            self.search_results = readme.search_index.search(&self.search_query);
-            self.search_selection = (!self.search_results.is_empty()).then_some(0);
+        } else if let Some(result_index) = UiLayout::hit(&self.layout.popup_items, column, row) {
+            self.search_selection = Some(result_index);
+            if double_click {
+                self.open_selected_search_result();
+            }
         }
+        true
     }
 
-    pub fn open_github_repo(&self) {
-        let repo_url = "https://github.com/aorumbayev/awesome-omarchy";
-        self.open_url(repo_url);
+    fn click_theme_browser(&mut self, column: u16, row: u16, double_click: bool) -> bool {
+        let Some(list_index) = UiLayout::hit(&self.layout.popup_items, column, row) else {
+            return false;
+        };
+
+        let before = self.selected_theme_entry().map(|entry| entry.name.clone());
+        if self.theme_browser.search_mode && !self.theme_browser.filtered_themes.is_empty() {
+            self.theme_browser.filtered_selected = Some(list_index);
+        } else if let Some(&theme_index) = self.visible_theme_indices().get(list_index) {
+            self.theme_browser.selected_index = Some(theme_index);
+        }
+        if self.selected_theme_entry().map(|entry| &entry.name) != before.as_ref() {
+            self.theme_selection_changed();
+        }
+        if double_click {
+            self.theme_browser_apply_selected();
+        }
+        true
     }
 
-    pub fn open_url(&self, url: &str) {
-        // Use system command to open URL in default browser
-        let _ = std::process::Command::new("xdg-open")
-            .arg(url)
-            .spawn()
-            .or_else(|_| {
-                // Fallback for macOS
-                std::process::Command::new("open").arg(url).spawn()
-            })
-            .or_else(|_| {
-                // Fallback for Windows
-                std::process::Command::new("cmd")
-                    .args(["/c", "start", url])
-                    .spawn()
+    /// Scroll the open popup, or otherwise the focused pane, by one item
+    fn scroll(&mut self, down: bool) -> bool {
+        if self.theme_browser_mode {
+            match (self.theme_browser.search_mode, down) {
+                (true, true) => self.theme_search_navigate_next(),
+                (true, false) => self.theme_search_navigate_previous(),
+                (false, true) => self.theme_browser_navigate_next(),
+                (false, false) => self.theme_browser_navigate_previous(),
+            }
+        } else if self.search_mode {
+            if self.search_results.is_empty() {
+                return false;
+            }
+            let last = self.search_results.len() - 1;
+            self.search_selection = Some(match (self.search_selection, down) {
+                (Some(idx), true) => (idx + 1).min(last),
+                (Some(idx), false) => idx.saturating_sub(1),
+                (None, _) => 0,
             });
+        } else {
+            match (&self.focus_area, down) {
+                (FocusArea::Sidebar, true) => self.sidebar_next(),
+                (FocusArea::Sidebar, false) => self.sidebar_previous(),
+                (FocusArea::Content, true) => self.list_next(),
+                (FocusArea::Content, false) => self.list_previous(),
+            }
+        }
+        true
     }
 
-    pub fn handle_resize(&mut self, _width: u16, _height: u16) {
-        // Handle terminal resize if needed in the future
+    /// Move the sidebar/content divider to the given column
+    fn resize_sidebar_to(&mut self, column: u16) {
+        let start = self.layout.sidebar.x;
+        let total = self.layout.sidebar.width + self.layout.content.width;
+        if total == 0 {
+            return;
+        }
+
+        let percent = u32::from(column.saturating_sub(start)) * 100 / u32::from(total);
+        self.sidebar_width = (percent as u16).clamp(MIN_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH);
     }
 
-    pub async fn on_tick(&mut self) {
-        // Handle periodic updates if needed in the future
+    pub fn select_tab(&mut self, index: usize) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        // Unfold the way to a section selected from elsewhere, e.g. a search or tag jump
+        if let Some(readme) = &self.readme_content {
+            for ancestor in readme.ancestors(self.tabs[index].section_index) {
+                self.collapsed.remove(&ancestor);
+            }
+        }
+        if let Some(tab) = self.tabs.get_mut(self.current_tab) {
+            tab.selected = false;
+        }
+        self.current_tab = index;
+        self.tabs[index].selected = true;
     }
 
-    pub fn should_quit(&self) -> bool {
-        self.quit
+    fn select_entry(&mut self, index: usize) {
+        if let Some(tab) = self.tabs.get_mut(self.current_tab) {
+            tab.list_state.select(Some(index));
+        }
     }
 
-    pub fn current_tab(&self) -> Option<&TabState> {
-        self.tabs.get(self.current_tab)
+    /// The entry selected in the current section
+    pub fn selected_entry(&self) -> Option<&RepositoryEntry> {
+        let tab = self.tabs.get(self.current_tab)?;
+        let selected_idx = tab.list_state.selected_index?;
+        let section = self
+            .readme_content
+            .as_ref()?
+            .sections
+            .get(tab.section_index)?;
+        section.entries.get(selected_idx)
     }
 
-    pub fn get_metadata_summary(&self) -> Option<String> {
-        self.readme_content.as_ref().map(|readme| {
-            format!(
-                "{} sections | {} total entries",
-                readme.sections.len(),
-                readme.metadata.total_entries
-            )
-        })
+    /// Open the URL of the selected entry in the current section
+    fn open_selected_entry(&self) {
+        if let Some(entry) = self.selected_entry() {
+            self.open_url(&entry.url);
+        }
     }
 
-    // Theme browser methods
-    pub async fn open_theme_browser(&mut self) -> Result<()> {
-        self.theme_browser_mode = true;
-        self.theme_browser.loading = true;
-        self.theme_browser.error = None;
+    /// Clone the selected entry, or offer a pull if it is already checked out
+    fn start_clone(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        if !entry.kind.is_repository() {
+            self.status_message = Some(format!(
+                "{} ({}) is not a git repository",
+                entry.title,
+                entry.kind.label()
+            ));
+            return;
+        }
+        let (name, url) = (entry.title.clone(), workspace::clone_url(&entry.url));
 
-        // Load theme entries from README if not already loaded
-        if self.theme_entries.is_empty() {
-            match self.client.fetch_themes_from_readme().await {
-                Ok(entries) => {
-                    self.theme_entries = entries;
-                    // Convert to display format for the browser
-                    self.theme_browser.themes = self
-                        .theme_entries
-                        .iter()
-                        .map(|entry| {
-                            use crate::models::{Theme, ThemeColorPalette, ThemeColors};
-                            Theme {
-                                name: entry.name.clone(),
-                                description: entry.description.clone(),
-                                source_url: entry.url.clone(),
-                                // Placeholder colors - will be loaded lazily
-                                colors: ThemeColors {
-                                    background: "#000000".to_string(),
-                                    foreground: "#ffffff".to_string(),
-                                    normal: ThemeColorPalette {
-                                        black: "#000000".to_string(),
-                                        red: "#ff0000".to_string(),
-                                        green: "#00ff00".to_string(),
-                                        yellow: "#ffff00".to_string(),
-                                        blue: "#0000ff".to_string(),
-                                        magenta: "#ff00ff".to_string(),
-                                        cyan: "#00ffff".to_string(),
-                                        white: "#ffffff".to_string(),
-                                    },
-                                    bright: ThemeColorPalette {
-                                        black: "#666666".to_string(),
-                                        red: "#ff6666".to_string(),
-                                        green: "#66ff66".to_string(),
-                                        yellow: "#ffff66".to_string(),
-                                        blue: "#6666ff".to_string(),
-                                        magenta: "#ff66ff".to_string(),
-                                        cyan: "#66ffff".to_string(),
-                                        white: "#ffffff".to_string(),
-                                    },
-                                },
-                            }
-                        })
-                        .collect();
-
-                    self.theme_browser.selected_index = if !self.theme_browser.themes.is_empty() {
-                        Some(0)
-                    } else {
-                        None
-                    };
+        let Some(checkout) = self.workspace.checkout_path(&url) else {
+            self.status_message = Some(format!("Cannot derive a checkout directory from {url}"));
+            return;
+        };
 
-                    // Initialize filtered themes (empty = show all)
-                    self.theme_browser.filtered_themes.clear();
-                    self.theme_browser.filtered_selected = None;
-                }
-                Err(e) => {
-                    self.theme_browser.error = Some(format!("Failed to load themes: {e}"));
-                }
+        match Workspace::checkout_state(&checkout) {
+            Checkout::Missing => {
+                self.command_panel = Some(CommandPanel::new(
+                    name,
+                    url,
+                    checkout,
+                    CommandPanelState::Finished,
+                ));
+                self.run_command(GitAction::Clone);
+            }
+            Checkout::Existing => {
+                let mut panel =
+                    CommandPanel::new(name, url, checkout, CommandPanelState::ConfirmPull);
+                panel.log.push(format!(
+                    "Already checked out at {}",
+                    panel.checkout.display()
+                ));
+                self.command_panel = Some(panel);
+            }
+            Checkout::Occupied => {
+                self.status_message = Some(format!(
+                    "{} exists and is not a git checkout",
+                    checkout.display()
+                ));
+            }
+        }
+    }
+
+    /// Run a command for the open panel, streaming its output into the log
+    fn run_command(&mut self, action: GitAction) {
+        let Some(panel) = self.command_panel.as_mut() else {
+            return;
+        };
+
+        panel.state = CommandPanelState::Running(action);
+        panel.scroll = 0;
+        panel.log.push(match action {
+            GitAction::Clone => format!("$ git clone {} {}", panel.url, panel.checkout.display()),
+            GitAction::Pull => format!("$ git -C {} pull --ff-only", panel.checkout.display()),
+            GitAction::Install => format!("$ cd {} && bash install.sh", panel.checkout.display()),
+        });
+
+        let (url, checkout) = (panel.url.clone(), panel.checkout.clone());
+        self.tasks
+            .spawn_with(TaskKind::Command, move |sender| async move {
+                let result = workspace::run(action, &url, &checkout, &sender).await;
+                AppMessage::CommandFinished { action, result }
+            });
+    }
+
+    fn apply_command_result(&mut self, action: GitAction, result: Result<()>) {
+        let Some(panel) = self.command_panel.as_mut() else {
+            return;
+        };
+
+        panel.state = CommandPanelState::Finished;
+        match result {
+            Ok(()) => panel.log.push(format!("✓ {} finished", action.label())),
+            Err(e) => {
+                panel.log.push(format!("✗ {e}"));
+                return;
+            }
+        }
+
+        // Offer the install script only after a successful clone or pull, and only after review
+        if action != GitAction::Install
+            && let Some(script) = Workspace::install_script(&panel.checkout)
+        {
+            match std::fs::read_to_string(&script) {
+                Ok(contents) => {
+                    panel.log.push(format!(
+                        "{} found, review it before running",
+                        workspace::INSTALL_SCRIPT
+                    ));
+                    panel.state = CommandPanelState::ConfirmInstall(contents);
+                }
+                Err(e) => panel
+                    .log
+                    .push(format!("✗ Failed to read {}: {e}", script.display())),
+            }
+        }
+    }
+
+    fn handle_command_panel_input(&mut self, key: KeyEvent) {
+        let Some(state) = self.command_panel.as_ref().map(|panel| panel.state.clone()) else {
+            return;
+        };
+
+        match (state, key.code) {
+            (_, KeyCode::Char('j') | KeyCode::Down) => {
+                self.scroll_command_log(false);
+            }
+            (_, KeyCode::Char('k') | KeyCode::Up) => {
+                self.scroll_command_log(true);
+            }
+            (CommandPanelState::ConfirmPull, KeyCode::Char('y') | KeyCode::Enter) => {
+                self.run_command(GitAction::Pull);
+            }
+            (CommandPanelState::ConfirmInstall(_), KeyCode::Char('y')) => {
+                self.run_command(GitAction::Install);
+            }
+            (CommandPanelState::ConfirmInstall(_), KeyCode::Char('n') | KeyCode::Esc) => {
+                if let Some(panel) = self.command_panel.as_mut() {
+                    panel
+                        .log
+                        .push(format!("Skipped {}", workspace::INSTALL_SCRIPT));
+                    panel.state = CommandPanelState::Finished;
+                }
+            }
+            (CommandPanelState::Running(action), KeyCode::Esc) => {
+                self.tasks.cancel(TaskKind::Command);
+                if let Some(panel) = self.command_panel.as_mut() {
+                    panel.log.push(format!("Cancelled {}", action.label()));
+                    panel.state = CommandPanelState::Finished;
+                }
+            }
+            (
+                CommandPanelState::ConfirmPull | CommandPanelState::Finished,
+                KeyCode::Char('n' | 'q') | KeyCode::Esc | KeyCode::Enter,
+            ) => {
+                self.command_panel = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Scroll the command log; returns whether anything moved
+    fn scroll_command_log(&mut self, up: bool) -> bool {
+        let Some(panel) = self.command_panel.as_mut() else {
+            return false;
+        };
+        let previous = panel.scroll;
+        panel.scroll = if up {
+            (panel.scroll + 1).min(panel.log.len().saturating_sub(1))
+        } else {
+            panel.scroll.saturating_sub(1)
+        };
+        panel.scroll != previous
+    }
+
+    /// Finish a yank: copy the selected entry in the chosen format, or cancel
+    fn handle_yank_input(&mut self, key: KeyEvent) {
+        self.yank_pending = false;
+
+        let KeyCode::Char(c) = key.code else {
+            return;
+        };
+        let Some(format) = YankFormat::from_key(c) else {
+            return;
+        };
+        if let Some(text) = self.selected_entry().map(|entry| format.render(entry)) {
+            self.status_message = Some(match terminal::copy_to_clipboard(&text) {
+                Ok(methods) => {
+                    let via: Vec<&str> = methods.iter().map(|method| method.label()).collect();
+                    format!("Copied {} via {}: {text}", format.label(), via.join(" + "))
+                }
+                Err(e) => format!("Copy failed: {e}"),
+            });
+        }
+    }
+
+    /// Open the selected search result's GitHub URL, select it in its section and leave search mode
+    fn open_selected_search_result(&mut self) {
+        if let Some(selected_idx) = self.search_selection
+            && let Some(result) = self.search_results.get(selected_idx).cloned()
+        {
+            if let Some(tab) = self
+                .tabs
+                .iter()
+                .position(|tab| tab.section_index == result.section_index)
+            {
+                self.select_tab(tab);
+                if let Some(entry) = result.entry_index {
+                    self.select_entry(entry);
+                }
+            }
+            if let Some(url) = &result.github_url {
+                self.open_url(url);
+            }
+        }
+        self.search_mode = false;
+        self.search_selection = None;
+    }
+
+    /// Move to the next section shown in the sidebar, skipping collapsed ones
+    pub fn next_tab(&mut self) {
+        let visible = self.visible_tabs();
+        if !visible.is_empty() {
+            let position = visible.iter().position(|&tab| tab == self.current_tab);
+            let next = position.map_or(0, |position| (position + 1) % visible.len());
+            self.select_tab(visible[next]);
+        }
+    }
+
+    pub fn previous_tab(&mut self) {
+        let visible = self.visible_tabs();
+        if !visible.is_empty() {
+            let position = visible.iter().position(|&tab| tab == self.current_tab);
+            let previous = match position {
+                Some(0) | None => visible.len() - 1,
+                Some(position) => position - 1,
+            };
+            self.select_tab(visible[previous]);
+        }
+    }
+
+    /// Whether the arrow keys fold sections, only with the section list focused
+    fn folds_sections(&self) -> bool {
+        self.focus_area == FocusArea::Sidebar && self.sidebar_view == SidebarView::Sections
+    }
+
+    /// Tabs whose sections are not inside a collapsed one, in sidebar order
+    pub fn visible_tabs(&self) -> Vec<usize> {
+        let Some(readme) = &self.readme_content else {
+            return (0..self.tabs.len()).collect();
+        };
+        self.tabs
+            .iter()
+            .enumerate()
+            .filter(|(_, tab)| {
+                !readme
+                    .ancestors(tab.section_index)
+                    .iter()
+                    .any(|ancestor| self.collapsed.contains(ancestor))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Fold or unfold the selected section's subsections
+    fn toggle_section(&mut self) {
+        if let Some(tab) = self.current_tab()
+            && self
+                .readme_content
+                .as_ref()
+                .is_some_and(|readme| readme.has_children(tab.section_index))
+        {
+            let section = tab.section_index;
+            if !self.collapsed.remove(&section) {
+                self.collapsed.insert(section);
+            }
+        }
+    }
+
+    /// Collapse the selected section, or move up to its parent when there is nothing to fold
+    fn collapse_section(&mut self) {
+        let Some(section) = self.current_tab().map(|tab| tab.section_index) else {
+            return;
+        };
+        let Some(readme) = &self.readme_content else {
+            return;
+        };
+        if readme.has_children(section) && !self.collapsed.contains(&section) {
+            self.collapsed.insert(section);
+        } else if let Some(parent) = readme.sections.get(section).and_then(|s| s.parent)
+            && let Some(tab) = self.tabs.iter().position(|tab| tab.section_index == parent)
+        {
+            self.select_tab(tab);
+        }
+    }
+
+    fn expand_section(&mut self) {
+        if let Some(tab) = self.current_tab() {
+            let section = tab.section_index;
+            self.collapsed.remove(&section);
+        }
+    }
+
+    fn sidebar_next(&mut self) {
+        match self.sidebar_view {
+            SidebarView::Sections => self.next_tab(),
+            SidebarView::Tags if !self.tag_browser.counts.is_empty() => {
+                self.tag_browser.selected =
+                    (self.tag_browser.selected + 1) % self.tag_browser.counts.len();
+            }
+            SidebarView::Tags => {}
+            SidebarView::Duplicates if !self.duplicates.groups.is_empty() => {
+                self.duplicates.selected =
+                    (self.duplicates.selected + 1) % self.duplicates.groups.len();
+                self.duplicates.member = 0;
+            }
+            SidebarView::Duplicates => {}
+        }
+    }
+
+    fn sidebar_previous(&mut self) {
+        match self.sidebar_view {
+            SidebarView::Sections => self.previous_tab(),
+            SidebarView::Tags if !self.tag_browser.counts.is_empty() => {
+                self.tag_browser.selected = self
+                    .tag_browser
+                    .selected
+                    .checked_sub(1)
+                    .unwrap_or(self.tag_browser.counts.len() - 1);
+            }
+            SidebarView::Tags => {}
+            SidebarView::Duplicates if !self.duplicates.groups.is_empty() => {
+                self.duplicates.selected = self
+                    .duplicates
+                    .selected
+                    .checked_sub(1)
+                    .unwrap_or(self.duplicates.groups.len() - 1);
+                self.duplicates.member = 0;
+            }
+            SidebarView::Duplicates => {}
+        }
+    }
+
+    /// Switch the sidebar between sections and tags
+    fn toggle_tag_browser(&mut self) {
+        self.sidebar_view = match self.sidebar_view {
+            SidebarView::Tags => SidebarView::Sections,
+            _ => SidebarView::Tags,
+        };
+        self.focus_area = FocusArea::Sidebar;
+    }
+
+    /// Switch the sidebar between sections and duplicate entries
+    fn toggle_duplicates_view(&mut self) {
+        self.sidebar_view = match self.sidebar_view {
+            SidebarView::Duplicates => SidebarView::Sections,
+            _ => SidebarView::Duplicates,
+        };
+        self.focus_area = FocusArea::Sidebar;
+    }
+
+    /// Group entries listed more than once, keeping the selection in range
+    fn refresh_duplicates(&mut self) {
+        let Some(readme) = &self.readme_content else {
+            return;
+        };
+        self.duplicates.groups = duplicates::find_duplicates(readme, &self.link_status);
+        let last = self.duplicates.groups.len().saturating_sub(1);
+        if self.duplicates.selected > last {
+            self.duplicates.selected = last;
+            self.duplicates.member = 0;
+        }
+    }
+
+    /// Show the next entry of the selected duplicate group, cycling through its sections
+    fn jump_to_duplicate(&mut self) {
+        let Some(group) = self.duplicates.groups.get(self.duplicates.selected) else {
+            return;
+        };
+        let count = group.members.len();
+        let member = self.duplicates.member % count;
+        let (section, entry) = group.members[member];
+        let reason = group.reason.label();
+        self.duplicates.member = (member + 1) % count;
+
+        self.select_tab(section);
+        self.select_entry(entry);
+        self.focus_area = FocusArea::Content;
+        self.status_message = Some(format!(
+            "Duplicate {}/{count} ({reason}) - Enter in the sidebar for the next",
+            member + 1
+        ));
+    }
+
+    /// Follow the tag under the cursor to its first entry from the current position
+    fn activate_selected_tag(&mut self) {
+        let Some((tag, _)) = self.tag_browser.counts.get(self.tag_browser.selected) else {
+            return;
+        };
+        self.tag_browser.active = Some(tag.clone());
+        self.focus_area = FocusArea::Content;
+
+        let here = self.current_position();
+        let tagged = self.tagged_entries();
+        if !tagged.contains(&here) {
+            self.jump_to_tagged(true);
+        } else {
+            self.report_tag_position(&tagged);
+        }
+    }
+
+    /// Move to the next or previous entry carrying the active tag, wrapping across sections
+    fn jump_to_tagged(&mut self, forward: bool) {
+        let tagged = self.tagged_entries();
+        if tagged.is_empty() {
+            return;
+        }
+
+        let here = self.current_position();
+        let target = if forward {
+            tagged
+                .iter()
+                .find(|&&position| position > here)
+                .or(tagged.first())
+        } else {
+            tagged
+                .iter()
+                .rev()
+                .find(|&&position| position < here)
+                .or(tagged.last())
+        };
+        if let Some(&(tab, entry)) = target {
+            self.select_tab(tab);
+            self.select_entry(entry);
+        }
+        self.report_tag_position(&tagged);
+    }
+
+    fn report_tag_position(&mut self, tagged: &[(usize, usize)]) {
+        let here = self.current_position();
+        if let Some(tag) = &self.tag_browser.active
+            && let Some(index) = tagged.iter().position(|&position| position == here)
+        {
+            self.status_message = Some(format!(
+                "#{tag} {}/{} - n/N next/previous, ESC to clear",
+                index + 1,
+                tagged.len()
+            ));
+        }
+    }
+
+    /// Tab and entry index of the selection
+    fn current_position(&self) -> (usize, usize) {
+        let entry = self
+            .current_tab()
+            .and_then(|tab| tab.list_state.selected_index)
+            .unwrap_or(0);
+        (self.current_tab, entry)
+    }
+
+    /// Tab and entry index of every entry carrying the active tag, in sidebar order
+    fn tagged_entries(&self) -> Vec<(usize, usize)> {
+        let (Some(tag), Some(readme)) = (&self.tag_browser.active, &self.readme_content) else {
+            return Vec::new();
+        };
+        self.tabs
+            .iter()
+            .enumerate()
+            .filter_map(|(tab_index, tab)| {
+                let section = readme.sections.get(tab.section_index)?;
+                Some(
+                    section
+                        .entries
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, entry)| entry.tags.contains(tag))
+                        .map(move |(entry_index, _)| (tab_index, entry_index)),
+                )
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn page_up(&mut self) {
+        // Keep for potential future use with large lists
+        for _ in 0..5 {
+            self.list_previous();
+        }
+    }
+
+    pub fn page_down(&mut self) {
+        // Keep for potential future use with large lists
+        for _ in 0..5 {
+            self.list_next();
+        }
+    }
+
+    pub fn list_next(&mut self) {
+        if self.current_tab < self.tabs.len()
+            && let Some(tab) = self.tabs.get_mut(self.current_tab)
+            && let Some(ref readme) = self.readme_content
+            && let Some(section) = readme.sections.get(tab.section_index)
+        {
+            if section.entries.is_empty() {
+                // Sections without entries read as a document; drawing clamps the end
+                tab.scroll_offset = tab.scroll_offset.saturating_add(1);
+            } else {
+                tab.list_state.select_next(section.entries.len());
+            }
+        }
+    }
+
+    pub fn list_previous(&mut self) {
+        if self.current_tab < self.tabs.len()
+            && let Some(tab) = self.tabs.get_mut(self.current_tab)
+            && let Some(ref readme) = self.readme_content
+            && let Some(section) = readme.sections.get(tab.section_index)
+        {
+            if section.entries.is_empty() {
+                tab.scroll_offset = tab.scroll_offset.saturating_sub(1);
+            } else {
+                tab.list_state.select_previous(section.entries.len());
+            }
+        }
+    }
+
+    pub fn list_first(&mut self) {
+        if self.current_tab < self.tabs.len()
+            && let Some(tab) = self.tabs.get_mut(self.current_tab)
+        {
+            tab.list_state.select_first();
+            tab.scroll_offset = 0;
+        }
+    }
+
+    pub fn list_last(&mut self) {
+        if self.current_tab < self.tabs.len()
+            && let Some(tab) = self.tabs.get_mut(self.current_tab)
+            && let Some(ref readme) = self.readme_content
+            && let Some(section) = readme.sections.get(tab.section_index)
+        {
+            match section.entries.len() {
+                0 => tab.scroll_offset = usize::MAX,
+                len => tab.list_state.select(Some(len - 1)),
+            }
+        }
+    }
+
+    pub fn perform_search(&mut self) {
+        let Some(ref readme) = self.readme_content else {
+            return;
+        };
+
+        if self.search_query.trim().is_empty() {
+            self.search_results.clear();
+            self.search_selection = None;
+        } else {
+            self.search_results = readme.search_index.search(&self.search_query);
+            self.search_selection = (!self.search_results.is_empty()).then_some(0);
+        }
+    }
+
+    pub fn open_github_repo(&self) {
+        let repo_url = "https://github.com/aorumbayev/awesome-omarchy";
+        self.open_url(repo_url);
+    }
+
+    pub fn open_url(&self, url: &str) {
+        // Use system command to open URL in default browser
+        let _ = std::process::Command::new("xdg-open")
+            .arg(url)
+            .spawn()
+            .or_else(|_| {
+                // Fallback for macOS
+                std::process::Command::new("open").arg(url).spawn()
+            })
+            .or_else(|_| {
+                // Fallback for Windows
+                std::process::Command::new("cmd")
+                    .args(["/c", "start", url])
+                    .spawn()
+            });
+    }
+
+    pub fn handle_resize(&mut self, _width: u16, _height: u16) {
+        // Handle terminal resize if needed in the future
+    }
+
+    /// Advance animations; returns whether anything changed on screen
+    pub fn on_tick(&mut self) -> bool {
+        if !self.is_animating() {
+            return false;
+        }
+
+        self.spinner_frame = self.spinner_frame.wrapping_add(1);
+        true
+    }
+
+    /// Whether the UI shows something that moves and therefore needs ticks
+    pub fn is_animating(&self) -> bool {
+        // Spinners are shown only while background work is in flight
+        self.tasks.any_running()
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    pub fn current_tab(&self) -> Option<&TabState> {
+        self.tabs.get(self.current_tab)
+    }
+
+    pub fn get_metadata_summary(&self) -> Option<String> {
+        self.readme_content.as_ref().map(|readme| {
+            format!(
+                "{} sections | {} total entries",
+                readme.sections.len(),
+                readme.metadata.total_entries
+            )
+        })
+    }
+
+    // Theme browser methods
+    pub fn open_theme_browser(&mut self) {
+        self.theme_browser_mode = true;
+        self.theme_browser.error = None;
+
+        // Load theme entries from README if not already loaded
+        if self.theme_entries.is_empty() {
+            self.theme_browser.loading = true;
+            let client = self.client.clone();
+            self.tasks.spawn(TaskKind::ThemeList, async move {
+                AppMessage::ThemeList(client.fetch_themes_from_readme().await)
+            });
+        } else if self.theme_browser.selected_index.is_none()
+            && !self.theme_browser.themes.is_empty()
+        {
+            self.theme_browser.selected_index = Some(0);
+            // Initialize filtered themes (empty = show all)
+            self.theme_browser.filtered_themes.clear();
+            self.theme_browser.filtered_selected = None;
+        }
+        self.prefetch_adjacent_themes();
+    }
+
+    fn apply_theme_entries(&mut self, result: Result<Vec<ThemeEntry>>) {
+        self.theme_browser.loading = false;
+
+        match result {
+            Ok(entries) => {
+                self.theme_entries = entries;
+                // Convert to display format for the browser
+                self.theme_browser.themes = self
+                    .theme_entries
+                    .iter()
+                    .map(|entry| {
+                        use crate::models::{Theme, ThemeColorPalette, ThemeColors};
+                        Theme {
+                            name: entry.name.clone(),
+                            description: entry.description.clone(),
+                            source_url: entry.url.clone(),
+                            // Placeholder colors - will be loaded lazily
+                            colors: ThemeColors {
+                                background: "#000000".to_string(),
+                                foreground: "#ffffff".to_string(),
+                                normal: ThemeColorPalette {
+                                    black: "#000000".to_string(),
+                                    red: "#ff0000".to_string(),
+                                    green: "#00ff00".to_string(),
+                                    yellow: "#ffff00".to_string(),
+                                    blue: "#0000ff".to_string(),
+                                    magenta: "#ff00ff".to_string(),
+                                    cyan: "#00ffff".to_string(),
+                                    white: "#ffffff".to_string(),
+                                },
+                                bright: ThemeColorPalette {
+                                    black: "#666666".to_string(),
+                                    red: "#ff6666".to_string(),
+                                    green: "#66ff66".to_string(),
+                                    yellow: "#ffff66".to_string(),
+                                    blue: "#6666ff".to_string(),
+                                    magenta: "#ff66ff".to_string(),
+                                    cyan: "#66ffff".to_string(),
+                                    white: "#ffffff".to_string(),
+                                },
+                            },
+                        }
+                    })
+                    .collect();
+
+                self.theme_browser.selected_index = if !self.theme_browser.themes.is_empty() {
+                    Some(0)
+                } else {
+                    None
+                };
+
+                // Initialize filtered themes (empty = show all)
+                self.theme_browser.filtered_themes.clear();
+                self.theme_browser.filtered_selected = None;
+                self.load_cached_themes();
+            }
+            Err(e) => {
+                self.theme_browser.error = Some(format!("Failed to load themes: {e}"));
             }
-        } else if self.theme_browser.selected_index.is_none()
-            && !self.theme_browser.themes.is_empty()
-        {
-            self.theme_browser.selected_index = Some(0);
-            // Initialize filtered themes (empty = show all)
-            self.theme_browser.filtered_themes.clear();
-            self.theme_browser.filtered_selected = None;
         }
-
-        self.theme_browser.loading = false;
-        Ok(())
     }
 
     pub fn close_theme_browser(&mut self) {
+        self.tasks.cancel(TaskKind::ThemeList);
+        self.tasks.cancel(TaskKind::ThemeColors);
+        self.tasks.cancel(TaskKind::Prefetch);
+        self.tasks.cancel(TaskKind::CachedThemes);
+        self.tasks.cancel(TaskKind::Wallpaper);
+        self.theme_browser.loading = false;
         self.theme_browser_mode = false;
         self.theme_browser.preview_theme = None;
         self.theme_browser.search_mode = false;
         self.theme_browser.search_query.clear();
         self.theme_browser.filtered_themes.clear();
         self.theme_browser.filtered_selected = None;
+        self.theme_browser.pinned_theme = None;
+        self.theme_browser.compare_diff = false;
+        self.theme_browser.palette_editor = None;
         self.preview_state = PreviewState::None;
 
-        // Complete theme restoration
+        // Previews end here; fall back to the saved color scheme, if any
+        self.restore_color_scheme();
+    }
+
+    /// Pin the previewed theme for comparison, or leave compare mode
+    fn toggle_pinned_theme(&mut self) {
+        if self.theme_browser.pinned_theme.take().is_some() {
+            self.theme_browser.compare_diff = false;
+        } else if let PreviewState::Applied(theme) = &self.preview_state {
+            self.theme_browser.pinned_theme = Some(theme.clone());
+        } else {
+            self.status_message = Some("Preview a theme with Enter before pinning it".to_string());
+        }
+    }
+
+    /// Load settings and apply the saved color scheme
+    pub fn load_config(&mut self, path: PathBuf) -> Result<()> {
+        self.config = Config::load(&path)?;
+        self.config_path = Some(path);
+        self.restore_color_scheme();
+        self.load_taxonomy();
+        Ok(())
+    }
+
+    /// Use the configured tag taxonomy, or a `tags.toml` beside the config
+    fn load_taxonomy(&mut self) {
+        let path = match &self.config.tags_file {
+            Some(path) => path.clone(),
+            None => match Taxonomy::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return,
+            },
+        };
+        match Taxonomy::load(&path) {
+            Ok(taxonomy) => self.taxonomy = taxonomy,
+            Err(e) => self.status_message = Some(format!("Using built-in tags: {e:#}")),
+        }
+    }
+
+    fn restore_color_scheme(&mut self) {
+        self.theme_applicator.clear_theme();
+        if let Some(theme) = self.config.color_scheme.clone() {
+            self.theme_applicator.apply_theme(theme);
+        }
+    }
+
+    /// Keep the previewed theme as the interface's color scheme across sessions
+    fn keep_color_scheme(&mut self) {
+        if let Some(theme) = self.theme_applicator.current_theme.clone() {
+            let name = theme.name.clone();
+            self.config.color_scheme = Some(theme);
+            self.save_config(format!("Using {name} as the color scheme"));
+        }
+    }
+
+    /// Write the previewed theme in every export format
+    fn export_previewed_theme(&mut self) {
+        let Some(theme) = self.theme_browser.preview_theme.as_ref() else {
+            self.status_message =
+                Some("Preview a theme with Enter before exporting it".to_string());
+            return;
+        };
+        let Some(dir) = export::default_export_dir(theme) else {
+            self.status_message = Some("No config directory to export into".to_string());
+            return;
+        };
+
+        self.status_message = Some(match export::export_all(theme, &dir) {
+            Ok(files) => format!(
+                "Exported {} for {} formats to {}",
+                theme.name,
+                files.len(),
+                dir.display()
+            ),
+            Err(e) => format!("Export failed: {e:#}"),
+        });
+    }
+
+    /// Go back to the terminal's own colors
+    fn reset_color_scheme(&mut self) {
+        self.config.color_scheme = None;
         self.theme_applicator.clear_theme();
+        self.preview_state = PreviewState::None;
+        self.theme_browser.preview_theme = None;
+        self.save_config("Using terminal colors".to_string());
     }
 
-    async fn load_and_apply_theme(&mut self, theme_entry: &ThemeEntry) -> Result<()> {
-        // Set loading state
-        self.preview_state = PreviewState::Loading;
+    fn save_config(&mut self, success: String) {
+        let result = match &self.config_path {
+            Some(path) => self.config.save(path),
+            None => Ok(()),
+        };
+        self.status_message = Some(match result {
+            Ok(()) => success,
+            Err(e) => format!("Failed to save settings: {e}"),
+        });
+    }
+
+    /// Fetch and preview a theme once `delay` passes without the selection moving on
+    fn load_and_apply_theme(&mut self, theme_entry: ThemeEntry, delay: Duration) {
+        // Set loading state; any fetch for a previously selected theme is replaced
+        self.preview_state = PreviewState::Loading(theme_entry.name.clone());
+
+        self.load_wallpaper(&theme_entry, delay);
 
         // Load theme colors lazily
-        match self.client.fetch_theme_colors(theme_entry).await {
-            Ok(theme) => {
-                // Apply theme globally
-                self.theme_applicator.apply_theme(theme.clone());
-                self.preview_state = PreviewState::Applied(Box::new(theme.clone()));
-                self.theme_browser.preview_theme = Some(theme);
+        let client = self.client.clone();
+        self.tasks.spawn(TaskKind::ThemeColors, async move {
+            tokio::time::sleep(delay).await;
+            AppMessage::ThemeColors {
+                name: theme_entry.name.clone(),
+                result: client.fetch_theme_colors(&theme_entry).await.map(Box::new),
+            }
+        });
+    }
+
+    /// Fetch and decode a theme's wallpaper alongside its colors, unless already cached
+    fn load_wallpaper(&mut self, theme_entry: &ThemeEntry, delay: Duration) {
+        if self.wallpapers.touch(&theme_entry.name) {
+            self.tasks.cancel(TaskKind::Wallpaper);
+            return;
+        }
+
+        let client = self.client.clone();
+        let name = theme_entry.name.clone();
+        let url = theme_entry.url.clone();
+        self.tasks.spawn(TaskKind::Wallpaper, async move {
+            tokio::time::sleep(delay).await;
+            let result = async {
+                let Some((file, bytes)) = client.fetch_wallpaper(&url).await? else {
+                    return Ok(None);
+                };
+                let wallpaper =
+                    tokio::task::spawn_blocking(move || Wallpaper::decode(file, &bytes)).await??;
+                Ok(Some(Box::new(wallpaper)))
             }
-            Err(_) => {
-                self.preview_state = PreviewState::Error;
+            .await;
+            AppMessage::Wallpaper { name, result }
+        });
+    }
+
+    fn apply_theme_colors(&mut self, name: String, result: Result<Box<crate::models::Theme>>) {
+        // Ignore results for a theme that is no longer being previewed
+        if !matches!(&self.preview_state, PreviewState::Loading(loading) if *loading == name) {
+            return;
+        }
+
+        match result {
+            Ok(theme) => self.show_theme_preview(name, theme),
+            Err(e) => {
+                self.preview_state = PreviewState::Error(format!("{e:#}"));
             }
         }
+    }
 
-        Ok(())
+    /// Keep a theme's colors, with the contrast the selector filters and sorts by
+    fn theme_loaded(&mut self, name: String, theme: Box<crate::models::Theme>) {
+        match ContrastReport::for_theme(&theme) {
+            Some(report) => self.theme_browser.contrast.insert(name.clone(), report),
+            None => self.theme_browser.contrast.remove(&name),
+        };
+        self.theme_browser.loaded_themes.insert(name, theme);
+    }
+
+    fn show_theme_preview(&mut self, name: String, theme: Box<crate::models::Theme>) {
+        self.theme_loaded(name, theme.clone());
+
+        // Apply theme globally
+        self.theme_applicator.apply_theme((*theme).clone());
+        self.theme_browser.preview_theme = Some((*theme).clone());
+        self.preview_state = PreviewState::Applied(theme);
+    }
+
+    /// Read the palettes cached by earlier sessions off the UI loop, then fetch what the
+    /// cursor's neighbours still lack
+    fn load_cached_themes(&mut self) {
+        let client = self.client.clone();
+        let entries = self.theme_entries.clone();
+        self.tasks.spawn(TaskKind::CachedThemes, async move {
+            let themes = tokio::task::spawn_blocking(move || client.cached_themes(&entries))
+                .await
+                .unwrap_or_default();
+            AppMessage::CachedThemes(
+                themes
+                    .into_iter()
+                    .map(|(name, theme)| (name, Box::new(theme)))
+                    .collect(),
+            )
+        });
+    }
+
+    /// Colors fetched this session or cached on disk by an earlier one, from memory
+    fn known_theme(&self, theme_entry: &ThemeEntry) -> Option<Box<crate::models::Theme>> {
+        self.theme_browser
+            .loaded_themes
+            .get(&theme_entry.name)
+            .cloned()
+    }
+
+    /// Preview a newly selected theme straight away when its colors are known, or in
+    /// live preview once the cursor rests on it, and fetch its neighbours' meanwhile
+    fn theme_selection_changed(&mut self) {
+        if let Some(theme_entry) = self.selected_theme_entry().cloned() {
+            if let Some(theme) = self.known_theme(&theme_entry) {
+                // Whatever was loading belongs to a theme the cursor has left
+                self.tasks.cancel(TaskKind::ThemeColors);
+                if self.config.live_preview {
+                    self.load_wallpaper(&theme_entry, PREVIEW_DEBOUNCE);
+                } else if !self.wallpapers.touch(&theme_entry.name) {
+                    self.tasks.cancel(TaskKind::Wallpaper);
+                }
+                self.show_theme_preview(theme_entry.name, theme);
+            } else if self.config.live_preview {
+                // Replacing the task cancels the fetch for the theme scrolled past
+                self.load_and_apply_theme(theme_entry, PREVIEW_DEBOUNCE);
+            }
+        }
+        self.prefetch_adjacent_themes();
+    }
+
+    fn toggle_live_preview(&mut self) {
+        self.config.live_preview = !self.config.live_preview;
+        let state = if self.config.live_preview {
+            "on"
+        } else {
+            "off"
+        };
+        self.save_config(format!("Live preview {state}"));
+    }
+
+    /// Fetch the palettes of the themes just above and below the cursor
+    fn prefetch_adjacent_themes(&mut self) {
+        let (order, position) =
+            if self.theme_browser.search_mode && !self.theme_browser.filtered_themes.is_empty() {
+                (
+                    self.theme_browser.filtered_themes.clone(),
+                    self.theme_browser.filtered_selected,
+                )
+            } else {
+                let visible = self.visible_theme_indices();
+                let position = self.selected_visible_position(&visible);
+                (visible, position)
+            };
+        let Some(position) = position else {
+            return;
+        };
+
+        let neighbours: Vec<ThemeEntry> = [position.checked_sub(1), Some(position + 1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|pos| order.get(pos))
+            .filter_map(|&index| self.theme_entries.get(index))
+            .filter(|entry| self.known_theme(entry).is_none())
+            .cloned()
+            .collect();
+        if neighbours.is_empty() {
+            return;
+        }
+
+        let client = self.client.clone();
+        self.tasks.spawn(TaskKind::Prefetch, async move {
+            let results = futures::future::join_all(
+                neighbours
+                    .iter()
+                    .map(|entry| client.fetch_theme_colors(entry)),
+            )
+            .await;
+            // A theme without colors is reported when it is actually previewed
+            AppMessage::ThemesPrefetched(
+                neighbours
+                    .into_iter()
+                    .zip(results)
+                    .filter_map(|(entry, result)| Some((entry.name, Box::new(result.ok()?))))
+                    .collect(),
+            )
+        });
+    }
+
+    /// Forget the selected theme's cached colors and fetch them again
+    fn refresh_selected_theme(&mut self) {
+        let Some(theme_entry) = self.selected_theme_entry().cloned() else {
+            return;
+        };
+        self.theme_browser.loaded_themes.remove(&theme_entry.name);
+        self.theme_browser.contrast.remove(&theme_entry.name);
+        if let Err(e) = self.client.forget_theme_colors(&theme_entry) {
+            self.status_message = Some(format!("Failed to clear cached colors: {e:#}"));
+        }
+        self.load_and_apply_theme(theme_entry, Duration::ZERO);
+    }
+
+    /// Cancel an in-flight theme preview; returns whether anything was cancelled
+    fn cancel_theme_preview(&mut self) -> bool {
+        if !self.tasks.cancel(TaskKind::ThemeColors) {
+            return false;
+        }
+
+        self.preview_state = match self.theme_browser.preview_theme {
+            Some(ref theme) => PreviewState::Applied(Box::new(theme.clone())),
+            None => PreviewState::None,
+        };
+        true
     }
 
     async fn handle_theme_browser_input(&mut self, key: KeyEvent) -> Result<()> {
@@ -546,6 +1961,8 @@ impl App {
             self.handle_theme_search_input(key).await
         } else {
             match key.code {
+                // First Esc cancels a pending preview, the next one closes
+                KeyCode::Esc if self.cancel_theme_preview() => {}
                 KeyCode::Esc => {
                     self.close_theme_browser();
                 }
@@ -562,7 +1979,54 @@ impl App {
                     self.theme_browser_navigate_previous();
                 }
                 KeyCode::Enter => {
-                    self.theme_browser_apply_selected().await?;
+                    self.theme_browser_apply_selected();
+                }
+                KeyCode::Char('s') => {
+                    self.keep_color_scheme();
+                }
+                // Compare mode: pin the previewed theme, flip the split, toggle the diff table
+                KeyCode::Char('p') => {
+                    self.toggle_pinned_theme();
+                }
+                KeyCode::Char('v') if self.theme_browser.pinned_theme.is_some() => {
+                    self.theme_browser.compare_split = match self.theme_browser.compare_split {
+                        Direction::Horizontal => Direction::Vertical,
+                        Direction::Vertical => Direction::Horizontal,
+                    };
+                }
+                KeyCode::Tab if self.theme_browser.pinned_theme.is_some() => {
+                    self.theme_browser.compare_diff = !self.theme_browser.compare_diff;
+                }
+                // Accessibility: simulate color-vision deficiencies, filter and sort by contrast
+                KeyCode::Char('b') => {
+                    self.cycle_simulation();
+                }
+                KeyCode::Char('f') => {
+                    self.cycle_min_contrast();
+                }
+                KeyCode::Char('o') => {
+                    self.theme_browser.sort_by_contrast = !self.theme_browser.sort_by_contrast;
+                }
+                KeyCode::Char('d') => {
+                    self.reset_color_scheme();
+                }
+                KeyCode::Char('l') => {
+                    self.cycle_code_sample();
+                }
+                KeyCode::Char('x') => {
+                    self.export_previewed_theme();
+                }
+                KeyCode::Char('r') => {
+                    self.refresh_selected_theme();
+                }
+                KeyCode::Char('a') => {
+                    self.toggle_live_preview();
+                }
+                KeyCode::Char('n') => {
+                    self.open_theme_builder();
+                }
+                KeyCode::Char('e') => {
+                    self.open_palette_editor();
                 }
                 KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                     self.quit = true;
@@ -577,6 +2041,7 @@ impl App {
         use crossterm::event::KeyModifiers;
 
         match key.code {
+            KeyCode::Esc if self.cancel_theme_preview() => {}
             KeyCode::Esc => {
                 // Clear search and return to normal browse mode
                 self.theme_browser.search_mode = false;
@@ -592,7 +2057,7 @@ impl App {
                 self.theme_search_navigate_previous();
             }
             KeyCode::Enter => {
-                self.theme_browser_apply_selected().await?;
+                self.theme_browser_apply_selected();
             }
             KeyCode::Backspace => {
                 self.theme_browser.search_query.pop();
@@ -620,11 +2085,9 @@ impl App {
             let query = self.theme_browser.search_query.to_lowercase();
 
             self.theme_browser.filtered_themes = self
-                .theme_entries
-                .iter()
-                .enumerate()
-                .filter(|(_, entry)| entry.name.to_lowercase().contains(&query))
-                .map(|(i, _)| i)
+                .visible_theme_indices()
+                .into_iter()
+                .filter(|&i| self.theme_entries[i].name.to_lowercase().contains(&query))
                 .collect();
 
             // Set selection to first result if we have any
@@ -638,23 +2101,109 @@ impl App {
     }
 
     fn theme_browser_navigate_next(&mut self) {
-        if !self.theme_entries.is_empty() {
-            self.theme_browser.selected_index = Some(match self.theme_browser.selected_index {
-                Some(idx) if idx + 1 < self.theme_entries.len() => idx + 1,
-                Some(_) => 0, // Wrap to beginning
-                None => 0,
+        let visible = self.visible_theme_indices();
+        if !visible.is_empty() {
+            let position = self.selected_visible_position(&visible);
+            self.theme_browser.selected_index = Some(match position {
+                Some(pos) if pos + 1 < visible.len() => visible[pos + 1],
+                Some(_) | None => visible[0], // Wrap to beginning
             });
+            self.theme_selection_changed();
         }
     }
 
     fn theme_browser_navigate_previous(&mut self) {
-        if !self.theme_entries.is_empty() {
-            self.theme_browser.selected_index = Some(match self.theme_browser.selected_index {
-                Some(idx) if idx > 0 => idx - 1,
-                Some(_) => self.theme_entries.len() - 1, // Wrap to end
-                None => self.theme_entries.len() - 1,
+        let visible = self.visible_theme_indices();
+        if !visible.is_empty() {
+            let position = self.selected_visible_position(&visible);
+            self.theme_browser.selected_index = Some(match position {
+                Some(pos) if pos > 0 => visible[pos - 1],
+                Some(_) | None => visible[visible.len() - 1], // Wrap to end
             });
+            self.theme_selection_changed();
+        }
+    }
+
+    fn selected_visible_position(&self, visible: &[usize]) -> Option<usize> {
+        let selected = self.theme_browser.selected_index?;
+        visible.iter().position(|&index| index == selected)
+    }
+
+    /// Contrast of a theme whose colors have been loaded
+    pub fn theme_contrast(&self, name: &str) -> Option<&ContrastReport> {
+        self.theme_browser.contrast.get(name)
+    }
+
+    /// Theme indices in display order, after the contrast filter and sort
+    pub fn visible_theme_indices(&self) -> Vec<usize> {
+        let minimum = |index: usize| {
+            self.theme_contrast(&self.theme_entries[index].name)
+                .map(|report| report.minimum())
+        };
+
+        // Themes whose colors are not loaded yet cannot be judged, so they stay visible
+        let mut visible: Vec<(usize, Option<f64>)> = (0..self.theme_entries.len())
+            .map(|index| (index, minimum(index)))
+            .filter(
+                |&(_, ratio)| match (self.theme_browser.min_contrast, ratio) {
+                    (Some(threshold), Some(ratio)) => ratio >= threshold,
+                    _ => true,
+                },
+            )
+            .collect();
+
+        if self.theme_browser.sort_by_contrast {
+            // Highest contrast first, unknown last
+            visible.sort_by(|(_, a), (_, b)| b.unwrap_or(-1.0).total_cmp(&a.unwrap_or(-1.0)));
         }
+        visible.into_iter().map(|(index, _)| index).collect()
+    }
+
+    /// The snippet the editor preview highlights
+    pub fn current_sample(&self) -> &CodeSample {
+        let index = self.theme_browser.sample_index % self.code_samples.len();
+        &self.code_samples[index]
+    }
+
+    fn cycle_code_sample(&mut self) {
+        self.theme_browser.sample_index =
+            (self.theme_browser.sample_index + 1) % self.code_samples.len();
+    }
+
+    /// Add a file of the user's own to the editor preview and show it
+    pub fn load_code_sample(&mut self, path: &Path) -> Result<()> {
+        let sample = CodeSample::from_file(path)?;
+        self.code_samples.push(sample);
+        self.theme_browser.sample_index = self.code_samples.len() - 1;
+        Ok(())
+    }
+
+    fn cycle_simulation(&mut self) {
+        let simulation = &mut self.theme_browser.simulation;
+        *simulation = match simulation {
+            None => Some(Deficiency::ALL[0]),
+            Some(current) => Deficiency::ALL
+                .iter()
+                .position(|d| d == current)
+                .and_then(|pos| Deficiency::ALL.get(pos + 1))
+                .copied(),
+        };
+    }
+
+    fn cycle_min_contrast(&mut self) {
+        self.theme_browser.min_contrast = match self.theme_browser.min_contrast {
+            None => Some(AA_LARGE),
+            Some(ratio) if ratio < AA_TEXT => Some(AA_TEXT),
+            Some(ratio) if ratio < AAA_TEXT => Some(AAA_TEXT),
+            Some(_) => None,
+        };
+
+        // Keep the selection on a theme that is still listed
+        let visible = self.visible_theme_indices();
+        if self.selected_visible_position(&visible).is_none() {
+            self.theme_browser.selected_index = visible.first().copied();
+        }
+        self.update_theme_search_filter();
     }
 
     fn theme_search_navigate_next(&mut self) {
@@ -665,6 +2214,7 @@ impl App {
                     Some(_) => 0, // Wrap to beginning
                     None => 0,
                 });
+            self.theme_selection_changed();
         }
     }
 
@@ -676,10 +2226,18 @@ impl App {
                     Some(_) => self.theme_browser.filtered_themes.len() - 1, // Wrap to end
                     None => self.theme_browser.filtered_themes.len() - 1,
                 });
+            self.theme_selection_changed();
         }
     }
 
-    async fn theme_browser_apply_selected(&mut self) -> Result<()> {
+    fn theme_browser_apply_selected(&mut self) {
+        if let Some(theme_entry) = self.selected_theme_entry().cloned() {
+            self.load_and_apply_theme(theme_entry, Duration::ZERO);
+        }
+    }
+
+    /// The theme under the cursor, in search results or the full list
+    fn selected_theme_entry(&self) -> Option<&ThemeEntry> {
         let selected_theme_index =
             if self.theme_browser.search_mode && !self.theme_browser.filtered_themes.is_empty() {
                 // In search mode - get the actual theme index from filtered results
@@ -696,12 +2254,235 @@ impl App {
                 self.theme_browser.selected_index
             };
 
-        if let Some(theme_idx) = selected_theme_index {
-            if let Some(theme_entry) = self.theme_entries.get(theme_idx).cloned() {
-                self.load_and_apply_theme(&theme_entry).await?;
+        self.theme_entries.get(selected_theme_index?)
+    }
+
+    /// Build a new theme from the previewed one, or from an image when nothing is previewed
+    fn open_theme_builder(&mut self) {
+        let mut builder = match &self.theme_browser.preview_theme {
+            Some(theme) => ThemeBuilder::new(theme),
+            None => {
+                let mut builder = ThemeBuilder::new(&ThemeApplicator::create_default_theme());
+                builder.input = Some(BuilderInput::ImagePath(String::new()));
+                builder
             }
+        };
+        builder.theme.source_url.clear();
+        self.theme_applicator.apply_theme(builder.theme.clone());
+        self.builder = Some(builder);
+    }
+
+    fn close_theme_builder(&mut self) {
+        self.tasks.cancel(TaskKind::ImagePalette);
+        self.builder = None;
+
+        // Back in the theme browser, its preview colors the interface again
+        match self.theme_browser.preview_theme.clone() {
+            Some(theme) => self.theme_applicator.apply_theme(theme),
+            None => self.restore_color_scheme(),
         }
-        Ok(())
+    }
+
+    /// Edit the previewed palette in place, as a new theme based on it
+    fn open_palette_editor(&mut self) {
+        let PreviewState::Applied(theme) = &self.preview_state else {
+            self.status_message = Some("Preview a theme with Enter before editing it".to_string());
+            return;
+        };
+        self.theme_browser.palette_editor = Some(ThemeBuilder::new(theme));
+        self.preview_built_theme();
+    }
+
+    /// The builder screen, or else the palette editor in the theme preview
+    fn active_builder(&mut self) -> Option<&mut ThemeBuilder> {
+        match self.builder {
+            Some(ref mut builder) => Some(builder),
+            None => self.theme_browser.palette_editor.as_mut(),
+        }
+    }
+
+    /// Show the theme being built across the whole interface, and in the
+    /// theme browser's preview panels when editing there
+    fn preview_built_theme(&mut self) {
+        if let Some(builder) = &self.builder {
+            self.theme_applicator.apply_theme(builder.theme.clone());
+        } else if let Some(editor) = &self.theme_browser.palette_editor {
+            let theme = editor.theme.clone();
+            self.theme_applicator.apply_theme(theme.clone());
+            self.theme_browser.preview_theme = Some(theme.clone());
+            self.preview_state = PreviewState::Applied(Box::new(theme));
+        }
+    }
+
+    fn handle_builder_input(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyModifiers;
+
+        let builder_screen = self.builder.is_some();
+        let Some(builder) = self.active_builder() else {
+            return;
+        };
+
+        if let Some(input) = builder.input.as_mut() {
+            match key.code {
+                KeyCode::Esc => builder.input = None,
+                KeyCode::Enter => {
+                    if let Some(input) = builder.input.take() {
+                        self.submit_builder_input(input);
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.text_mut().pop();
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.quit = true;
+                }
+                KeyCode::Char(c) => input.text_mut().push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc if builder_screen => self.close_theme_builder(),
+            // Edits stay previewed, so `s` can still keep them as the color scheme
+            KeyCode::Esc => self.theme_browser.palette_editor = None,
+            KeyCode::Char('j') | KeyCode::Down => builder.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => builder.select_previous(),
+            // Sliders: small steps, big steps with shift
+            KeyCode::Char('h') | KeyCode::Left => builder.adjust(-1.0),
+            KeyCode::Char('l') | KeyCode::Right => builder.adjust(1.0),
+            KeyCode::Char('H') => builder.adjust(-10.0),
+            KeyCode::Char('L') => builder.adjust(10.0),
+            KeyCode::Tab => builder.next_channel(),
+            KeyCode::Char('m') => builder.toggle_channels(),
+            KeyCode::Char('u') => {
+                builder.undo();
+            }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                builder.redo();
+            }
+            KeyCode::Char('U') => {
+                builder.redo();
+            }
+            KeyCode::Enter | KeyCode::Char('e') => {
+                let hex = builder.selected_slot().1.to_string();
+                builder.input = Some(BuilderInput::Hex(hex));
+            }
+            // Typing a color straight away starts from a blank prompt
+            KeyCode::Char('#') => builder.input = Some(BuilderInput::Hex("#".to_string())),
+            KeyCode::Char('n') => {
+                builder.input = Some(BuilderInput::Name(builder.theme.name.clone()));
+            }
+            KeyCode::Char('i') => {
+                builder.input = Some(BuilderInput::ImagePath(String::new()));
+            }
+            KeyCode::Char('w') => self.write_built_theme(),
+            KeyCode::Char('x') => self.export_built_theme(),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.quit = true;
+            }
+            _ => {}
+        }
+        self.preview_built_theme();
+    }
+
+    fn submit_builder_input(&mut self, input: BuilderInput) {
+        let Some(builder) = self.active_builder() else {
+            return;
+        };
+
+        match input {
+            BuilderInput::Hex(text) => match builder.set_selected(&text) {
+                Ok(()) => self.preview_built_theme(),
+                Err(e) => self.status_message = Some(format!("{e:#}")),
+            },
+            BuilderInput::Name(text) if !text.trim().is_empty() => {
+                // The name becomes the theme's directory, which needs something to be called
+                if export::slug(&text).is_empty() {
+                    self.status_message =
+                        Some(format!("\"{}\" needs a letter or digit", text.trim()));
+                    return;
+                }
+                builder.rename(text.trim());
+                self.preview_built_theme();
+            }
+            BuilderInput::Name(_) => {}
+            BuilderInput::ImagePath(text) => {
+                let path = match text.trim().strip_prefix("~/") {
+                    Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+                    None => PathBuf::from(text.trim()),
+                };
+                self.tasks.spawn(TaskKind::ImagePalette, async move {
+                    let result =
+                        tokio::task::spawn_blocking(move || builder::theme_from_image(&path))
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|result| result)
+                            .map(Box::new);
+                    AppMessage::ImagePalette(result)
+                });
+            }
+        }
+    }
+
+    fn apply_image_palette(&mut self, result: Result<Box<crate::models::Theme>>) {
+        let Some(builder) = self.active_builder() else {
+            return;
+        };
+
+        match result {
+            Ok(theme) => {
+                builder.replace(*theme);
+                self.preview_built_theme();
+            }
+            Err(e) => self.status_message = Some(format!("{e:#}")),
+        }
+    }
+
+    /// Save the built theme as a new local theme in Omarchy's themes directory
+    fn write_built_theme(&mut self) {
+        let Some(builder) = self.active_builder() else {
+            return;
+        };
+        let theme = builder.theme.clone();
+        let dir = match omarchy::theme_dir(&theme.name) {
+            Ok(dir) => dir,
+            Err(e) => {
+                self.status_message = Some(format!("{e:#}"));
+                return;
+            }
+        };
+
+        self.status_message = Some(match omarchy::write_theme(&theme, &dir) {
+            Ok(files) => format!(
+                "Wrote {} files for {} to {}",
+                files.len(),
+                theme.name,
+                dir.display()
+            ),
+            Err(e) => format!("Writing the theme failed: {e:#}"),
+        });
+    }
+
+    /// Export the built theme's palette as alacritty.toml
+    fn export_built_theme(&mut self) {
+        let Some(builder) = self.active_builder() else {
+            return;
+        };
+        let theme = builder.theme.clone();
+        let Some(dir) = export::default_export_dir(&theme) else {
+            self.status_message = Some("No config directory to export into".to_string());
+            return;
+        };
+
+        let format = export::ExportFormat::Alacritty;
+        let path = dir.join(format.file_name());
+        let result = std::fs::create_dir_all(&dir)
+            .and_then(|()| std::fs::write(&path, export::export(&theme, format)));
+        self.status_message = Some(match result {
+            Ok(()) => format!("Exported {} to {}", theme.name, path.display()),
+            Err(e) => format!("Export failed: {e:#}"),
+        });
     }
 
     /// Check if a theme is currently applied
@@ -709,3 +2490,114 @@ impl App {
         self.theme_applicator.is_applied
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Section;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn tools(names: &[&str]) -> ReadmeContent {
+        let mut section = Section::new("Tools".to_string());
+        for name in names {
+            let url = format!("https://github.com/user/{name}");
+            section
+                .entries
+                .push(RepositoryEntry::sample(name, &url, ""));
+        }
+        section.entry_count = section.entries.len();
+        ReadmeContent {
+            sections: vec![section],
+            ..ReadmeContent::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_c_quits_with_an_entry_selected() {
+        let mut app = App::with_readme(tools(&["tool"]));
+        assert!(app.selected_entry().is_some());
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+            .await
+            .unwrap();
+        assert!(app.quit);
+        assert!(app.command_panel.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sidebar_folds_subsections() {
+        // Tools › Bars › Widgets, then Guides, after `shift` sections of their own
+        let outline = |shift: usize| {
+            let mut readme = tools(&["one", "two", "three"]);
+            let mut sections: Vec<Section> = (0..shift)
+                .map(|n| Section::new(format!("Intro {n}")))
+                .collect();
+            sections.append(&mut readme.sections);
+            for (title, parent) in [("Bars", Some(0)), ("Widgets", Some(1)), ("Guides", None)] {
+                let mut section = Section::new(title.to_string());
+                section.parent = parent.map(|index| index + shift);
+                sections.push(section);
+            }
+            readme.sections = sections;
+            readme
+        };
+        let mut app = App::with_readme(outline(0));
+        assert_eq!(app.visible_tabs(), [0, 1, 2, 3]);
+
+        // Folding the top section hides both levels beneath it
+        for code in [KeyCode::Char('h'), KeyCode::Char(' '), KeyCode::Char('j')] {
+            app.handle_key_event(key(code)).await.unwrap();
+        }
+        assert_eq!(app.visible_tabs(), [0, 3]);
+        assert_eq!(app.current_tab, 3);
+
+        // Selecting a hidden section unfolds the way to it
+        app.select_tab(2);
+        assert_eq!(app.visible_tabs(), [0, 1, 2, 3]);
+
+        // The arrows fold only while the sidebar has focus
+        app.select_tab(1);
+        app.focus_area = FocusArea::Content;
+        app.handle_key_event(key(KeyCode::Left)).await.unwrap();
+        assert_eq!(app.visible_tabs(), [0, 1, 2, 3]);
+        app.focus_area = FocusArea::Sidebar;
+        app.handle_key_event(key(KeyCode::Left)).await.unwrap();
+        assert_eq!(app.visible_tabs(), [0, 1, 3]);
+
+        // A refresh that moves sections keeps the same ones folded
+        app.handle_message(AppMessage::Readme(Ok(outline(1))));
+        assert_eq!(app.visible_tabs(), [0, 1, 2, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_prose_sections_scroll_as_documents() {
+        let steps: String = (1..=30).map(|n| format!("{n}. Step **{n}**\n")).collect();
+        // Long words wrap early, onto more rows than the line's width alone suggests
+        let prose = format!("{} ", "w".repeat(45)).repeat(10);
+        let markdown = format!(
+            "# Awesome\n\n## Install Guide\n\nRun `omarchy-update` first.\n\n\
+             ```bash\nsudo pacman -S foo\n```\n\n| Key | Action |\n|---|---|\n| j | Down |\n\n\
+             {prose}\n\n{steps}"
+        );
+        let readme = crate::parser::ReadmeParser::new().parse(&markdown).unwrap();
+        let mut app = App::with_readme(readme);
+
+        let top = crate::ui::render_to_string(&mut app);
+        assert!(top.contains("│ sudo pacman -S foo"));
+        assert!(top.contains("Key │ Action"));
+        assert!(!top.contains("30. Step 30"));
+
+        // Scrolling stops once the last line is in view
+        app.handle_key_event(key(KeyCode::End)).await.unwrap();
+        assert!(crate::ui::render_to_string(&mut app).contains("30. Step 30"));
+        let bottom = app.tabs[0].scroll_offset;
+        assert!(bottom > 0 && bottom < usize::MAX);
+
+        app.list_previous();
+        assert_eq!(app.tabs[0].scroll_offset, bottom - 1);
+    }
+}
@@ -77,21 +77,20 @@ impl BootScreen {
             let elapsed = self.start_time.elapsed();
 
             // Check for user input to skip
-            if poll(Duration::from_millis(0))? {
-                if let Event::Key(KeyEvent {
+            if poll(Duration::from_millis(0))?
+                && let Event::Key(KeyEvent {
                     code, modifiers, ..
                 }) = read()?
-                {
-                    match code {
-                        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
-                            ratatui::restore();
-                            return Ok(false); // Exit application
-                        }
-                        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ') => {
-                            break; // Skip boot screen
-                        }
-                        _ => {}
+            {
+                match code {
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        ratatui::restore();
+                        return Ok(false); // Exit application
+                    }
+                    KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ') => {
+                        break; // Skip boot screen
                     }
+                    _ => {}
                 }
             }
 
@@ -128,7 +127,7 @@ impl BootScreen {
         };
 
         // Update loading dots animation (faster)
-        if elapsed.as_millis() % 200 == 0 {
+        if elapsed.as_millis().is_multiple_of(200) {
             self.show_loading_dots = (self.show_loading_dots + 1) % 4;
         }
     }
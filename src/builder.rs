@@ -0,0 +1,717 @@
+use crate::color::Rgb;
+use crate::models::{Theme, ThemeColorPalette, ThemeColors};
+use crate::wallpaper::Wallpaper;
+use anyhow::{Context, Result, anyhow};
+use image::RgbImage;
+use std::fs;
+use std::path::Path;
+
+/// Colors median cut splits an image into before slots are picked from them
+const DOMINANT_COLORS: usize = 16;
+
+/// How far from an ANSI hue an image color may be and still fill that slot
+const HUE_TOLERANCE: f64 = 40.0;
+
+/// Hues of red, green, yellow, blue, magenta and cyan, in palette order
+const ACCENT_HUES: [f64; 6] = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0];
+
+/// Edits kept for undo; older ones are dropped
+const UNDO_LIMIT: usize = 100;
+
+/// A slider of the color picker, in HSL or RGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Hue,
+    Saturation,
+    Lightness,
+    Red,
+    Green,
+    Blue,
+}
+
+impl Channel {
+    pub const HSL: [Channel; 3] = [Channel::Hue, Channel::Saturation, Channel::Lightness];
+    pub const RGB: [Channel; 3] = [Channel::Red, Channel::Green, Channel::Blue];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Channel::Hue => "H",
+            Channel::Saturation => "S",
+            Channel::Lightness => "L",
+            Channel::Red => "R",
+            Channel::Green => "G",
+            Channel::Blue => "B",
+        }
+    }
+
+    /// The other sliders this one is shown with
+    pub fn group(self) -> [Channel; 3] {
+        match self {
+            Channel::Hue | Channel::Saturation | Channel::Lightness => Self::HSL,
+            Channel::Red | Channel::Green | Channel::Blue => Self::RGB,
+        }
+    }
+
+    /// Largest value: degrees for hue, percent for saturation and lightness
+    pub fn max(self) -> f64 {
+        match self {
+            Channel::Hue => 360.0,
+            Channel::Saturation | Channel::Lightness => 100.0,
+            Channel::Red | Channel::Green | Channel::Blue => 255.0,
+        }
+    }
+
+    pub fn value(self, color: Rgb) -> f64 {
+        let [hue, saturation, lightness] = color.to_hsl();
+        match self {
+            Channel::Hue => hue,
+            Channel::Saturation => saturation * 100.0,
+            Channel::Lightness => lightness * 100.0,
+            Channel::Red => f64::from(color.r),
+            Channel::Green => f64::from(color.g),
+            Channel::Blue => f64::from(color.b),
+        }
+    }
+
+    /// `color` with this channel set to `value`; hue wraps, everything else is clamped
+    pub fn with_value(self, color: Rgb, value: f64) -> Rgb {
+        let [hue, saturation, lightness] = color.to_hsl();
+        let byte = || value.round().clamp(0.0, 255.0) as u8;
+        match self {
+            Channel::Hue => Rgb::from_hsl([value.rem_euclid(360.0), saturation, lightness]),
+            Channel::Saturation => Rgb::from_hsl([hue, value / 100.0, lightness]),
+            Channel::Lightness => Rgb::from_hsl([hue, saturation, value / 100.0]),
+            Channel::Red => Rgb::new(byte(), color.g, color.b),
+            Channel::Green => Rgb::new(color.r, byte(), color.b),
+            Channel::Blue => Rgb::new(color.r, color.g, byte()),
+        }
+    }
+}
+
+/// What the builder's prompt is collecting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuilderInput {
+    /// A new color for the selected slot
+    Hex(String),
+    Name(String),
+    /// A local image to take the palette from
+    ImagePath(String),
+}
+
+impl BuilderInput {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BuilderInput::Hex(_) => "Hex color",
+            BuilderInput::Name(_) => "Theme name",
+            BuilderInput::ImagePath(_) => "Image path",
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            BuilderInput::Hex(text) | BuilderInput::Name(text) | BuilderInput::ImagePath(text) => {
+                text
+            }
+        }
+    }
+
+    pub fn text_mut(&mut self) -> &mut String {
+        match self {
+            BuilderInput::Hex(text) | BuilderInput::Name(text) | BuilderInput::ImagePath(text) => {
+                text
+            }
+        }
+    }
+}
+
+/// A theme being put together slot by slot
+#[derive(Debug, Clone)]
+pub struct ThemeBuilder {
+    pub theme: Theme,
+    /// Position of the edited slot in `ThemeColors::slots`
+    pub selected: usize,
+    pub input: Option<BuilderInput>,
+    /// The slider the arrow keys move
+    pub channel: Channel,
+    undo: Vec<Theme>,
+    redo: Vec<Theme>,
+    /// Slot and slider of the last adjustment; holding a key makes one undo step
+    last_adjusted: Option<(usize, Channel)>,
+}
+
+impl ThemeBuilder {
+    /// Start from a copy of `base`, renamed so writing it never replaces the original
+    pub fn new(base: &Theme) -> Self {
+        Self {
+            theme: Theme {
+                name: format!("{} Custom", base.name),
+                description: format!("Made from {}", base.name),
+                source_url: String::new(),
+                colors: base.colors.clone(),
+            },
+            selected: 0,
+            input: None,
+            channel: Channel::Hue,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_adjusted: None,
+        }
+    }
+
+    /// Name and color of the slot being edited
+    pub fn selected_slot(&self) -> (&'static str, &str) {
+        self.theme.colors.slots()[self.selected]
+    }
+
+    /// The selected slot's color, black when it is not plain hex
+    pub fn selected_color(&self) -> Rgb {
+        Rgb::from_hex(self.selected_slot().1).unwrap_or(Rgb::new(0, 0, 0))
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % ThemeColors::SLOT_COUNT;
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(ThemeColors::SLOT_COUNT - 1);
+    }
+
+    /// Move to the next slider of the same kind
+    pub fn next_channel(&mut self) {
+        let group = self.channel.group();
+        let position = group.iter().position(|&c| c == self.channel).unwrap_or(0);
+        self.channel = group[(position + 1) % group.len()];
+    }
+
+    /// Swap HSL and RGB sliders, keeping the slider position
+    pub fn toggle_channels(&mut self) {
+        let position = self
+            .channel
+            .group()
+            .iter()
+            .position(|&c| c == self.channel)
+            .unwrap_or(0);
+        self.channel = match self.channel.group() {
+            group if group == Channel::HSL => Channel::RGB[position],
+            _ => Channel::HSL[position],
+        };
+    }
+
+    /// Nudge the selected slot along the current slider.
+    ///
+    /// Rounding to 8-bit channels can swallow a small step, so the step grows until
+    /// the color actually changes or the slider hits its end.
+    pub fn adjust(&mut self, step: f64) {
+        let color = self.selected_color();
+        let value = self.channel.value(color);
+        let Some(changed) = (1..=20)
+            .map(|scale| {
+                self.channel
+                    .with_value(color, value + step * f64::from(scale))
+            })
+            .find(|&candidate| candidate != color)
+        else {
+            return;
+        };
+
+        let edit = (self.selected, self.channel);
+        if self.last_adjusted != Some(edit) {
+            self.checkpoint();
+        }
+        self.last_adjusted = Some(edit);
+        self.redo.clear();
+        if let Some(slot) = self.theme.colors.slot_mut(self.selected) {
+            *slot = changed.to_hex();
+        }
+    }
+
+    /// Set the selected slot from `#rrggbb` or `rrggbb`
+    pub fn set_selected(&mut self, hex: &str) -> Result<()> {
+        let color = Rgb::from_hex(hex).ok_or_else(|| anyhow!("{hex} is not a #rrggbb color"))?;
+        self.record();
+        if let Some(slot) = self.theme.colors.slot_mut(self.selected) {
+            *slot = color.to_hex();
+        }
+        Ok(())
+    }
+
+    /// Replace the whole theme, e.g. with one read from an image, as one undo step
+    pub fn replace(&mut self, theme: Theme) {
+        self.record();
+        self.theme = theme;
+    }
+
+    pub fn rename(&mut self, name: &str) {
+        self.record();
+        self.theme.name = name.to_string();
+    }
+
+    /// Whether anything has been changed that could be undone
+    pub fn is_modified(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Step back one edit; returns whether there was one
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(&mut self.theme, previous));
+        self.last_adjusted = None;
+        true
+    }
+
+    /// Reapply an undone edit; returns whether there was one
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(&mut self.theme, next));
+        self.last_adjusted = None;
+        true
+    }
+
+    /// Remember the current theme before a discrete edit
+    fn record(&mut self) {
+        self.checkpoint();
+        self.last_adjusted = None;
+        self.redo.clear();
+    }
+
+    fn checkpoint(&mut self) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(self.theme.clone());
+    }
+}
+
+/// A theme from the dominant colors of a PNG or JPEG, named after the file
+pub fn theme_from_image(path: &Path) -> Result<Theme> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Image".to_string());
+    let wallpaper = Wallpaper::decode(name.clone(), &bytes)?;
+
+    let colors = dominant_colors(&wallpaper.image, DOMINANT_COLORS);
+    let colors = palette_from_colors(&colors)
+        .ok_or_else(|| anyhow!("No colors found in {}", path.display()))?;
+    Ok(Theme {
+        description: format!("Colors from {}", path.display()),
+        name,
+        source_url: String::new(),
+        colors,
+    })
+}
+
+/// Median cut: split the pixels along their widest channel until there are `count`
+/// boxes, then average each. Most common colors come first, with their pixel counts.
+pub fn dominant_colors(image: &RgbImage, count: usize) -> Vec<(Rgb, usize)> {
+    let pixels: Vec<[u8; 3]> = image.pixels().map(|pixel| pixel.0).collect();
+    if pixels.is_empty() {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .map(|(index, pixels)| {
+                let (channel, range) = widest_channel(pixels);
+                (index, channel, range)
+            })
+            .filter(|&(_, _, range)| range > 0)
+            .max_by_key(|&(_, _, range)| range);
+        let Some((index, channel, _)) = widest else {
+            break;
+        };
+
+        let mut lower = boxes.swap_remove(index);
+        lower.sort_unstable_by_key(|pixel| pixel[channel]);
+        let upper = lower.split_off(lower.len() / 2);
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+
+    let mut colors: Vec<(Rgb, usize)> = boxes
+        .iter()
+        .map(|pixels| (average(pixels), pixels.len()))
+        .collect();
+    colors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.to_hex().cmp(&b.0.to_hex())));
+    colors
+}
+
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let values = pixels.iter().map(|pixel| pixel[channel]);
+            let (min, max) = values.fold((u8::MAX, u8::MIN), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+            (channel, max.saturating_sub(min))
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or_default()
+}
+
+fn average(pixels: &[[u8; 3]]) -> Rgb {
+    let mut sums = [0usize; 3];
+    for pixel in pixels {
+        for (sum, value) in sums.iter_mut().zip(pixel) {
+            *sum += usize::from(*value);
+        }
+    }
+    let channel = |sum: usize| (sum / pixels.len().max(1)) as u8;
+    Rgb::new(channel(sums[0]), channel(sums[1]), channel(sums[2]))
+}
+
+fn hue_distance(a: f64, b: f64) -> f64 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+/// Turn an image's dominant colors into a dark terminal palette.
+///
+/// The darkest and lightest colors become background and foreground. Each accent
+/// takes the closest image color within `HUE_TOLERANCE` of its hue, or that hue at
+/// the image's typical saturation, with lightness evened out so all stay readable.
+pub fn palette_from_colors(colors: &[(Rgb, usize)]) -> Option<ThemeColors> {
+    let by_luminance = |a: &&(Rgb, usize), b: &&(Rgb, usize)| {
+        a.0.relative_luminance()
+            .total_cmp(&b.0.relative_luminance())
+    };
+    let darkest = colors.iter().min_by(by_luminance)?.0;
+    let lightest = colors.iter().max_by(by_luminance)?.0;
+
+    let [hue, saturation, lightness] = darkest.to_hsl();
+    let background = Rgb::from_hsl([hue, saturation.min(0.4), lightness.min(0.12)]);
+    let [hue, saturation, lightness] = lightest.to_hsl();
+    let foreground = Rgb::from_hsl([hue, saturation.min(0.3), lightness.max(0.82)]);
+
+    let vivid: Vec<[f64; 3]> = colors
+        .iter()
+        .map(|(color, _)| color.to_hsl())
+        .filter(|[_, saturation, lightness]| *saturation > 0.2 && (0.15..0.9).contains(lightness))
+        .collect();
+    let typical_saturation = if vivid.is_empty() {
+        0.5
+    } else {
+        vivid
+            .iter()
+            .map(|[_, saturation, _]| saturation)
+            .sum::<f64>()
+            / vivid.len() as f64
+    };
+
+    let accent = |target: f64| {
+        let [hue, saturation, lightness] = vivid
+            .iter()
+            .filter(|[hue, ..]| hue_distance(*hue, target) <= HUE_TOLERANCE)
+            .min_by(|a, b| hue_distance(a[0], target).total_cmp(&hue_distance(b[0], target)))
+            .copied()
+            .unwrap_or([target, typical_saturation, 0.6]);
+        let (saturation, lightness) = (saturation.max(0.35), lightness.clamp(0.5, 0.7));
+        (
+            Rgb::from_hsl([hue, saturation, lightness]).to_hex(),
+            Rgb::from_hsl([hue, saturation, (lightness + 0.1).min(0.8)]).to_hex(),
+        )
+    };
+    let [red, green, yellow, blue, magenta, cyan] = ACCENT_HUES.map(accent);
+
+    let palette =
+        |black: Rgb, white: Rgb, pick: fn(&(String, String)) -> &String| ThemeColorPalette {
+            black: black.to_hex(),
+            red: pick(&red).clone(),
+            green: pick(&green).clone(),
+            yellow: pick(&yellow).clone(),
+            blue: pick(&blue).clone(),
+            magenta: pick(&magenta).clone(),
+            cyan: pick(&cyan).clone(),
+            white: white.to_hex(),
+        };
+
+    Some(ThemeColors {
+        background: background.to_hex(),
+        foreground: foreground.to_hex(),
+        normal: palette(
+            background.mix(foreground, 0.12),
+            foreground.mix(background, 0.15),
+            |(normal, _)| normal,
+        ),
+        bright: palette(
+            background.mix(foreground, 0.35),
+            foreground,
+            |(_, bright)| bright,
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ThemeApplicator;
+
+    fn fixture() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/quadrants.png")
+    }
+
+    #[test]
+    fn test_dominant_colors_find_each_quadrant() {
+        let image = image::open(fixture()).unwrap().to_rgb8();
+        let colors = dominant_colors(&image, DOMINANT_COLORS);
+
+        assert_eq!(colors.len(), 4);
+        assert!(colors.iter().all(|&(_, count)| count == 8));
+        let hexes: Vec<String> = colors.iter().map(|(color, _)| color.to_hex()).collect();
+        assert!(hexes.contains(&"#ff0000".to_string()));
+        assert!(hexes.contains(&"#ffffff".to_string()));
+    }
+
+    #[test]
+    fn test_theme_from_image_maps_hues_to_slots() {
+        let theme = theme_from_image(&fixture()).unwrap();
+        assert_eq!(theme.name, "quadrants");
+
+        let colors = &theme.colors;
+        let hsl = |hex: &str| Rgb::from_hex(hex).unwrap().to_hsl();
+        assert!(hsl(&colors.background)[2] <= 0.12);
+        assert!(hsl(&colors.foreground)[2] >= 0.82);
+
+        // Red, green and blue come from the image; the rest are filled in at the same hues
+        assert!(hue_distance(hsl(&colors.normal.red)[0], 0.0) < 1.0);
+        assert!(hue_distance(hsl(&colors.normal.blue)[0], 240.0) < 1.0);
+        assert!(hue_distance(hsl(&colors.normal.yellow)[0], 60.0) < 1.0);
+        for hex in [&colors.normal.red, &colors.bright.cyan] {
+            let [_, _, lightness] = hsl(hex);
+            assert!((0.5..=0.81).contains(&lightness), "{hex}");
+        }
+
+        assert!(theme_from_image(Path::new("/nonexistent.png")).is_err());
+    }
+
+    #[test]
+    fn test_builder_edits_each_slot() {
+        let mut builder = ThemeBuilder::new(&ThemeApplicator::create_default_theme());
+        assert_eq!(builder.theme.name, "Default Custom");
+
+        builder.select_previous();
+        assert_eq!(builder.selected_slot().0, "bright white");
+        builder.set_selected("ABCDEF").unwrap();
+        assert_eq!(builder.theme.colors.bright.white, "#abcdef");
+
+        builder.select_next();
+        builder.select_next();
+        builder.select_next();
+        builder.set_selected("#123456").unwrap();
+        assert_eq!(builder.theme.colors.normal.black, "#123456");
+        assert!(builder.set_selected("#12345").is_err());
+
+        // Every slot is reachable and distinct
+        let slots = ThemeColors::SLOT_COUNT;
+        for index in 0..slots {
+            builder.selected = index;
+            builder.set_selected(&format!("#0000{index:02x}")).unwrap();
+        }
+        let hexes: Vec<&str> = builder.theme.colors.slots().iter().map(|s| s.1).collect();
+        assert_eq!(hexes[2], "#000002");
+        assert_eq!(hexes[17], "#000011");
+        assert_eq!(builder.theme.colors.bright.red, "#00000b");
+    }
+
+    #[test]
+    fn test_sliders_adjust_the_selected_slot() {
+        let mut builder = ThemeBuilder::new(&ThemeApplicator::create_default_theme());
+        builder.set_selected("#804040").unwrap();
+
+        builder.toggle_channels();
+        assert_eq!(builder.channel, Channel::Red);
+        builder.adjust(1.0);
+        assert_eq!(builder.theme.colors.background, "#814040");
+        builder.next_channel();
+        builder.adjust(-10.0);
+        assert_eq!(builder.theme.colors.background, "#813640");
+        builder.adjust(-1000.0);
+        assert_eq!(builder.theme.colors.background, "#810040");
+
+        // A step too small to move an 8-bit channel still moves the slider
+        builder.toggle_channels();
+        assert_eq!(builder.channel, Channel::Saturation);
+        builder.channel = Channel::Hue;
+        let before = builder.selected_color();
+        builder.adjust(0.1);
+        assert_ne!(builder.selected_color(), before);
+        builder.adjust(-360.0);
+        let hue = Channel::Hue.value(builder.selected_color());
+        assert!((0.0..360.0).contains(&hue));
+    }
+
+    #[test]
+    fn test_undo_groups_held_slider_keys() {
+        let mut builder = ThemeBuilder::new(&ThemeApplicator::create_default_theme());
+        let original = builder.theme.colors.background.clone();
+        assert!(!builder.is_modified());
+        assert!(!builder.undo());
+
+        builder.channel = Channel::Blue;
+        for _ in 0..5 {
+            builder.adjust(1.0);
+        }
+        let adjusted = builder.theme.colors.background.clone();
+        builder.rename("Dusk");
+        assert!(builder.is_modified());
+
+        assert!(builder.undo());
+        assert_eq!(builder.theme.name, "Default Custom");
+        assert!(builder.undo());
+        assert_eq!(builder.theme.colors.background, original);
+        assert!(!builder.undo());
+
+        assert!(builder.redo());
+        assert_eq!(builder.theme.colors.background, adjusted);
+        // A new edit drops what was undone
+        builder.set_selected("#000000").unwrap();
+        assert!(!builder.redo());
+
+        for index in 0..UNDO_LIMIT + 10 {
+            builder
+                .set_selected(&format!("#0000{:02x}", index % 256))
+                .unwrap();
+        }
+        let mut steps = 0;
+        while builder.undo() {
+            steps += 1;
+        }
+        assert_eq!(steps, UNDO_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_palette_editor_updates_the_preview() {
+        use crate::models::{PreviewState, ThemeEntry};
+        use crate::tasks::TaskManager;
+        use crate::{App, HttpClient};
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        let theme = ThemeApplicator::create_default_theme();
+        app.theme_entries.push(ThemeEntry {
+            name: theme.name.clone(),
+            url: "https://github.com/user/omarchy-default-theme".to_string(),
+            description: String::new(),
+        });
+        app.theme_browser.preview_theme = Some(theme.clone());
+        app.preview_state = PreviewState::Applied(Box::new(theme.clone()));
+        app.theme_browser_mode = true;
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        app.handle_key_event(key(KeyCode::Char('e'))).await.unwrap();
+        assert!(app.theme_browser.palette_editor.is_some());
+        for c in "#ff8800".chars() {
+            app.handle_key_event(key(KeyCode::Char(c))).await.unwrap();
+        }
+        app.handle_key_event(key(KeyCode::Enter)).await.unwrap();
+
+        let PreviewState::Applied(shown) = &app.preview_state else {
+            panic!("preview should stay applied");
+        };
+        assert_eq!(shown.colors.background, "#ff8800");
+        assert_eq!(shown.name, "Default Custom");
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(140, 50)).unwrap();
+        terminal.draw(|f| crate::ui::draw(f, &mut app)).unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Editing Default Custom *"));
+        assert!(text.contains("background 1/18"));
+
+        app.handle_key_event(key(KeyCode::Char('u'))).await.unwrap();
+        let PreviewState::Applied(shown) = &app.preview_state else {
+            panic!("preview should stay applied");
+        };
+        assert_eq!(shown.colors.background, theme.colors.background);
+
+        // The wheel moves between slots instead of along the theme list underneath
+        let selected = app.theme_browser.palette_editor.as_ref().unwrap().selected;
+        app.handle_mouse_event(crossterm::event::MouseEvent {
+            kind: crossterm::event::MouseEventKind::ScrollDown,
+            column: 10,
+            row: 10,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            app.theme_browser.palette_editor.as_ref().unwrap().selected,
+            selected + 1
+        );
+        assert!(
+            matches!(&app.preview_state, PreviewState::Applied(shown) if shown.name == "Default Custom")
+        );
+
+        // Leaving the editor keeps the edited preview
+        app.handle_key_event(key(KeyCode::Esc)).await.unwrap();
+        assert!(app.theme_browser.palette_editor.is_none());
+        assert!(matches!(app.preview_state, PreviewState::Applied(_)));
+    }
+
+    #[tokio::test]
+    async fn test_builder_rejects_names_without_letters() {
+        use crate::tasks::TaskManager;
+        use crate::{App, HttpClient};
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        let mut builder = ThemeBuilder::new(&ThemeApplicator::create_default_theme());
+        let name = builder.theme.name.clone();
+        builder.input = Some(BuilderInput::Name("!!! 🌙".to_string()));
+        app.builder = Some(builder);
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .await
+            .unwrap();
+        assert_eq!(app.builder.as_ref().unwrap().theme.name, name);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("\"!!! 🌙\" needs a letter or digit")
+        );
+    }
+
+    #[test]
+    fn test_builder_screen_shows_edited_slots() {
+        use crate::tasks::TaskManager;
+        use crate::{App, HttpClient};
+        use ratatui::style::Color;
+
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        let mut builder = ThemeBuilder::new(&ThemeApplicator::create_default_theme());
+        builder.selected = 5;
+        builder.set_selected("#ff8800").unwrap();
+        app.builder = Some(builder);
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(140, 50)).unwrap();
+        terminal.draw(|f| crate::ui::draw(f, &mut app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("Theme Builder: Default Custom"));
+        assert!(text.contains("#ff8800"));
+        assert!(
+            buffer
+                .content()
+                .iter()
+                .any(|cell| cell.symbol() == "█" && cell.fg == Color::Rgb(0xff, 0x88, 0x00))
+        );
+    }
+}
@@ -0,0 +1,601 @@
+use crate::links::{self, LinkKind};
+use crate::models::ReadmeContent;
+use anyhow::Result;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+/// Requests in flight at once
+pub const CHECK_REQUESTS: usize = 8;
+
+/// Attempts after the first for network errors, rate limits and server errors
+const RETRIES: u32 = 2;
+
+/// What a URL turned out to be
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum LinkStatus {
+    Ok,
+    /// The page answers from another location now
+    Redirect {
+        to: String,
+    },
+    NotFound,
+    /// A GitHub repository archived by its owner
+    Archived,
+    /// A GitHub repository renamed or transferred
+    Moved {
+        to: String,
+    },
+    /// Anything else, after retries: server errors, timeouts, TLS failures
+    Failed {
+        reason: String,
+    },
+    /// Not checked: the host's rate limit ran out, e.g. GitHub's without a token
+    RateLimited,
+}
+
+impl LinkStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, LinkStatus::Ok)
+    }
+
+    /// Whether the entry needs fixing; rate-limited links are merely unknown
+    pub fn is_broken(&self) -> bool {
+        !matches!(self, LinkStatus::Ok | LinkStatus::RateLimited)
+    }
+
+    /// Short label for entry badges
+    pub fn badge(&self) -> &'static str {
+        match self {
+            LinkStatus::Ok => "ok",
+            LinkStatus::Redirect { .. } => "redirect",
+            LinkStatus::NotFound => "404",
+            LinkStatus::Archived => "archived",
+            LinkStatus::Moved { .. } => "moved",
+            LinkStatus::Failed { .. } => "unreachable",
+            LinkStatus::RateLimited => "rate-limited",
+        }
+    }
+
+    /// Where the entry should point instead, if known
+    pub fn new_location(&self) -> Option<&str> {
+        match self {
+            LinkStatus::Redirect { to } | LinkStatus::Moved { to } => Some(to),
+            _ => None,
+        }
+    }
+}
+
+/// A response, with redirects left unfollowed
+#[derive(Debug, Clone, Default)]
+pub struct Reply {
+    pub status: u16,
+    pub location: Option<String>,
+    pub body: String,
+    /// A 403 or 429 saying the rate limit is used up (`x-ratelimit-remaining: 0`)
+    pub rate_limited: bool,
+}
+
+/// The HTTP requests the checker makes, so tests can answer them
+pub trait Http: Sync {
+    fn head(&self, url: &str) -> impl Future<Output = Result<Reply>> + Send;
+    fn get(&self, url: &str) -> impl Future<Output = Result<Reply>> + Send;
+}
+
+/// The parts of the GitHub repository API the checker reads
+#[derive(Deserialize)]
+struct Repository {
+    full_name: String,
+    html_url: String,
+    #[serde(default)]
+    archived: bool,
+}
+
+/// Checks entry URLs concurrently, retrying transient failures
+pub struct Checker<H> {
+    http: H,
+    pub parallel: usize,
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each further one
+    pub backoff: Duration,
+}
+
+impl<H: Http> Checker<H> {
+    pub fn new(http: H) -> Self {
+        Self {
+            http,
+            parallel: CHECK_REQUESTS,
+            retries: RETRIES,
+            backoff: Duration::from_millis(500),
+        }
+    }
+
+    /// Status of every URL, each checked once
+    pub async fn check_all(&self, mut urls: Vec<String>) -> HashMap<String, LinkStatus> {
+        urls.sort();
+        urls.dedup();
+        futures::stream::iter(urls)
+            .map(|url| async move {
+                let status = self.check(&url).await;
+                (url, status)
+            })
+            .buffer_unordered(self.parallel.max(1))
+            .collect()
+            .await
+    }
+
+    pub async fn check(&self, url: &str) -> LinkStatus {
+        let result = match github_repo(url) {
+            Some(repo) => self.check_repository(&repo).await,
+            None => self.check_page(url).await,
+        };
+        result.unwrap_or_else(|e| LinkStatus::Failed {
+            reason: format!("{e:#}"),
+        })
+    }
+
+    /// Ask the GitHub API, which knows about archiving and renames
+    async fn check_repository(&self, repo: &str) -> Result<LinkStatus> {
+        let api = format!("https://api.github.com/repos/{repo}");
+        let mut reply = self.retrying(|| self.http.get(&api)).await?;
+        if reply.rate_limited {
+            return self.check_repository_page(repo).await;
+        }
+        // Renamed repositories answer with a redirect to their new API location
+        if is_redirect(reply.status)
+            && let Some(location) = reply.location.clone()
+        {
+            reply = self.retrying(|| self.http.get(&location)).await?;
+        }
+
+        match reply.status {
+            200 => {
+                let found: Repository = serde_json::from_str(&reply.body)?;
+                Ok(if !found.full_name.eq_ignore_ascii_case(repo) {
+                    LinkStatus::Moved { to: found.html_url }
+                } else if found.archived {
+                    LinkStatus::Archived
+                } else {
+                    LinkStatus::Ok
+                })
+            }
+            404 | 410 => Ok(LinkStatus::NotFound),
+            status => Ok(failed(status)),
+        }
+    }
+
+    /// Out of API requests, the repository page still tells a live repository from a gone or
+    /// renamed one, though not an archived one
+    async fn check_repository_page(&self, repo: &str) -> Result<LinkStatus> {
+        Ok(
+            match self
+                .check_page(&format!("https://github.com/{repo}"))
+                .await?
+            {
+                LinkStatus::Redirect { to } => LinkStatus::Moved { to },
+                status => status,
+            },
+        )
+    }
+
+    async fn check_page(&self, url: &str) -> Result<LinkStatus> {
+        let mut reply = self.retrying(|| self.http.head(url)).await?;
+        // Some servers refuse HEAD but answer GET
+        if matches!(reply.status, 403 | 405 | 501) {
+            reply = self.retrying(|| self.http.get(url)).await?;
+        }
+
+        Ok(match reply.status {
+            _ if reply.rate_limited || reply.status == 429 => LinkStatus::RateLimited,
+            200..=299 => LinkStatus::Ok,
+            status if is_redirect(status) => match reply.location {
+                Some(location) => LinkStatus::Redirect {
+                    to: resolve(url, &location),
+                },
+                None => failed(status),
+            },
+            404 | 410 => LinkStatus::NotFound,
+            status => failed(status),
+        })
+    }
+
+    /// Run a request, again after a growing pause while it errors or the server is struggling
+    async fn retrying<F, Fut>(&self, request: F) -> Result<Reply>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<Reply>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let result = request().await;
+            // A used up rate limit lasts until its reset, well beyond any backoff
+            let transient = match &result {
+                Ok(reply) => !reply.rate_limited && (reply.status == 429 || reply.status >= 500),
+                Err(_) => true,
+            };
+            if !transient || attempt >= self.retries {
+                return result;
+            }
+            tokio::time::sleep(self.backoff * 2u32.pow(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_redirect(status: u16) -> bool {
+    matches!(status, 301 | 302 | 303 | 307 | 308)
+}
+
+fn failed(status: u16) -> LinkStatus {
+    LinkStatus::Failed {
+        reason: format!("HTTP {status}"),
+    }
+}
+
+/// `owner/repo` of a GitHub repository URL
+fn github_repo(url: &str) -> Option<String> {
+    (LinkKind::classify(url) == LinkKind::GitHub)
+        .then(|| links::repo_path(url))
+        .flatten()
+}
+
+/// Absolute form of a `Location` header, which may be relative to the request
+fn resolve(url: &str, location: &str) -> String {
+    if links::is_web_url(location) {
+        return location.to_string();
+    }
+    let scheme_end = url.find("://").map_or(0, |i| i + 3);
+    let origin_end = url[scheme_end..]
+        .find('/')
+        .map_or(url.len(), |i| scheme_end + i);
+    if location.starts_with('/') {
+        format!("{}{location}", &url[..origin_end])
+    } else {
+        let base = url
+            .rfind('/')
+            .filter(|&i| i >= origin_end)
+            .unwrap_or(url.len());
+        format!("{}/{location}", &url[..base])
+    }
+}
+
+/// Entries with a problem, as a markdown checklist for a cleanup PR
+pub fn report(readme: &ReadmeContent, statuses: &HashMap<String, LinkStatus>) -> String {
+    let mut groups: Vec<(&str, Vec<String>)> = [
+        ("404", Vec::new()),
+        ("moved", Vec::new()),
+        ("archived", Vec::new()),
+        ("redirect", Vec::new()),
+        ("unreachable", Vec::new()),
+        ("rate-limited", Vec::new()),
+    ]
+    .into();
+    for (index, section) in readme.sections.iter().enumerate() {
+        for entry in &section.entries {
+            let Some(status) = statuses.get(&entry.url).filter(|status| !status.is_ok()) else {
+                continue;
+            };
+            let mut line = format!(
+                "- [ ] **{}** ({}): {}",
+                entry.title,
+                readme.breadcrumb(index),
+                entry.url
+            );
+            if let Some(to) = status.new_location() {
+                line.push_str(&format!(" → {to}"));
+            }
+            if let LinkStatus::Failed { reason } = status {
+                line.push_str(&format!(" ({reason})"));
+            }
+            if let Some((_, lines)) = groups
+                .iter_mut()
+                .find(|(badge, _)| *badge == status.badge())
+            {
+                lines.push(line);
+            }
+        }
+    }
+
+    let mut report = String::new();
+    for (badge, lines) in groups.iter().filter(|(_, lines)| !lines.is_empty()) {
+        let heading = match *badge {
+            "404" => "Not found — remove",
+            "moved" => "Moved repositories — update the URL",
+            "archived" => "Archived repositories — consider removing",
+            "redirect" => "Redirects — update the URL",
+            "rate-limited" => "Rate-limited — not checked, try again later or set GITHUB_TOKEN",
+            _ => "Unreachable — check by hand",
+        };
+        report.push_str(&format!("### {heading} ({})\n\n", lines.len()));
+        for line in lines {
+            report.push_str(line);
+            report.push('\n');
+        }
+        report.push('\n');
+    }
+    report
+}
+
+/// One line on how a check went, counting rate-limited links apart from broken ones
+pub fn summary(statuses: &HashMap<String, LinkStatus>) -> String {
+    let broken = statuses
+        .values()
+        .filter(|status| status.is_broken())
+        .count();
+    let limited = statuses
+        .values()
+        .filter(|status| **status == LinkStatus::RateLimited)
+        .count();
+    let total = statuses.len();
+    match (broken, limited) {
+        (0, 0) => format!("All {total} links are fine"),
+        (0, limited) => format!("No broken links; {limited} of {total} were rate-limited"),
+        (broken, 0) => format!("{broken} of {total} links need attention"),
+        (broken, limited) => {
+            format!("{broken} of {total} links need attention, {limited} were rate-limited")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::links::Link;
+    use crate::models::{RepositoryEntry, Section};
+    use anyhow::anyhow;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Canned replies by URL, served in order; the last one repeats
+    #[derive(Default)]
+    struct MockHttp {
+        replies: Mutex<HashMap<String, Vec<Option<Reply>>>>,
+        in_flight: AtomicUsize,
+        most_in_flight: AtomicUsize,
+        requests: AtomicUsize,
+    }
+
+    impl MockHttp {
+        fn reply(self, url: &str, status: u16, location: Option<&str>, body: &str) -> Self {
+            self.replies
+                .lock()
+                .unwrap()
+                .entry(url.to_string())
+                .or_default()
+                .push(Some(Reply {
+                    status,
+                    location: location.map(str::to_string),
+                    body: body.to_string(),
+                    rate_limited: false,
+                }));
+            self
+        }
+
+        fn rate_limited(self, url: &str) -> Self {
+            self.replies
+                .lock()
+                .unwrap()
+                .entry(url.to_string())
+                .or_default()
+                .push(Some(Reply {
+                    status: 403,
+                    rate_limited: true,
+                    ..Reply::default()
+                }));
+            self
+        }
+
+        fn error(self, url: &str) -> Self {
+            self.replies
+                .lock()
+                .unwrap()
+                .entry(url.to_string())
+                .or_default()
+                .push(None);
+            self
+        }
+
+        async fn serve(&self, url: &str) -> Result<Reply> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.most_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let mut replies = self.replies.lock().unwrap();
+            let queue = replies
+                .get_mut(url)
+                .ok_or_else(|| anyhow!("no reply for {url}"))?;
+            let reply = if queue.len() > 1 {
+                queue.remove(0)
+            } else {
+                queue[0].clone()
+            };
+            reply.ok_or_else(|| anyhow!("connection reset"))
+        }
+    }
+
+    impl Http for MockHttp {
+        fn head(&self, url: &str) -> impl Future<Output = Result<Reply>> + Send {
+            self.serve(url)
+        }
+
+        fn get(&self, url: &str) -> impl Future<Output = Result<Reply>> + Send {
+            self.serve(url)
+        }
+    }
+
+    fn repo(full_name: &str, archived: bool) -> String {
+        format!(
+            r#"{{"full_name":"{full_name}","html_url":"https://github.com/{full_name}","archived":{archived}}}"#
+        )
+    }
+
+    fn checker(http: MockHttp) -> Checker<MockHttp> {
+        Checker {
+            backoff: Duration::from_millis(1),
+            ..Checker::new(http)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_statuses_are_classified() {
+        let api = "https://api.github.com/repos";
+        let http = MockHttp::default()
+            .reply(&format!("{api}/a/live"), 200, None, &repo("a/live", false))
+            .reply(&format!("{api}/a/old"), 200, None, &repo("a/old", true))
+            .reply(&format!("{api}/a/gone"), 404, None, "")
+            .reply(
+                &format!("{api}/a/renamed"),
+                301,
+                Some("https://api.github.com/repositories/7"),
+                "",
+            )
+            .reply(
+                "https://api.github.com/repositories/7",
+                200,
+                None,
+                &repo("b/new-name", false),
+            )
+            .reply("https://site.dev/docs", 301, Some("/v2/docs"), "")
+            .reply("https://strict.dev", 405, None, "")
+            .reply("https://strict.dev", 200, None, "")
+            .reply("https://down.dev", 503, None, "");
+        let checker = checker(http);
+
+        let urls = [
+            "https://github.com/a/live",
+            "https://github.com/a/old",
+            "https://github.com/a/gone",
+            "https://github.com/a/renamed",
+            "https://site.dev/docs",
+            "https://strict.dev",
+            "https://down.dev",
+        ];
+        let statuses = checker
+            .check_all(urls.iter().map(|url| url.to_string()).collect())
+            .await;
+        let status = |url: &str| statuses[url].clone();
+
+        assert_eq!(status(urls[0]), LinkStatus::Ok);
+        assert_eq!(status(urls[1]), LinkStatus::Archived);
+        assert_eq!(status(urls[2]), LinkStatus::NotFound);
+        assert_eq!(
+            status(urls[3]),
+            LinkStatus::Moved {
+                to: "https://github.com/b/new-name".to_string()
+            }
+        );
+        assert_eq!(
+            status(urls[4]),
+            LinkStatus::Redirect {
+                to: "https://site.dev/v2/docs".to_string()
+            }
+        );
+        assert_eq!(status(urls[5]), LinkStatus::Ok);
+        assert_eq!(
+            status(urls[6]),
+            LinkStatus::Failed {
+                reason: "HTTP 503".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_and_bounded_parallelism() {
+        let mut http = MockHttp::default()
+            .error("https://flaky.dev")
+            .reply("https://flaky.dev", 429, None, "")
+            .reply("https://flaky.dev", 200, None, "");
+        let urls: Vec<String> = (0..12).map(|i| format!("https://site{i}.dev")).collect();
+        for url in &urls {
+            http = http.reply(url, 200, None, "");
+        }
+        let mut checker = checker(http);
+        checker.parallel = 3;
+
+        assert_eq!(checker.check("https://flaky.dev").await, LinkStatus::Ok);
+        assert_eq!(checker.http.requests.load(Ordering::SeqCst), 3);
+
+        let statuses = checker.check_all(urls).await;
+        assert!(statuses.values().all(LinkStatus::is_ok));
+        assert_eq!(checker.http.most_in_flight.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_api_falls_back_to_the_page() {
+        let api = "https://api.github.com/repos";
+        let http = MockHttp::default()
+            .rate_limited(&format!("{api}/a/live"))
+            .rate_limited(&format!("{api}/a/renamed"))
+            .rate_limited(&format!("{api}/a/busy"))
+            .reply("https://github.com/a/live", 200, None, "")
+            .reply(
+                "https://github.com/a/renamed",
+                301,
+                Some("https://github.com/b/renamed"),
+                "",
+            )
+            .reply("https://github.com/a/busy", 429, None, "");
+        let checker = checker(http);
+
+        let statuses = checker
+            .check_all(
+                ["live", "renamed", "busy"]
+                    .map(|name| format!("https://github.com/a/{name}"))
+                    .into(),
+            )
+            .await;
+        assert_eq!(statuses["https://github.com/a/live"], LinkStatus::Ok);
+        assert_eq!(
+            statuses["https://github.com/a/renamed"],
+            LinkStatus::Moved {
+                to: "https://github.com/b/renamed".to_string()
+            }
+        );
+        assert_eq!(
+            statuses["https://github.com/a/busy"],
+            LinkStatus::RateLimited
+        );
+        assert!(!LinkStatus::RateLimited.is_broken());
+        assert_eq!(
+            summary(&statuses),
+            "1 of 3 links need attention, 1 were rate-limited"
+        );
+    }
+
+    #[test]
+    fn test_report_groups_problems() {
+        let mut section = Section::new("Tools".to_string());
+        for name in ["gone", "fine"] {
+            let url = format!("https://github.com/a/{name}");
+            section.entries.push(RepositoryEntry {
+                title: name.to_string(),
+                url: url.clone(),
+                description: String::new(),
+                tags: Vec::new(),
+                kind: LinkKind::GitHub,
+                links: vec![Link::new(&url)],
+            });
+        }
+        let readme = ReadmeContent {
+            sections: vec![section],
+            ..ReadmeContent::default()
+        };
+        let statuses = HashMap::from([
+            (
+                "https://github.com/a/gone".to_string(),
+                LinkStatus::NotFound,
+            ),
+            ("https://github.com/a/fine".to_string(), LinkStatus::Ok),
+        ]);
+
+        assert_eq!(
+            report(&readme, &statuses),
+            "### Not found — remove (1)\n\n- [ ] **gone** (Tools): https://github.com/a/gone\n\n"
+        );
+    }
+}
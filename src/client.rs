@@ -1,26 +1,97 @@
+use crate::checker::{Http, Reply};
+use crate::history::{self, History};
+use crate::links::{Link, LinkKind};
 use crate::models::ThemeEntry;
 use crate::models::{ReadmeContent, Theme};
 use crate::parser::ReadmeParser;
-use crate::parser::ThemeParser;
-use anyhow::Result;
-use reqwest::Client;
+use crate::parser::{ThemeFormat, ThemeParser};
+use crate::theme_cache::{self, ThemeCache};
+use crate::wallpaper::{BACKGROUNDS_DIR, pick_background};
+use anyhow::{Result, anyhow};
+use futures::StreamExt;
+use reqwest::header::{ACCEPT, USER_AGENT};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::fs;
 
+/// File in the cache directory holding the GitHub topics already fetched
+const TOPICS_FILE: &str = "topics.json";
+
+/// Topic requests in flight at once
+const TOPIC_REQUESTS: usize = 8;
+
+/// File under the cache directory holding the parsed README
+const README_CACHE: &str = "readme.json";
+
+/// Bumped whenever parsing changes what cached README content holds, so older caches get refetched
+const README_CACHE_VERSION: u32 = 2;
+
+/// Response of the GitHub topics API
+#[derive(Deserialize)]
+struct Topics {
+    names: Vec<String>,
+}
+
+/// An entry of a GitHub contents API directory listing
+#[derive(Deserialize)]
+struct ContentEntry {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    download_url: Option<String>,
+}
+
+#[derive(Clone)]
 pub struct HttpClient {
     client: Client,
+    /// Leaves redirects unfollowed, for checking links
+    probe: Client,
     cache_dir: PathBuf,
+    themes: ThemeCache,
+    history: History,
+    /// Stands in for GitHub in tests: theme colors come from it and there are no wallpapers
+    #[cfg(test)]
+    theme_stub: Option<fn(&ThemeEntry) -> Result<Theme>>,
 }
 
 impl HttpClient {
     pub fn new() -> Self {
-        let cache_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("awesome-omarchy-tui");
+        Self::with_cache_dir(
+            dirs::cache_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("awesome-omarchy-tui"),
+        )
+    }
+
+    /// A client keeping the README, theme and history caches under `cache_dir`
+    pub fn with_cache_dir(cache_dir: PathBuf) -> Self {
+        let probe = Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(Duration::from_secs(15))
+            .user_agent("awesome-omarchy-tui")
+            .build()
+            .unwrap_or_default();
 
         Self {
             client: Client::new(),
+            probe,
+            themes: ThemeCache::new(&cache_dir),
+            history: History::new(&cache_dir),
             cache_dir,
+            #[cfg(test)]
+            theme_stub: None,
+        }
+    }
+
+    /// A client caching under `cache_dir` that answers theme color fetches with `stub`
+    #[cfg(test)]
+    pub fn with_theme_stub(cache_dir: PathBuf, stub: fn(&ThemeEntry) -> Result<Theme>) -> Self {
+        Self {
+            theme_stub: Some(stub),
+            ..Self::with_cache_dir(cache_dir)
         }
     }
 
@@ -30,12 +101,17 @@ impl HttpClient {
 
         // Try to load from cache first (unless force refresh)
         if !force_refresh && let Ok(cached) = self.load_from_cache().await {
+            // A failed snapshot only costs the next diff some precision
+            let _ = self.history.record(&cached, history::now());
             return Ok(cached);
         }
 
         // Fetch from GitHub
-        let response = self.client.get(url).send().await?;
-        let markdown_content = response.text().await?;
+        let markdown_content = match self.download(url).await {
+            Ok(markdown) => markdown,
+            // Offline, a cache written by an older version beats nothing
+            Err(e) => return self.load_stale_cache().await.ok_or(e),
+        };
 
         // Parse the markdown content using comprehensive parser
         let parser = ReadmeParser::new();
@@ -47,10 +123,21 @@ impl HttpClient {
 
         // Cache the result
         self.save_to_cache(&readme_content).await?;
+        let _ = self.history.record(&readme_content, history::now());
 
         Ok(readme_content)
     }
 
+    async fn download(&self, url: &str) -> Result<String> {
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        Ok(response.text().await?)
+    }
+
+    /// Snapshots of the README kept across visits
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
     /// Fetch themes from the cached README "Themes" section
     pub async fn fetch_themes_from_readme(&self) -> Result<Vec<ThemeEntry>> {
         // Load cached README content
@@ -64,283 +151,194 @@ impl HttpClient {
         parser.extract_themes_from_readme(&readme_content)
     }
 
-    /// Lazy load a specific theme's alacritty.toml from GitHub
+    /// Lazy load a specific theme's colors, from the on-disk cache while its repository is unchanged
     pub async fn fetch_theme_colors(&self, theme_entry: &ThemeEntry) -> Result<Theme> {
-        // Extract GitHub owner/repo from URL
-        let url_parts: Vec<&str> = theme_entry.url.split('/').collect();
-        if url_parts.len() < 5 || !theme_entry.url.starts_with("https://github.com/") {
-            return Err(anyhow::anyhow!("Invalid GitHub URL: {}", theme_entry.url));
+        #[cfg(test)]
+        if let Some(stub) = self.theme_stub {
+            return stub(theme_entry);
         }
+        let (owner, repo) = github_repo(&theme_entry.url)?;
+
+        let theme = match self.fetch_head_commit(owner, repo).await {
+            Ok(commit) => match self.themes.load(owner, repo, &commit) {
+                Some(theme) => theme,
+                None => {
+                    let theme = self
+                        .discover_theme_colors(theme_entry, owner, repo, &commit)
+                        .await?;
+                    // A failed write only costs a refetch next time
+                    let _ = self.themes.store(owner, repo, &commit, &theme);
+                    theme
+                }
+            },
+            // Offline or unreachable: an older palette beats none
+            Err(_) => match self.themes.latest(owner, repo) {
+                Some(theme) => theme,
+                None => {
+                    self.discover_theme_colors(theme_entry, owner, repo, "HEAD")
+                        .await?
+                }
+            },
+        };
+        Ok(with_entry(theme, theme_entry))
+    }
+
+    /// Palettes of `entries` from the disk cache alone, without touching the network
+    pub fn cached_themes(&self, entries: &[ThemeEntry]) -> Vec<(String, Theme)> {
+        let cached = self.themes.all();
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let (owner, repo) = github_repo(&entry.url).ok()?;
+                let theme = cached.get(&(owner.to_lowercase(), repo.to_lowercase()))?;
+                Some((entry.name.clone(), with_entry(theme.clone(), entry)))
+            })
+            .collect()
+    }
 
-        let owner = url_parts[3];
-        let repo = url_parts[4];
+    /// Drop a theme's cached palette so the next fetch reads its repository again
+    pub fn forget_theme_colors(&self, theme_entry: &ThemeEntry) -> Result<()> {
+        let (owner, repo) = github_repo(&theme_entry.url)?;
+        self.themes.remove(owner, repo)
+    }
+
+    /// The commit a repository's default branch points at, from git's ref advertisement,
+    /// which unlike the REST API is not rate limited
+    async fn fetch_head_commit(&self, owner: &str, repo: &str) -> Result<String> {
+        let url =
+            format!("https://github.com/{owner}/{repo}.git/info/refs?service=git-upload-pack");
+        let advertisement = self
+            .client
+            .get(&url)
+            .header(USER_AGENT, "git/2.0 awesome-omarchy-tui")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        theme_cache::head_commit(&advertisement)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("No HEAD in {owner}/{repo}"))
+    }
 
-        // Try common alacritty config file locations
-        let possible_paths = vec![
+    /// Read the colors at `rev` from whichever theme file the repository ships
+    async fn discover_theme_colors(
+        &self,
+        theme_entry: &ThemeEntry,
+        owner: &str,
+        repo: &str,
+        rev: &str,
+    ) -> Result<Theme> {
+        let name = theme_entry.name.to_lowercase();
+
+        // Candidate files in order of preference
+        let possible_paths = [
             "alacritty.toml".to_string(),
             "alacritty.yml".to_string(),
             ".config/alacritty/alacritty.toml".to_string(),
             ".config/alacritty/alacritty.yml".to_string(),
             "config/alacritty.toml".to_string(),
             "config/alacritty.yml".to_string(),
-            format!("{}.toml", theme_entry.name.to_lowercase()),
-            format!("{}.yml", theme_entry.name.to_lowercase()),
+            format!("{name}.toml"),
+            format!("{name}.yml"),
+            "kitty.conf".to_string(),
+            "kitty-theme.conf".to_string(),
+            "ghostty.conf".to_string(),
+            "ghostty".to_string(),
+            "base16.yaml".to_string(),
+            "base16.yml".to_string(),
+            "btop.theme".to_string(),
         ];
 
-        for path in possible_paths {
-            let raw_url = format!("https://raw.githubusercontent.com/{owner}/{repo}/main/{path}");
-
-            if let Ok(response) = self.client.get(&raw_url).send().await
-                && response.status().is_success()
-                && let Ok(content) = response.text().await
-            {
-                let parser = ThemeParser::new();
-
-                // Try parsing as TOML first
-                if path.ends_with(".toml") {
-                    if let Ok(theme) = parser.parse_alacritty_theme(&theme_entry.name, &content) {
-                        return Ok(Theme {
-                            name: theme_entry.name.clone(),
-                            description: theme_entry.description.clone(),
-                            source_url: theme_entry.url.clone(),
-                            colors: theme.colors,
-                        });
-                    }
-                }
-                // Try parsing as YAML if TOML fails or for .yml files
-                else if (path.ends_with(".yml") || path.ends_with(".yaml"))
-                    && let Ok(theme) = parser.parse_alacritty_yaml(&theme_entry.name, &content)
-                {
-                    return Ok(Theme {
-                        name: theme_entry.name.clone(),
-                        description: theme_entry.description.clone(),
-                        source_url: theme_entry.url.clone(),
-                        colors: theme.colors,
-                    });
-                }
-            }
-        }
-
-        // Fallback: create a default theme with the entry information
-        Ok(self.create_fallback_theme(theme_entry))
-    }
-
-    fn create_fallback_theme(&self, theme_entry: &ThemeEntry) -> Theme {
-        // Create a visually distinct fallback theme
-        let theme_colors = match theme_entry.name.to_lowercase().as_str() {
-            name if name.contains("dark") => self.create_dark_theme_colors(),
-            name if name.contains("light") => self.create_light_theme_colors(),
-            name if name.contains("blue") => self.create_blue_theme_colors(),
-            name if name.contains("green") => self.create_green_theme_colors(),
-            name if name.contains("red") => self.create_red_theme_colors(),
-            name if name.contains("purple") || name.contains("violet") => {
-                self.create_purple_theme_colors()
+        // Request every candidate at once; the order above still decides which one wins
+        let contents = futures::future::join_all(
+            possible_paths
+                .iter()
+                .map(|path| self.fetch_raw_file(owner, repo, rev, path)),
+        )
+        .await;
+
+        let parser = ThemeParser::new();
+        for (path, content) in possible_paths.iter().zip(contents) {
+            // A file that failed to download might have been the one; report that, not a miss
+            let (Some(content), Some(format)) = (content?, ThemeFormat::from_path(path)) else {
+                continue;
+            };
+            if let Ok(theme) = parser.parse_format(format, &theme_entry.name, &content) {
+                return Ok(with_entry(theme, theme_entry));
             }
-            _ => self.create_default_theme_colors(),
-        };
-
-        Theme {
-            name: theme_entry.name.clone(),
-            description: format!("{} (fallback colors)", theme_entry.description),
-            source_url: theme_entry.url.clone(),
-            colors: theme_colors,
-        }
-    }
-
-    fn create_dark_theme_colors(&self) -> crate::models::ThemeColors {
-        use crate::models::{ThemeColorPalette, ThemeColors};
-        ThemeColors {
-            background: "#0d1117".to_string(),
-            foreground: "#c9d1d9".to_string(),
-            normal: ThemeColorPalette {
-                black: "#484f58".to_string(),
-                red: "#ff7b72".to_string(),
-                green: "#7ee787".to_string(),
-                yellow: "#f2cc60".to_string(),
-                blue: "#79c0ff".to_string(),
-                magenta: "#d2a8ff".to_string(),
-                cyan: "#56d4dd".to_string(),
-                white: "#c9d1d9".to_string(),
-            },
-            bright: ThemeColorPalette {
-                black: "#6e7681".to_string(),
-                red: "#ffa198".to_string(),
-                green: "#56d364".to_string(),
-                yellow: "#e3b341".to_string(),
-                blue: "#58a6ff".to_string(),
-                magenta: "#bc8cff".to_string(),
-                cyan: "#39c5cf".to_string(),
-                white: "#f0f6fc".to_string(),
-            },
         }
-    }
 
-    fn create_light_theme_colors(&self) -> crate::models::ThemeColors {
-        use crate::models::{ThemeColorPalette, ThemeColors};
-        ThemeColors {
-            background: "#ffffff".to_string(),
-            foreground: "#24292f".to_string(),
-            normal: ThemeColorPalette {
-                black: "#24292f".to_string(),
-                red: "#cf222e".to_string(),
-                green: "#116329".to_string(),
-                yellow: "#4d2d00".to_string(),
-                blue: "#0969da".to_string(),
-                magenta: "#8250df".to_string(),
-                cyan: "#1b7c83".to_string(),
-                white: "#6e7781".to_string(),
-            },
-            bright: ThemeColorPalette {
-                black: "#57606a".to_string(),
-                red: "#a40e26".to_string(),
-                green: "#1a7f37".to_string(),
-                yellow: "#633c01".to_string(),
-                blue: "#218bff".to_string(),
-                magenta: "#a475f9".to_string(),
-                cyan: "#3192aa".to_string(),
-                white: "#8c959f".to_string(),
-            },
-        }
+        Err(anyhow!(
+            "No colors found in {owner}/{repo}: no alacritty, kitty, Ghostty, base16 or btop theme file"
+        ))
     }
 
-    fn create_blue_theme_colors(&self) -> crate::models::ThemeColors {
-        use crate::models::{ThemeColorPalette, ThemeColors};
-        ThemeColors {
-            background: "#0f1419".to_string(),
-            foreground: "#bfbab0".to_string(),
-            normal: ThemeColorPalette {
-                black: "#1c2023".to_string(),
-                red: "#f07178".to_string(),
-                green: "#c3e88d".to_string(),
-                yellow: "#ffcb6b".to_string(),
-                blue: "#82aaff".to_string(),
-                magenta: "#c792ea".to_string(),
-                cyan: "#89ddff".to_string(),
-                white: "#d6deeb".to_string(),
-            },
-            bright: ThemeColorPalette {
-                black: "#5c6370".to_string(),
-                red: "#f78c6c".to_string(),
-                green: "#addb67".to_string(),
-                yellow: "#f78c6a".to_string(),
-                blue: "#82aaff".to_string(),
-                magenta: "#c792ea".to_string(),
-                cyan: "#7fdbca".to_string(),
-                white: "#ffffff".to_string(),
-            },
+    /// A file at `rev`, or `None` when it does not exist; any other failure is an error
+    async fn fetch_raw_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        rev: &str,
+        path: &str,
+    ) -> Result<Option<String>> {
+        let raw_url = format!("https://raw.githubusercontent.com/{owner}/{repo}/{rev}/{path}");
+        let response = self.client.get(&raw_url).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
         }
+        Ok(Some(response.error_for_status()?.text().await?))
     }
 
-    fn create_green_theme_colors(&self) -> crate::models::ThemeColors {
-        use crate::models::{ThemeColorPalette, ThemeColors};
-        ThemeColors {
-            background: "#0d1b2a".to_string(),
-            foreground: "#a8cc8c".to_string(),
-            normal: ThemeColorPalette {
-                black: "#0d1b2a".to_string(),
-                red: "#e06c75".to_string(),
-                green: "#98c379".to_string(),
-                yellow: "#e5c07b".to_string(),
-                blue: "#61afef".to_string(),
-                magenta: "#c678dd".to_string(),
-                cyan: "#56b6c2".to_string(),
-                white: "#979eab".to_string(),
-            },
-            bright: ThemeColorPalette {
-                black: "#393e46".to_string(),
-                red: "#f07178".to_string(),
-                green: "#c3e88d".to_string(),
-                yellow: "#ffcb6b".to_string(),
-                blue: "#82aaff".to_string(),
-                magenta: "#c792ea".to_string(),
-                cyan: "#89ddff".to_string(),
-                white: "#ffffff".to_string(),
-            },
+    /// Download the first image in a theme's `backgrounds/`; `None` when it has none
+    pub async fn fetch_wallpaper(&self, repo_url: &str) -> Result<Option<(String, Vec<u8>)>> {
+        #[cfg(test)]
+        if self.theme_stub.is_some() {
+            return Ok(None);
         }
-    }
+        let Some(owner_repo) = repo_url
+            .strip_prefix("https://github.com/")
+            .map(|path| path.trim_end_matches('/').trim_end_matches(".git"))
+            .filter(|path| path.split('/').count() == 2)
+        else {
+            return Err(anyhow!("Invalid GitHub URL: {repo_url}"));
+        };
 
-    fn create_red_theme_colors(&self) -> crate::models::ThemeColors {
-        use crate::models::{ThemeColorPalette, ThemeColors};
-        ThemeColors {
-            background: "#2b1b17".to_string(),
-            foreground: "#d4d4d4".to_string(),
-            normal: ThemeColorPalette {
-                black: "#2b1b17".to_string(),
-                red: "#f07178".to_string(),
-                green: "#c3e88d".to_string(),
-                yellow: "#ffcb6b".to_string(),
-                blue: "#82aaff".to_string(),
-                magenta: "#c792ea".to_string(),
-                cyan: "#89ddff".to_string(),
-                white: "#eeffff".to_string(),
-            },
-            bright: ThemeColorPalette {
-                black: "#5c6370".to_string(),
-                red: "#ff6b6b".to_string(),
-                green: "#95e1d3".to_string(),
-                yellow: "#f8ca93".to_string(),
-                blue: "#74b9ff".to_string(),
-                magenta: "#e17899".to_string(),
-                cyan: "#81ecec".to_string(),
-                white: "#fdcb6e".to_string(),
-            },
+        // The contents API rejects requests without a user agent
+        let listing_url =
+            format!("https://api.github.com/repos/{owner_repo}/contents/{BACKGROUNDS_DIR}");
+        let response = self
+            .client
+            .get(&listing_url)
+            .header(USER_AGENT, "awesome-omarchy-tui")
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
         }
-    }
+        let entries: Vec<ContentEntry> = response.error_for_status()?.json().await?;
 
-    fn create_purple_theme_colors(&self) -> crate::models::ThemeColors {
-        use crate::models::{ThemeColorPalette, ThemeColors};
-        ThemeColors {
-            background: "#1e1a2e".to_string(),
-            foreground: "#d4c5f9".to_string(),
-            normal: ThemeColorPalette {
-                black: "#1e1a2e".to_string(),
-                red: "#e06c75".to_string(),
-                green: "#98c379".to_string(),
-                yellow: "#e5c07b".to_string(),
-                blue: "#61afef".to_string(),
-                magenta: "#c678dd".to_string(),
-                cyan: "#56b6c2".to_string(),
-                white: "#abb2bf".to_string(),
-            },
-            bright: ThemeColorPalette {
-                black: "#5c6370".to_string(),
-                red: "#be5046".to_string(),
-                green: "#98c379".to_string(),
-                yellow: "#d19a66".to_string(),
-                blue: "#61afef".to_string(),
-                magenta: "#c678dd".to_string(),
-                cyan: "#56b6c2".to_string(),
-                white: "#ffffff".to_string(),
-            },
-        }
-    }
+        let files = entries.iter().filter(|entry| entry.kind == "file");
+        let Some(name) = pick_background(files.map(|entry| entry.name.as_str())) else {
+            return Ok(None);
+        };
+        let Some(download_url) = entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .and_then(|entry| entry.download_url.as_deref())
+        else {
+            return Ok(None);
+        };
 
-    fn create_default_theme_colors(&self) -> crate::models::ThemeColors {
-        use crate::models::{ThemeColorPalette, ThemeColors};
-        ThemeColors {
-            background: "#1a1b26".to_string(),
-            foreground: "#a9b1d6".to_string(),
-            normal: ThemeColorPalette {
-                black: "#32344a".to_string(),
-                red: "#f7768e".to_string(),
-                green: "#9ece6a".to_string(),
-                yellow: "#e0af68".to_string(),
-                blue: "#7aa2f7".to_string(),
-                magenta: "#ad8ee6".to_string(),
-                cyan: "#449dab".to_string(),
-                white: "#787c99".to_string(),
-            },
-            bright: ThemeColorPalette {
-                black: "#444b6a".to_string(),
-                red: "#ff7a93".to_string(),
-                green: "#b9f27c".to_string(),
-                yellow: "#ff9e64".to_string(),
-                blue: "#7da6ff".to_string(),
-                magenta: "#bb9af7".to_string(),
-                cyan: "#0db9d7".to_string(),
-                white: "#acb0d0".to_string(),
-            },
-        }
+        let bytes = self
+            .client
+            .get(download_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        Ok(Some((name.to_string(), bytes.to_vec())))
     }
 
     /// Simple markdown parser until module resolution is fixed
@@ -370,30 +368,28 @@ impl HttpClient {
                     current_header_level = level as u32;
                     header_text.clear();
                 }
-                Event::End(TagEnd::Heading(_)) => {
-                    if is_in_header {
-                        let header = header_text.trim().to_string();
-
-                        // Extract title from first H1
-                        if !title_extracted && current_header_level == 1 {
-                            metadata.title = header.clone();
-                            title_extracted = true;
-                        }
+                Event::End(TagEnd::Heading(_)) if is_in_header => {
+                    let header = header_text.trim().to_string();
 
-                        // Check if this is a section header we should parse
-                        if current_header_level >= 2 {
-                            // Save previous section if exists
-                            if let Some(section) = current_section.take() {
-                                readme_content.sections.push(section);
-                            }
+                    // Extract title from first H1
+                    if !title_extracted && current_header_level == 1 {
+                        metadata.title = header.clone();
+                        title_extracted = true;
+                    }
 
-                            // Start new section
-                            current_section = Some(Section::new(header));
-                            current_text.clear();
+                    // Check if this is a section header we should parse
+                    if current_header_level >= 2 {
+                        // Save previous section if exists
+                        if let Some(section) = current_section.take() {
+                            readme_content.sections.push(section);
                         }
 
-                        is_in_header = false;
+                        // Start new section
+                        current_section = Some(Section::new(header));
+                        current_text.clear();
                     }
+
+                    is_in_header = false;
                 }
                 Event::Text(text) => {
                     if is_in_header {
@@ -405,12 +401,10 @@ impl HttpClient {
                         }
                     }
                 }
-                Event::SoftBreak | Event::HardBreak => {
-                    if !is_in_header {
-                        current_text.push('\n');
-                        if let Some(ref mut section) = current_section {
-                            section.raw_content.push('\n');
-                        }
+                Event::SoftBreak | Event::HardBreak if !is_in_header => {
+                    current_text.push('\n');
+                    if let Some(ref mut section) = current_section {
+                        section.raw_content.push('\n');
                     }
                 }
                 _ => {}
@@ -425,10 +419,8 @@ impl HttpClient {
         // Create fallback content if no sections found
         if readme_content.sections.is_empty() {
             let section = Section {
-                title: "README".to_string(),
-                entries: vec![],
                 raw_content: markdown_content.to_string(),
-                entry_count: 0,
+                ..Section::new("README".to_string())
             };
             readme_content.sections.push(section);
         }
@@ -440,26 +432,221 @@ impl HttpClient {
         Ok(readme_content)
     }
 
-    /// Load cached README content
+    /// GitHub topics of `repos` (lowercase `owner/repo`), keyed the same way.
+    ///
+    /// Topics are kept on disk, so only repositories not seen before cost an API
+    /// request. Set `GITHUB_TOKEN` to lift the anonymous rate limit.
+    pub async fn fetch_topics(&self, repos: Vec<String>) -> Result<HashMap<String, Vec<String>>> {
+        let path = self.cache_dir.join(TOPICS_FILE);
+        let mut topics: HashMap<String, Vec<String>> = match fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => HashMap::new(),
+        };
+
+        let missing: Vec<String> = repos
+            .into_iter()
+            .filter(|repo| !topics.contains_key(repo))
+            .collect();
+        if missing.is_empty() {
+            return Ok(topics);
+        }
+
+        let results: Vec<(String, Result<Vec<String>>)> = futures::stream::iter(missing)
+            .map(|repo| async move {
+                let result = self.fetch_repo_topics(&repo).await;
+                (repo, result)
+            })
+            .buffer_unordered(TOPIC_REQUESTS)
+            .collect()
+            .await;
+
+        let mut first_error = None;
+        let mut fetched = 0;
+        for (repo, result) in results {
+            match result {
+                Ok(names) => {
+                    topics.insert(repo, names);
+                    fetched += 1;
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if fetched == 0
+            && let Some(e) = first_error
+        {
+            return Err(e);
+        }
+
+        fs::create_dir_all(&self.cache_dir).await?;
+        fs::write(&path, serde_json::to_string(&topics)?).await?;
+        Ok(topics)
+    }
+
+    async fn fetch_repo_topics(&self, repo: &str) -> Result<Vec<String>> {
+        let mut request = self
+            .client
+            .get(format!("https://api.github.com/repos/{repo}/topics"))
+            .header(USER_AGENT, "awesome-omarchy-tui")
+            .header(ACCEPT, "application/vnd.github+json");
+        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        // Moved or deleted repositories simply have no topics
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let topics: Topics = response.error_for_status()?.json().await?;
+        Ok(topics.names)
+    }
+
+    /// Load cached README content, unless an older version of the parser wrote it
     async fn load_from_cache(&self) -> Result<ReadmeContent> {
-        let cache_path = self.cache_dir.join("readme.json");
+        let cache_path = self.cache_dir.join(README_CACHE);
         let content = fs::read_to_string(cache_path).await?;
-        let readme_content: ReadmeContent = serde_json::from_str(&content)?;
-        Ok(readme_content)
+        let cached: CachedReadme = serde_json::from_str(&content)?;
+        if cached.version != README_CACHE_VERSION {
+            return Err(anyhow!("README cache is from an older version"));
+        }
+        Ok(cached.content)
+    }
+
+    /// Whatever README content the cache holds, whichever version wrote it
+    async fn load_stale_cache(&self) -> Option<ReadmeContent> {
+        let content = fs::read_to_string(self.cache_dir.join(README_CACHE))
+            .await
+            .ok()?;
+        stale_readme(&content)
     }
 
     /// Save README content to cache
     async fn save_to_cache(&self, content: &ReadmeContent) -> Result<()> {
         fs::create_dir_all(&self.cache_dir).await?;
-        let cache_path = self.cache_dir.join("readme.json");
-        let json = serde_json::to_string_pretty(content)?;
+        let cache_path = self.cache_dir.join(README_CACHE);
+        let json = serde_json::to_string_pretty(&CachedReadme {
+            version: README_CACHE_VERSION,
+            content: content.clone(),
+        })?;
         fs::write(cache_path, json).await?;
         Ok(())
     }
 }
 
+/// Parsed README content as cached on disk
+#[derive(Serialize, Deserialize)]
+struct CachedReadme {
+    version: u32,
+    content: ReadmeContent,
+}
+
+/// README content from a cache of any version, with the link kinds older ones lack worked out
+fn stale_readme(json: &str) -> Option<ReadmeContent> {
+    let mut content = serde_json::from_str::<CachedReadme>(json)
+        .map(|cached| cached.content)
+        .or_else(|_| serde_json::from_str::<ReadmeContent>(json))
+        .ok()?;
+    for entry in content
+        .sections
+        .iter_mut()
+        .flat_map(|section| &mut section.entries)
+    {
+        if entry.links.is_empty() {
+            entry.kind = LinkKind::classify(&entry.url);
+            entry.links = vec![Link::new(&entry.url)];
+        }
+    }
+    Some(content)
+}
+
+/// Owner and repository of a `https://github.com/owner/repo` URL
+fn github_repo(url: &str) -> Result<(&str, &str)> {
+    let url_parts: Vec<&str> = url.split('/').collect();
+    if url_parts.len() < 5 || !url.starts_with("https://github.com/") {
+        return Err(anyhow!("Invalid GitHub URL: {url}"));
+    }
+    Ok((url_parts[3], url_parts[4]))
+}
+
+/// A parsed palette under the name and links the README gives its theme
+fn with_entry(theme: Theme, theme_entry: &ThemeEntry) -> Theme {
+    Theme {
+        name: theme_entry.name.clone(),
+        description: theme_entry.description.clone(),
+        source_url: theme_entry.url.clone(),
+        colors: theme.colors,
+    }
+}
+
+impl Http for HttpClient {
+    async fn head(&self, url: &str) -> Result<Reply> {
+        reply(self.probe.head(url).send().await?).await
+    }
+
+    async fn get(&self, url: &str) -> Result<Reply> {
+        let mut request = self.probe.get(url);
+        if url.starts_with("https://api.github.com/") {
+            request = request.header(ACCEPT, "application/vnd.github+json");
+            if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+                request = request.bearer_auth(token);
+            }
+        }
+        reply(request.send().await?).await
+    }
+}
+
+async fn reply(response: reqwest::Response) -> Result<Reply> {
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let status = response.status().as_u16();
+    let rate_limited = matches!(status, 403 | 429)
+        && response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .is_some_and(|remaining| remaining == "0");
+    Ok(Reply {
+        status,
+        location,
+        rate_limited,
+        body: response.text().await?,
+    })
+}
+
 impl Default for HttpClient {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_cache_gets_link_kinds() {
+        // A cache written before entries had link kinds and sections had levels
+        let json = r#"{
+            "sections": [{
+                "title": "Tools",
+                "entries": [{
+                    "title": "walker",
+                    "url": "https://github.com/abenz1267/walker",
+                    "description": "A launcher",
+                    "tags": []
+                }],
+                "raw_content": "",
+                "entry_count": 1
+            }],
+            "search_index": {"terms": {}, "total_terms": 0},
+            "metadata": {"title": "", "description": "", "last_updated": null, "total_entries": 1}
+        }"#;
+        let content = stale_readme(json).unwrap();
+        let entry = &content.sections[0].entries[0];
+        assert_eq!(entry.kind, LinkKind::GitHub);
+        assert_eq!(entry.links, [Link::new(&entry.url)]);
+    }
+}
@@ -0,0 +1,605 @@
+use crate::models::Theme;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+/// An sRGB color as written in theme files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parse `#rrggbb` (the leading `#` is optional)
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |range| u8::from_str_radix(&hex[range], 16).ok();
+        Some(Self::new(channel(0..2)?, channel(2..4)?, channel(4..6)?))
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// CIELAB coordinates under the D65 white point
+    pub fn to_lab(self) -> [f64; 3] {
+        fn f(t: f64) -> f64 {
+            if t > 216.0 / 24389.0 {
+                t.cbrt()
+            } else {
+                (24389.0 / 27.0 * t + 16.0) / 116.0
+            }
+        }
+
+        let [r, g, b] = self.to_linear();
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    /// WCAG relative luminance, from 0 (black) to 1 (white)
+    pub fn relative_luminance(self) -> f64 {
+        let [r, g, b] = self.to_linear();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// WCAG contrast ratio between two colors, from 1:1 up to 21:1
+    pub fn contrast_ratio(self, other: Rgb) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// How this color appears with the given color-vision deficiency
+    pub fn simulate(self, deficiency: Deficiency) -> Rgb {
+        let m = deficiency.matrix();
+        let [r, g, b] = self.to_linear();
+        let mix = |row: [f64; 3]| encode(row[0] * r + row[1] * g + row[2] * b);
+        Rgb::new(mix(m[0]), mix(m[1]), mix(m[2]))
+    }
+
+    fn to_linear(self) -> [f64; 3] {
+        [decode(self.r), decode(self.g), decode(self.b)]
+    }
+
+    /// Hue in degrees, saturation and lightness from 0 to 1
+    pub fn to_hsl(self) -> [f64; 3] {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| f64::from(c) / 255.0);
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let lightness = (max + min) / 2.0;
+        let chroma = max - min;
+        if chroma == 0.0 {
+            return [0.0, 0.0, lightness];
+        }
+
+        let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        };
+        [hue * 60.0, saturation, lightness]
+    }
+
+    /// The inverse of `to_hsl`; hue wraps and saturation and lightness are clamped
+    pub fn from_hsl([hue, saturation, lightness]: [f64; 3]) -> Rgb {
+        let (saturation, lightness) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+        Rgb::new(channel(r), channel(g), channel(b))
+    }
+
+    /// Blend towards `other`; `amount` 0 keeps this color, 1 gives `other`
+    pub fn mix(self, other: Rgb, amount: f64) -> Rgb {
+        let blend =
+            |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * amount).round() as u8;
+        Rgb::new(
+            blend(self.r, other.r),
+            blend(self.g, other.g),
+            blend(self.b, other.b),
+        )
+    }
+
+    /// Perceptual distance (CIE76 ΔE); around 2.3 is barely noticeable
+    pub fn delta_e(self, other: Rgb) -> f64 {
+        lab_distance(self.to_lab(), other.to_lab())
+    }
+}
+
+/// Euclidean distance of two CIELAB colors
+fn lab_distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// sRGB channel to linear light
+fn decode(channel: u8) -> f64 {
+    let c = f64::from(channel) / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Linear light back to an sRGB channel
+fn encode(linear: f64) -> u8 {
+    let linear = linear.clamp(0.0, 1.0);
+    let c = if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+/// Common color-vision deficiencies, simulated at full severity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Deficiency {
+    pub const ALL: [Deficiency; 3] = [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Deficiency::Protanopia => "Protanopia",
+            Deficiency::Deuteranopia => "Deuteranopia",
+            Deficiency::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// Linear-RGB matrices from Machado, Oliveira & Fernandes (2009)
+    fn matrix(&self) -> [[f64; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    /// The theme as seen with this deficiency
+    pub fn simulate_theme(&self, theme: &Theme) -> Theme {
+        let mut simulated = theme.clone();
+        simulated.colors.map_colors(|hex| {
+            Rgb::from_hex(hex)
+                .map(|rgb| rgb.simulate(*self).to_hex())
+                .unwrap_or_else(|| hex.to_string())
+        });
+        simulated
+    }
+}
+
+/// WCAG levels: 4.5:1 for body text (AA), 7:1 enhanced (AAA), 3:1 for large text and UI
+pub const AA_TEXT: f64 = 4.5;
+pub const AAA_TEXT: f64 = 7.0;
+pub const AA_LARGE: f64 = 3.0;
+
+/// Readability grade of a palette against its own background
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContrastGrade {
+    Fail,
+    Aa,
+    Aaa,
+}
+
+impl ContrastGrade {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContrastGrade::Fail => "FAIL",
+            ContrastGrade::Aa => "AA",
+            ContrastGrade::Aaa => "AAA",
+        }
+    }
+}
+
+/// Contrast of the foreground and ANSI colors against a theme's background
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastReport {
+    pub foreground: f64,
+    /// Every ANSI slot except the two blacks, which usually double as backgrounds
+    pub ansi: Vec<(&'static str, f64)>,
+}
+
+impl ContrastReport {
+    pub fn for_theme(theme: &Theme) -> Option<Self> {
+        let background = Rgb::from_hex(&theme.colors.background)?;
+        let foreground = Rgb::from_hex(&theme.colors.foreground)?.contrast_ratio(background);
+        let ansi = theme
+            .colors
+            .slots()
+            .into_iter()
+            .skip(2)
+            .filter(|(slot, _)| !slot.ends_with("black"))
+            .filter_map(|(slot, hex)| Some((slot, Rgb::from_hex(hex)?.contrast_ratio(background))))
+            .collect();
+        Some(Self { foreground, ansi })
+    }
+
+    /// The weakest ratio in the palette
+    pub fn minimum(&self) -> f64 {
+        self.ansi
+            .iter()
+            .map(|(_, ratio)| *ratio)
+            .fold(self.foreground, f64::min)
+    }
+
+    /// AA needs readable body text and distinguishable ANSI colors; AAA raises both bars
+    pub fn grade(&self) -> ContrastGrade {
+        let ansi_minimum = self
+            .ansi
+            .iter()
+            .map(|(_, ratio)| *ratio)
+            .fold(f64::MAX, f64::min);
+        if self.foreground >= AAA_TEXT && ansi_minimum >= AA_TEXT {
+            ContrastGrade::Aaa
+        } else if self.foreground >= AA_TEXT && ansi_minimum >= AA_LARGE {
+            ContrastGrade::Aa
+        } else {
+            ContrastGrade::Fail
+        }
+    }
+}
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColorDepth::TrueColor => "truecolor",
+            ColorDepth::Ansi256 => "256 colors",
+            ColorDepth::Ansi16 => "16 colors",
+        }
+    }
+
+    /// Map a color to the closest one this depth can display
+    pub fn quantize(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => {
+                Color::Indexed(Rgb::new(r, g, b).to_ansi256())
+            }
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => {
+                Color::Indexed(Rgb::new(r, g, b).to_ansi16())
+            }
+            // Indexed colors beyond the first 16 are not available either
+            (ColorDepth::Ansi16, Color::Indexed(index)) if index >= 16 => {
+                Color::Indexed(xterm_color(index).to_ansi16())
+            }
+            _ => color,
+        }
+    }
+}
+
+/// xterm's default colors for the 16 ANSI slots
+const ANSI16: [Rgb; 16] = [
+    Rgb::new(0x00, 0x00, 0x00),
+    Rgb::new(0xcd, 0x00, 0x00),
+    Rgb::new(0x00, 0xcd, 0x00),
+    Rgb::new(0xcd, 0xcd, 0x00),
+    Rgb::new(0x00, 0x00, 0xee),
+    Rgb::new(0xcd, 0x00, 0xcd),
+    Rgb::new(0x00, 0xcd, 0xcd),
+    Rgb::new(0xe5, 0xe5, 0xe5),
+    Rgb::new(0x7f, 0x7f, 0x7f),
+    Rgb::new(0xff, 0x00, 0x00),
+    Rgb::new(0x00, 0xff, 0x00),
+    Rgb::new(0xff, 0xff, 0x00),
+    Rgb::new(0x5c, 0x5c, 0xff),
+    Rgb::new(0xff, 0x00, 0xff),
+    Rgb::new(0x00, 0xff, 0xff),
+    Rgb::new(0xff, 0xff, 0xff),
+];
+
+/// Channel levels of the 6x6x6 color cube (indices 16-231)
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The color xterm shows for a 256-color palette index
+fn xterm_color(index: u8) -> Rgb {
+    match index {
+        0..=15 => ANSI16[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            Rgb::new(
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (index - 232);
+            Rgb::new(level, level, level)
+        }
+    }
+}
+
+impl Rgb {
+    /// Nearest entry of the xterm color cube or grayscale ramp.
+    ///
+    /// The first 16 entries are skipped since terminals redefine them freely.
+    pub fn to_ansi256(self) -> u8 {
+        let level = |v: u8| match v {
+            0..48 => 0,
+            48..115 => 1,
+            _ => (v - 35) / 40,
+        };
+        let cube = 16 + 36 * level(self.r) + 6 * level(self.g) + level(self.b);
+
+        let average = (u16::from(self.r) + u16::from(self.g) + u16::from(self.b)) / 3;
+        let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+        self.nearest([cube, gray].into_iter(), xterm_color)
+            .unwrap_or(cube)
+    }
+
+    /// Nearest of the 16 ANSI colors, judged against xterm's defaults
+    pub fn to_ansi16(self) -> u8 {
+        self.nearest(0..16u8, |index| ANSI16[index as usize])
+            .unwrap_or(0)
+    }
+
+    /// The palette index whose color is closest to this one
+    fn nearest(self, indices: impl Iterator<Item = u8>, color: impl Fn(u8) -> Rgb) -> Option<u8> {
+        let lab = self.to_lab();
+        indices
+            .map(|index| (index, lab_distance(lab, color(index).to_lab())))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+}
+
+impl From<Rgb> for Color {
+    fn from(rgb: Rgb) -> Self {
+        Color::Rgb(rgb.r, rgb.g, rgb.b)
+    }
+}
+
+/// How one palette slot differs between two themes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotDiff {
+    pub slot: &'static str,
+    pub left: String,
+    pub right: String,
+    /// Per-channel change from left to right, when both colors parse
+    pub channels: Option<[i16; 3]>,
+    pub delta_e: Option<f64>,
+}
+
+/// Compare every palette slot of two themes
+pub fn diff_themes(left: &Theme, right: &Theme) -> Vec<SlotDiff> {
+    left.colors
+        .slots()
+        .into_iter()
+        .zip(right.colors.slots())
+        .map(|((slot, left_hex), (_, right_hex))| {
+            let parsed = Rgb::from_hex(left_hex).zip(Rgb::from_hex(right_hex));
+            SlotDiff {
+                slot,
+                left: left_hex.to_string(),
+                right: right_hex.to_string(),
+                channels: parsed.map(|(a, b)| {
+                    [
+                        i16::from(b.r) - i16::from(a.r),
+                        i16::from(b.g) - i16::from(a.g),
+                        i16::from(b.b) - i16::from(a.b),
+                    ]
+                }),
+                delta_e: parsed.map(|(a, b)| a.delta_e(b)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ThemeApplicator, ThemeEntry};
+    use crate::tasks::{AppMessage, TaskManager};
+    use crate::{App, HttpClient};
+
+    #[test]
+    fn test_hex_parsing() {
+        assert_eq!(Rgb::from_hex("#1a1b26"), Some(Rgb::new(0x1a, 0x1b, 0x26)));
+        assert_eq!(Rgb::from_hex("FFFFFF"), Some(Rgb::new(255, 255, 255)));
+        assert_eq!(Rgb::from_hex("#fff"), None);
+        assert_eq!(Rgb::from_hex("#ggggggg"), None);
+    }
+
+    #[test]
+    fn test_hsl_round_trips() {
+        for hex in [
+            "#1a1b26", "#f7768e", "#9ece6a", "#7aa2f7", "#ffffff", "#000000",
+        ] {
+            let color = Rgb::from_hex(hex).unwrap();
+            assert_eq!(Rgb::from_hsl(color.to_hsl()), color, "{hex}");
+        }
+        assert_eq!(Rgb::from_hsl([360.0, 1.0, 0.5]), Rgb::new(255, 0, 0));
+        assert_eq!(Rgb::from_hsl([120.0, 2.0, 0.5]), Rgb::new(0, 255, 0));
+    }
+
+    #[test]
+    fn test_delta_e() {
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+        assert!(black.delta_e(black) < f64::EPSILON);
+        assert!((black.delta_e(white) - 100.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_contrast_ratio_matches_wcag_reference_values() {
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+        assert!((black.contrast_ratio(white) - 21.0).abs() < 1e-9);
+        assert!((white.contrast_ratio(white) - 1.0).abs() < 1e-9);
+        // #777777 on white is the classic "just fails AA" gray
+        let gray = Rgb::from_hex("#777777").unwrap();
+        assert!((gray.contrast_ratio(white) - 4.48).abs() < 0.01);
+        assert_eq!(gray.contrast_ratio(white), white.contrast_ratio(gray));
+    }
+
+    #[test]
+    fn test_contrast_report_grades_palettes() {
+        let theme = crate::models::ThemeApplicator::create_default_theme();
+        let report = ContrastReport::for_theme(&theme).unwrap();
+        assert_eq!(report.ansi.len(), 14);
+        assert!(report.minimum() <= report.foreground);
+
+        let mut washed_out = theme.clone();
+        washed_out.colors.foreground = washed_out.colors.background.clone();
+        let report = ContrastReport::for_theme(&washed_out).unwrap();
+        assert_eq!(report.grade(), ContrastGrade::Fail);
+
+        let mut stark = theme;
+        stark.colors.map_colors(|_| "#ffffff".to_string());
+        stark.colors.background = "#000000".to_string();
+        let report = ContrastReport::for_theme(&stark).unwrap();
+        assert_eq!(report.grade(), ContrastGrade::Aaa);
+    }
+
+    #[test]
+    fn test_color_vision_simulation() {
+        let red = Rgb::new(255, 0, 0);
+        let green = Rgb::new(0, 255, 0);
+        // Grays are unaffected by every deficiency
+        for deficiency in Deficiency::ALL {
+            assert_eq!(
+                Rgb::new(128, 128, 128).simulate(deficiency),
+                Rgb::new(128, 128, 128)
+            );
+        }
+        // Red and green collapse towards each other for red-green deficiencies
+        let before = red.delta_e(green);
+        let after = red
+            .simulate(Deficiency::Deuteranopia)
+            .delta_e(green.simulate(Deficiency::Deuteranopia));
+        assert!(after < before / 2.0);
+    }
+
+    #[test]
+    fn test_quantization() {
+        // Exact cube and gray entries map to themselves
+        assert_eq!(Rgb::new(255, 0, 0).to_ansi256(), 196);
+        assert_eq!(Rgb::new(95, 135, 175).to_ansi256(), 67);
+        assert_eq!(Rgb::new(128, 128, 128).to_ansi256(), 244);
+        assert_eq!(Rgb::new(0, 0, 0).to_ansi256(), 16);
+        assert_eq!(xterm_color(67), Rgb::new(95, 135, 175));
+
+        assert_eq!(Rgb::new(250, 10, 10).to_ansi16(), 9);
+        assert_eq!(Rgb::new(0x1a, 0x1b, 0x26).to_ansi16(), 0);
+        assert_eq!(Rgb::new(0xe0, 0xe0, 0xe0).to_ansi16(), 7);
+
+        assert_eq!(
+            ColorDepth::Ansi16.quantize(Color::Indexed(196)),
+            Color::Indexed(9)
+        );
+        assert_eq!(
+            ColorDepth::TrueColor.quantize(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+        assert_eq!(ColorDepth::Ansi256.quantize(Color::Blue), Color::Blue);
+    }
+
+    #[test]
+    fn test_diff_themes_reports_changed_slots() {
+        let mut left = crate::models::ThemeApplicator::create_default_theme();
+        let mut right = left.clone();
+        right.colors.normal.red = "#ff0000".to_string();
+        left.colors.normal.red = "#fe0000".to_string();
+
+        let diff = diff_themes(&left, &right);
+        assert_eq!(diff.len(), 18);
+        let red = diff.iter().find(|d| d.slot == "red").unwrap();
+        assert_eq!(red.channels, Some([1, 0, 0]));
+        assert!(red.delta_e.unwrap() > 0.0);
+        assert!(
+            diff.iter()
+                .filter(|d| d.slot != "red")
+                .all(|d| d.delta_e == Some(0.0))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_contrast_sort_follows_loaded_themes() {
+        let cache = tempfile::tempdir().unwrap();
+        let client = HttpClient::with_theme_stub(cache.path().to_path_buf(), |_| {
+            Ok(ThemeApplicator::create_default_theme())
+        });
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = App::new(client, tasks);
+        let themes = ["Dim", "Stark", "Unknown"].map(|name| ThemeEntry {
+            name: name.to_string(),
+            url: format!("https://github.com/user/{}-theme", name.to_lowercase()),
+            description: String::new(),
+        });
+        app.handle_message(AppMessage::ThemeList(Ok(themes.into())));
+
+        let mut dim = ThemeApplicator::create_default_theme();
+        dim.colors.foreground = dim.colors.background.clone();
+        let mut stark = ThemeApplicator::create_default_theme();
+        stark.colors.map_colors(|_| "#ffffff".to_string());
+        stark.colors.background = "#000000".to_string();
+        app.handle_message(AppMessage::ThemesPrefetched(vec![
+            ("Dim".to_string(), Box::new(dim)),
+            ("Stark".to_string(), Box::new(stark)),
+        ]));
+        assert!(app.theme_contrast("Stark").unwrap().minimum() > 20.0);
+
+        app.theme_browser.sort_by_contrast = true;
+        assert_eq!(app.visible_theme_indices(), [1, 0, 2]);
+        app.theme_browser.min_contrast = Some(4.5);
+        assert_eq!(app.visible_theme_indices(), [1, 2]);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::color::ColorDepth;
+use crate::models::Theme;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User settings persisted between sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Theme used for the whole interface; terminal colors when unset
+    pub color_scheme: Option<Theme>,
+    /// Overrides the detected color depth: "truecolor", "ansi256" or "ansi16"
+    pub color_depth: Option<ColorDepth>,
+    /// File shown in the theme preview's code editor next to the built-in samples
+    pub sample_file: Option<PathBuf>,
+    /// Preview themes as the cursor moves over them instead of waiting for Enter
+    pub live_preview: bool,
+    /// Taxonomy of tags and aliases; `tags.toml` next to this file, else the built-in one
+    pub tags_file: Option<PathBuf>,
+    /// Also tag entries with their repositories' GitHub topics
+    pub github_topics: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            color_scheme: None,
+            color_depth: None,
+            sample_file: None,
+            live_preview: true,
+            tags_file: None,
+            github_topics: false,
+        }
+    }
+}
+
+impl Config {
+    /// `~/.config/awesome-omarchy-tui/config.toml` on Linux
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("awesome-omarchy-tui").join("config.toml"))
+    }
+
+    /// Load the config, falling back to defaults when the file does not exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ThemeColorPalette, ThemeColors};
+
+    fn palette(hex: &str) -> ThemeColorPalette {
+        ThemeColorPalette {
+            black: hex.to_string(),
+            red: hex.to_string(),
+            green: hex.to_string(),
+            yellow: hex.to_string(),
+            blue: hex.to_string(),
+            magenta: hex.to_string(),
+            cyan: hex.to_string(),
+            white: hex.to_string(),
+        }
+    }
+
+    fn nord() -> Theme {
+        Theme {
+            name: "Nord".to_string(),
+            colors: ThemeColors {
+                background: "#2e3440".to_string(),
+                foreground: "#d8dee9".to_string(),
+                normal: palette("#88c0d0"),
+                bright: palette("#8fbcbb"),
+            },
+            source_url: "https://github.com/user/omarchy-nord-theme".to_string(),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_color_scheme_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.toml");
+        assert!(Config::load(&path).unwrap().color_scheme.is_none());
+
+        let config = Config {
+            color_scheme: Some(nord()),
+            color_depth: Some(ColorDepth::Ansi256),
+            sample_file: None,
+            live_preview: true,
+            tags_file: None,
+            github_topics: false,
+        };
+        config.save(&path).unwrap();
+
+        let loaded = Config::load(&path).unwrap().color_scheme.unwrap();
+        assert_eq!(loaded.name, "Nord");
+        assert_eq!(loaded.colors.background, "#2e3440");
+        assert_eq!(loaded.colors.bright.cyan, "#8fbcbb");
+        assert_eq!(
+            Config::load(&path).unwrap().color_depth,
+            Some(ColorDepth::Ansi256)
+        );
+    }
+
+    #[test]
+    fn test_live_preview_defaults_on() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        // Configs written before the setting existed keep previewing as the cursor moves
+        fs::write(&path, "sample_file = \"init.lua\"\n").unwrap();
+        assert!(Config::load(&path).unwrap().live_preview);
+
+        Config {
+            live_preview: false,
+            ..Config::default()
+        }
+        .save(&path)
+        .unwrap();
+        assert!(!Config::load(&path).unwrap().live_preview);
+    }
+
+    #[tokio::test]
+    async fn test_saved_color_scheme_themes_the_interface() {
+        use crate::tasks::TaskManager;
+        use crate::{App, HttpClient};
+        use ratatui::style::Color;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        Config {
+            color_scheme: Some(nord()),
+            ..Config::default()
+        }
+        .save(&path)
+        .unwrap();
+
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        app.load_config(path).unwrap();
+        assert!(app.is_theme_applied());
+
+        // Closing the theme browser restores the saved scheme rather than clearing it
+        app.close_theme_browser();
+        assert!(app.is_theme_applied());
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| crate::ui::draw(f, &mut app)).unwrap();
+        let corner = &terminal.backend().buffer()[(0, 0)];
+        assert_eq!(corner.bg, Color::Rgb(0x2e, 0x34, 0x40));
+    }
+}
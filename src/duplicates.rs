@@ -0,0 +1,304 @@
+use crate::checker::LinkStatus;
+use crate::links;
+use crate::models::ReadmeContent;
+use std::collections::HashMap;
+
+/// Share of matching characters from which two titles count as the same project
+const SIMILAR_TITLE: f64 = 0.9;
+
+/// Shortest normalized title compared fuzzily; short names differ by one letter too easily
+const MIN_FUZZY_LENGTH: usize = 5;
+
+/// Why entries were grouped together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateReason {
+    SameUrl,
+    SimilarTitle,
+}
+
+impl DuplicateReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            DuplicateReason::SameUrl => "same URL",
+            DuplicateReason::SimilarTitle => "similar titles",
+        }
+    }
+}
+
+/// Entries that look like the same project, as `(section, entry)` indices in README order
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub reason: DuplicateReason,
+    pub members: Vec<(usize, usize)>,
+}
+
+impl DuplicateGroup {
+    /// Sections other members of the group are listed in, besides `section`
+    pub fn other_sections(&self, section: usize) -> Vec<usize> {
+        let mut others: Vec<usize> = self
+            .members
+            .iter()
+            .map(|&(other, _)| other)
+            .filter(|&other| other != section)
+            .collect();
+        others.dedup();
+        others
+    }
+}
+
+/// URLs compare by repository when they have one, so trailing slashes or `.git` do not matter
+pub fn url_key(url: &str) -> String {
+    match links::repo_path(url) {
+        Some(path) => format!("{}/{}", links::host(url).unwrap_or_default(), path),
+        None => url.trim_end_matches('/').to_lowercase(),
+    }
+    .trim_end_matches(".git")
+    .to_lowercase()
+}
+
+/// Whether two titles name the same project, ignoring case, spacing and punctuation
+pub fn similar_titles(a: &str, b: &str) -> bool {
+    let (a, b) = (title_key(a), title_key(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+    let longest = a.chars().count().max(b.chars().count());
+    if a.chars().count().min(b.chars().count()) < MIN_FUZZY_LENGTH {
+        return false;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / longest as f64 >= SIMILAR_TITLE
+}
+
+fn title_key(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Group entries sharing a URL or a near-identical title
+///
+/// Checked links count under their new location, so a repository listed under both
+/// its old and new name is caught once the link check has run.
+pub fn find_duplicates(
+    readme: &ReadmeContent,
+    statuses: &HashMap<String, LinkStatus>,
+) -> Vec<DuplicateGroup> {
+    let entries: Vec<((usize, usize), &str, String)> = readme
+        .sections
+        .iter()
+        .enumerate()
+        .flat_map(|(s, section)| {
+            section.entries.iter().enumerate().map(move |(e, entry)| {
+                let url = statuses
+                    .get(&entry.url)
+                    .and_then(LinkStatus::new_location)
+                    .unwrap_or(&entry.url);
+                ((s, e), entry.title.as_str(), url_key(url))
+            })
+        })
+        .collect();
+
+    let mut parent: Vec<usize> = (0..entries.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut by_url: HashMap<&str, usize> = HashMap::new();
+    for (i, (_, _, key)) in entries.iter().enumerate() {
+        if let Some(&first) = by_url.get(key.as_str()) {
+            let (a, b) = (root(&mut parent, first), root(&mut parent, i));
+            parent[b] = a;
+        } else {
+            by_url.insert(key, i);
+        }
+    }
+    for i in 0..entries.len() {
+        for j in i + 1..entries.len() {
+            if root(&mut parent, i) != root(&mut parent, j)
+                && similar_titles(entries[i].1, entries[j].1)
+            {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[b] = a;
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for i in 0..entries.len() {
+        let r = root(&mut parent, i);
+        match groups.iter_mut().find(|(group_root, _)| *group_root == r) {
+            Some((_, members)) => members.push(i),
+            None => groups.push((r, vec![i])),
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(_, members)| {
+            let first_key = &entries[members[0]].2;
+            let reason = if members.iter().all(|&m| &entries[m].2 == first_key) {
+                DuplicateReason::SameUrl
+            } else {
+                DuplicateReason::SimilarTitle
+            };
+            DuplicateGroup {
+                reason,
+                members: members.iter().map(|&m| entries[m].0).collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::App;
+    use crate::models::{FocusArea, ReadmeContent, RepositoryEntry, Section, SidebarView};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn section(title: &str, entries: &[(&str, &str)]) -> Section {
+        let mut section = Section::new(title.to_string());
+        section.entries = entries
+            .iter()
+            .map(|(name, url)| RepositoryEntry::sample(name, url, ""))
+            .collect();
+        section
+    }
+
+    #[test]
+    fn test_similar_titles() {
+        assert!(similar_titles("Hyprland Dots", "hyprland-dots"));
+        assert!(similar_titles("omarchy-themes", "omarchy-theme"));
+        assert!(!similar_titles("catppuccin-latte", "catppuccin-mocha"));
+        assert!(!similar_titles("nord", "norde"));
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_groups_by_url_title_and_redirect() {
+        let readme = ReadmeContent {
+            sections: vec![
+                section(
+                    "Tools",
+                    &[
+                        ("walker", "https://github.com/a/walker"),
+                        ("Hypr Dots", "https://github.com/a/dots"),
+                        ("old-name", "https://github.com/a/old-name"),
+                        ("unique", "https://github.com/a/unique"),
+                    ],
+                ),
+                section(
+                    "Themes",
+                    &[
+                        ("Walker launcher", "https://github.com/A/walker/"),
+                        ("hypr-dots", "https://gitlab.com/b/dots"),
+                        ("new-name", "https://github.com/a/new-name"),
+                    ],
+                ),
+            ],
+            ..ReadmeContent::default()
+        };
+
+        let groups = find_duplicates(&readme, &HashMap::new());
+        assert_eq!(
+            groups,
+            [
+                DuplicateGroup {
+                    reason: DuplicateReason::SameUrl,
+                    members: vec![(0, 0), (1, 0)],
+                },
+                DuplicateGroup {
+                    reason: DuplicateReason::SimilarTitle,
+                    members: vec![(0, 1), (1, 1)],
+                },
+            ]
+        );
+        assert_eq!(groups[0].other_sections(0), [1]);
+
+        // Once checked, the old name redirects to the new one
+        let statuses = HashMap::from([(
+            "https://github.com/a/old-name".to_string(),
+            LinkStatus::Moved {
+                to: "https://github.com/a/new-name".to_string(),
+            },
+        )]);
+        let groups = find_duplicates(&readme, &statuses);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[2].members, [(0, 2), (1, 2)]);
+        assert_eq!(groups[2].reason, DuplicateReason::SameUrl);
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_view_jumps_between_sections() {
+        let mut tools = section(
+            "Tools",
+            &[
+                ("alpha", "https://github.com/a/alpha"),
+                ("walker", "https://github.com/a/walker"),
+            ],
+        );
+        tools.entry_count = tools.entries.len();
+        let mut launchers = section("Launchers", &[("Walker", "https://github.com/a/walker")]);
+        launchers.entry_count = launchers.entries.len();
+        let mut app = App::with_readme(ReadmeContent {
+            sections: vec![tools, launchers],
+            ..ReadmeContent::default()
+        });
+        assert_eq!(app.duplicates.groups.len(), 1);
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        app.handle_key_event(key(KeyCode::Char('D'))).await.unwrap();
+        assert_eq!(app.sidebar_view, SidebarView::Duplicates);
+        app.handle_key_event(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(
+            (
+                app.current_tab,
+                app.tabs[app.current_tab].list_state.selected_index
+            ),
+            (0, Some(1))
+        );
+
+        // Each entry is marked with the other section listing it
+        assert!(crate::ui::render_to_string(&mut app).contains("also in Launchers"));
+
+        app.focus_area = FocusArea::Sidebar;
+        app.handle_key_event(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(
+            (
+                app.current_tab,
+                app.tabs[app.current_tab].list_state.selected_index
+            ),
+            (1, Some(0))
+        );
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .starts_with("Duplicate 2/2 (same URL)")
+        );
+    }
+}
@@ -1,45 +1,315 @@
-use anyhow::Result;
-use crossterm::event::{self, Event};
+use crate::tasks::TaskMessage;
+use anyhow::{Result, anyhow};
+use crossterm::event::{Event, EventStream, KeyEvent, MouseEvent};
+use futures::{Stream, StreamExt};
+use std::io;
+use std::pin::Pin;
 use std::time::Duration;
-use tokio::time::timeout;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{Interval, MissedTickBehavior, interval};
 
-pub struct EventHandler {
-    #[allow(dead_code)]
-    tick_rate: Duration,
+/// Everything the main loop reacts to, from the terminal or from background tasks
+#[derive(Debug)]
+pub enum AppEvent {
+    Tick,
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Resize(u16, u16),
+    Paste(String),
+    Focus(bool),
+    Message(TaskMessage),
 }
 
-impl Default for EventHandler {
-    fn default() -> Self {
-        Self::new()
+impl From<Event> for AppEvent {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::Key(key) => AppEvent::Key(key),
+            Event::Mouse(mouse) => AppEvent::Mouse(mouse),
+            Event::Resize(width, height) => AppEvent::Resize(width, height),
+            Event::Paste(text) => AppEvent::Paste(text),
+            Event::FocusGained => AppEvent::Focus(true),
+            Event::FocusLost => AppEvent::Focus(false),
+        }
     }
 }
 
+type TerminalEvents = Pin<Box<dyn Stream<Item = io::Result<Event>> + Send>>;
+
+/// Merges terminal input, background task messages and animation ticks
+pub struct EventHandler {
+    terminal_events: TerminalEvents,
+    messages: UnboundedReceiver<TaskMessage>,
+    tick: Interval,
+    ticking: bool,
+}
+
 impl EventHandler {
-    pub fn new() -> Self {
+    /// Read terminal input through crossterm's async event stream
+    pub fn new(messages: UnboundedReceiver<TaskMessage>) -> Self {
+        Self::from_stream(EventStream::new(), messages, Duration::from_millis(100))
+    }
+
+    /// Build a handler over any event source, e.g. a synthetic sequence in tests
+    pub fn from_stream<S>(
+        terminal_events: S,
+        messages: UnboundedReceiver<TaskMessage>,
+        tick_rate: Duration,
+    ) -> Self
+    where
+        S: Stream<Item = io::Result<Event>> + Send + 'static,
+    {
+        let mut tick = interval(tick_rate);
+        tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
         Self {
-            tick_rate: Duration::from_millis(250),
+            terminal_events: Box::pin(terminal_events),
+            messages,
+            tick,
+            ticking: false,
+        }
+    }
+
+    /// Feed a fixed sequence of terminal events, as a test harness would
+    #[cfg(test)]
+    pub fn scripted(events: Vec<Event>, messages: UnboundedReceiver<TaskMessage>) -> Self {
+        Self::from_stream(
+            futures::stream::iter(events.into_iter().map(Ok)),
+            messages,
+            Duration::from_millis(100),
+        )
+    }
+
+    /// Ticks are only produced while something is animating, so idle costs nothing
+    pub fn set_ticking(&mut self, ticking: bool) {
+        if ticking && !self.ticking {
+            self.tick.reset();
         }
+        self.ticking = ticking;
     }
 
-    pub async fn next(&mut self) -> Result<Event> {
-        // Poll for events with a timeout to prevent blocking indefinitely
-        let event = timeout(Duration::from_millis(100), async {
-            loop {
-                if event::poll(Duration::from_millis(0))? {
-                    return Ok(event::read()?);
-                }
-                tokio::task::yield_now().await;
+    /// Wait for the next event; errors once the terminal stream has closed
+    pub async fn next(&mut self) -> Result<AppEvent> {
+        tokio::select! {
+            // Input first, so a scripted sequence is replayed deterministically
+            biased;
+            event = self.terminal_events.next() => match event {
+                Some(event) => Ok(event?.into()),
+                None => Err(anyhow!("Terminal event stream closed")),
+            },
+            Some(message) = self.messages.recv() => Ok(AppEvent::Message(message)),
+            _ = self.tick.tick(), if self.ticking => Ok(AppEvent::Tick),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FocusArea, ReadmeContent, RepositoryEntry, Section};
+    use crate::tasks::{AppMessage, TaskManager};
+    use crate::{App, HttpClient};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn sample_readme() -> ReadmeContent {
+        let mut section = Section::new("Tools".to_string());
+        for name in ["one", "two", "three"] {
+            let url = format!("https://github.com/user/{name}");
+            section
+                .entries
+                .push(RepositoryEntry::sample(name, &url, ""));
+        }
+        section.entry_count = section.entries.len();
+        ReadmeContent {
+            sections: vec![section],
+            ..ReadmeContent::default()
+        }
+    }
+
+    /// An app showing `content`, with the receiving end of its task messages
+    fn app_with(content: ReadmeContent) -> (App, UnboundedReceiver<TaskMessage>) {
+        let (tasks, messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        app.handle_message(AppMessage::Readme(Ok(content)));
+        (app, messages)
+    }
+
+    /// Run the app against a scripted handler until the script runs out
+    async fn drive(app: &mut App, events: &mut EventHandler) -> usize {
+        let mut redraws = 0;
+        while let Ok(event) = events.next().await {
+            if app.handle_event(event).await.unwrap() {
+                redraws += 1;
             }
+        }
+        redraws
+    }
+
+    #[tokio::test]
+    async fn test_terminal_events_are_translated() {
+        let (_tasks, messages) = TaskManager::channel();
+        let mut events = EventHandler::scripted(
+            vec![
+                key(KeyCode::Char('j')),
+                Event::Resize(80, 24),
+                Event::Paste("omarchy".to_string()),
+                Event::FocusLost,
+            ],
+            messages,
+        );
+
+        assert!(matches!(events.next().await, Ok(AppEvent::Key(_))));
+        assert!(matches!(events.next().await, Ok(AppEvent::Resize(80, 24))));
+        assert!(matches!(events.next().await, Ok(AppEvent::Paste(ref text)) if text == "omarchy"));
+        assert!(matches!(events.next().await, Ok(AppEvent::Focus(false))));
+    }
+
+    #[tokio::test]
+    async fn test_idle_handler_does_not_tick() {
+        let (_tasks, messages) = TaskManager::channel();
+        let mut events = EventHandler::from_stream(
+            futures::stream::pending(),
+            messages,
+            Duration::from_millis(1),
+        );
+
+        let idle = tokio::time::timeout(Duration::from_millis(20), events.next()).await;
+        assert!(idle.is_err());
+
+        events.set_ticking(true);
+        assert!(matches!(events.next().await, Ok(AppEvent::Tick)));
+    }
+
+    #[tokio::test]
+    async fn test_scripted_session_navigates_entries() {
+        let (mut app, messages) = app_with(sample_readme());
+
+        let mut events = EventHandler::scripted(
+            vec![
+                key(KeyCode::Char('j')),
+                key(KeyCode::Char('j')),
+                key(KeyCode::Char('k')),
+                Event::FocusLost,
+            ],
+            messages,
+        );
+        let redraws = drive(&mut app, &mut events).await;
+
+        assert_eq!(redraws, 3);
+        assert_eq!(app.tabs[0].list_state.selected_index, Some(1));
+    }
+
+    fn click(column: u16, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind: crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
         })
-        .await;
+    }
 
-        match event {
-            Ok(event_result) => event_result,
-            Err(_) => {
-                // Timeout occurred, return a dummy event
-                // This allows the main loop to continue and handle other async tasks
-                Ok(Event::Resize(0, 0)) // This will be filtered out in the main loop
-            }
+    #[tokio::test]
+    async fn test_mouse_clicks_hit_drawn_regions() {
+        let mut readme = sample_readme();
+        readme.sections.push(Section::new("Guides".to_string()));
+        let (mut app, messages) = app_with(readme);
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| crate::ui::draw(f, &mut app)).unwrap();
+        let third_entry = app.layout.entries[2].area;
+        let second_tab = app.layout.tabs[1].area;
+
+        // Each hyperlink starts on the first character of its URL, selected or not
+        for (area, url) in &app.layout.hyperlinks {
+            let first = crate::links::display(url).chars().next().unwrap();
+            let cell = &terminal.backend().buffer()[(area.x, area.y)];
+            assert_eq!(cell.symbol(), first.to_string());
         }
+        assert_eq!(app.layout.hyperlinks.len(), 3);
+
+        let mut events = EventHandler::scripted(
+            vec![
+                click(third_entry.x + 2, third_entry.y),
+                click(second_tab.x + 2, second_tab.y),
+            ],
+            messages,
+        );
+        app.handle_event(events.next().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(app.tabs[0].list_state.selected_index, Some(2));
+
+        app.handle_event(events.next().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(app.current_tab, 1);
+        assert_eq!(app.focus_area, FocusArea::Sidebar);
+    }
+
+    #[tokio::test]
+    async fn test_live_preview_loads_where_the_cursor_rests() {
+        use crate::models::{PreviewState, ThemeApplicator, ThemeEntry};
+
+        let cache = tempfile::tempdir().unwrap();
+        let client = HttpClient::with_theme_stub(cache.path().to_path_buf(), |entry| {
+            Ok(crate::models::Theme {
+                name: entry.name.clone(),
+                ..ThemeApplicator::create_default_theme()
+            })
+        });
+        let (tasks, messages) = TaskManager::channel();
+        let mut app = App::new(client, tasks);
+        app.theme_browser_mode = true;
+        let themes = ["Alpha", "Beta", "Gamma"].map(|name| ThemeEntry {
+            name: name.to_string(),
+            url: format!("https://github.com/user/{}-theme", name.to_lowercase()),
+            description: String::new(),
+        });
+        app.handle_message(AppMessage::ThemeList(Ok(themes.into())));
+
+        // Both moves land well within the debounce
+        let mut events = EventHandler::scripted(
+            vec![key(KeyCode::Char('j')), key(KeyCode::Char('j'))],
+            messages,
+        );
+        drive(&mut app, &mut events).await;
+        assert!(matches!(&app.preview_state, PreviewState::Loading(name) if name == "Gamma"));
+
+        // Beta's fetch was cancelled while it waited, so Gamma's colors come first
+        let loaded = loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), events.messages.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let AppMessage::ThemeColors { name, .. } = message.message {
+                break name;
+            }
+        };
+        assert_eq!(loaded, "Gamma");
+
+        // Clicking a theme previews it just like moving the cursor there
+        crate::ui::render_to_string(&mut app);
+        let first = app.layout.popup_items[0].area;
+        app.handle_event(click(first.x + 2, first.y).into())
+            .await
+            .unwrap();
+        assert!(matches!(&app.preview_state, PreviewState::Loading(name) if name == "Alpha"));
+    }
+
+    #[tokio::test]
+    async fn test_paste_fills_search_query() {
+        let (mut app, messages) = app_with(sample_readme());
+
+        let mut events = EventHandler::scripted(
+            vec![key(KeyCode::Char('/')), Event::Paste("two".to_string())],
+            messages,
+        );
+        drive(&mut app, &mut events).await;
+
+        assert_eq!(app.search_query, "two");
     }
 }
@@ -0,0 +1,456 @@
+use crate::client::HttpClient;
+use crate::color::Rgb;
+use crate::models::{Theme, ThemeColorPalette};
+use crate::parser::{ThemeFormat, ThemeParser};
+use anyhow::{Context, Result, anyhow};
+use clap::ValueEnum;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Config formats a theme's palette can be written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Alacritty,
+    Kitty,
+    Foot,
+    Ghostty,
+    Wezterm,
+    Xresources,
+    Base16,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 7] = [
+        ExportFormat::Alacritty,
+        ExportFormat::Kitty,
+        ExportFormat::Foot,
+        ExportFormat::Ghostty,
+        ExportFormat::Wezterm,
+        ExportFormat::Xresources,
+        ExportFormat::Base16,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Alacritty => "Alacritty",
+            ExportFormat::Kitty => "kitty",
+            ExportFormat::Foot => "foot",
+            ExportFormat::Ghostty => "Ghostty",
+            ExportFormat::Wezterm => "WezTerm",
+            ExportFormat::Xresources => "Xresources",
+            ExportFormat::Base16 => "base16",
+        }
+    }
+
+    /// File name used when exporting every format into one directory
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ExportFormat::Alacritty => "alacritty.toml",
+            ExportFormat::Kitty => "kitty.conf",
+            ExportFormat::Foot => "foot.ini",
+            ExportFormat::Ghostty => "ghostty",
+            ExportFormat::Wezterm => "wezterm.toml",
+            ExportFormat::Xresources => "Xresources",
+            ExportFormat::Base16 => "base16.yaml",
+        }
+    }
+}
+
+/// `#rrggbb` in lowercase, or the color as written when it is not plain hex
+fn hex(color: &str) -> String {
+    Rgb::from_hex(color)
+        .map(Rgb::to_hex)
+        .unwrap_or_else(|| color.to_string())
+}
+
+/// Hex without the leading `#`, as foot and base16 expect
+fn bare(color: &str) -> String {
+    hex(color).trim_start_matches('#').to_string()
+}
+
+/// The 16 ANSI colors, normal then bright
+fn ansi(theme: &Theme) -> Vec<&str> {
+    theme
+        .colors
+        .normal
+        .colors()
+        .into_iter()
+        .chain(theme.colors.bright.colors())
+        .collect()
+}
+
+fn escape_quotes(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Render the theme's palette in the given format
+pub fn export(theme: &Theme, format: ExportFormat) -> String {
+    let colors = &theme.colors;
+    let mut out = String::new();
+    let comment = if format == ExportFormat::Xresources {
+        "!"
+    } else {
+        "#"
+    };
+    let _ = writeln!(out, "{comment} {}", theme.name);
+    if theme.source_url.starts_with("http") {
+        let _ = writeln!(out, "{comment} {}", theme.source_url);
+    }
+    out.push('\n');
+
+    match format {
+        ExportFormat::Alacritty => {
+            let _ = writeln!(out, "[colors.primary]");
+            let _ = writeln!(out, "background = \"{}\"", hex(&colors.background));
+            let _ = writeln!(out, "foreground = \"{}\"", hex(&colors.foreground));
+            for (table, palette) in [("normal", &colors.normal), ("bright", &colors.bright)] {
+                let _ = writeln!(out, "\n[colors.{table}]");
+                for ((name, _), color) in ThemeColorPalette::NAMES.iter().zip(palette.colors()) {
+                    let _ = writeln!(out, "{name} = \"{}\"", hex(color));
+                }
+            }
+        }
+        ExportFormat::Kitty => {
+            let _ = writeln!(out, "foreground {}", hex(&colors.foreground));
+            let _ = writeln!(out, "background {}", hex(&colors.background));
+            for (i, color) in ansi(theme).into_iter().enumerate() {
+                let _ = writeln!(out, "color{i} {}", hex(color));
+            }
+        }
+        ExportFormat::Foot => {
+            let _ = writeln!(out, "[colors]");
+            let _ = writeln!(out, "foreground={}", bare(&colors.foreground));
+            let _ = writeln!(out, "background={}", bare(&colors.background));
+            for (i, color) in colors.normal.colors().into_iter().enumerate() {
+                let _ = writeln!(out, "regular{i}={}", bare(color));
+            }
+            for (i, color) in colors.bright.colors().into_iter().enumerate() {
+                let _ = writeln!(out, "bright{i}={}", bare(color));
+            }
+        }
+        ExportFormat::Ghostty => {
+            let _ = writeln!(out, "background = {}", hex(&colors.background));
+            let _ = writeln!(out, "foreground = {}", hex(&colors.foreground));
+            for (i, color) in ansi(theme).into_iter().enumerate() {
+                let _ = writeln!(out, "palette = {i}={}", hex(color));
+            }
+        }
+        ExportFormat::Wezterm => {
+            let list = |palette: &ThemeColorPalette| {
+                palette
+                    .colors()
+                    .iter()
+                    .map(|color| format!("\"{}\"", hex(color)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let _ = writeln!(out, "[colors]");
+            let _ = writeln!(out, "foreground = \"{}\"", hex(&colors.foreground));
+            let _ = writeln!(out, "background = \"{}\"", hex(&colors.background));
+            let _ = writeln!(out, "ansi = [{}]", list(&colors.normal));
+            let _ = writeln!(out, "brights = [{}]", list(&colors.bright));
+            let _ = writeln!(out, "\n[metadata]");
+            let _ = writeln!(out, "name = \"{}\"", escape_quotes(&theme.name));
+            if theme.source_url.starts_with("http") {
+                let _ = writeln!(out, "origin_url = \"{}\"", escape_quotes(&theme.source_url));
+            }
+        }
+        ExportFormat::Xresources => {
+            let _ = writeln!(out, "*.foreground: {}", hex(&colors.foreground));
+            let _ = writeln!(out, "*.background: {}", hex(&colors.background));
+            let _ = writeln!(out, "*.cursorColor: {}", hex(&colors.foreground));
+            for (i, color) in ansi(theme).into_iter().enumerate() {
+                let _ = writeln!(out, "*.color{i}: {}", hex(color));
+            }
+        }
+        ExportFormat::Base16 => {
+            let _ = writeln!(out, "scheme: \"{}\"", escape_quotes(&theme.name));
+            let _ = writeln!(out, "author: \"awesome-omarchy-tui\"");
+            for (i, color) in base16_palette(theme).iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "base0{i:X}: \"{}\"",
+                    color.to_hex().trim_start_matches('#')
+                );
+            }
+        }
+    }
+
+    out
+}
+
+/// The 16 base16 slots derived from the ANSI palette.
+///
+/// Follows the mapping base16-shell uses in the other direction; the slots
+/// ANSI has no color for (subtle backgrounds, orange, brown) are blended.
+pub fn base16_palette(theme: &Theme) -> [Rgb; 16] {
+    let colors = &theme.colors;
+    let rgb = |color: &str| Rgb::from_hex(color).unwrap_or(Rgb::new(0, 0, 0));
+    let (background, foreground) = (rgb(&colors.background), rgb(&colors.foreground));
+    let (normal, bright) = (&colors.normal, &colors.bright);
+    let comment = rgb(&bright.black);
+
+    [
+        background,
+        background.mix(foreground, 0.08),
+        background.mix(comment, 0.5),
+        comment,
+        comment.mix(foreground, 0.5),
+        foreground,
+        rgb(&normal.white),
+        rgb(&bright.white),
+        rgb(&normal.red),
+        rgb(&normal.red).mix(rgb(&normal.yellow), 0.5),
+        rgb(&normal.yellow),
+        rgb(&normal.green),
+        rgb(&normal.cyan),
+        rgb(&normal.blue),
+        rgb(&normal.magenta),
+        rgb(&normal.red).mix(background, 0.4),
+    ]
+}
+
+/// Lowercase, dash-separated form of a theme name for file and directory names
+pub fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// `~/.config/awesome-omarchy-tui/exports/<theme>` on Linux
+pub fn default_export_dir(theme: &Theme) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| {
+        dir.join("awesome-omarchy-tui")
+            .join("exports")
+            .join(slug(&theme.name))
+    })
+}
+
+/// Write every format into `dir`, returning the files written
+pub fn export_all(theme: &Theme, dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    ExportFormat::ALL
+        .iter()
+        .map(|format| {
+            let path = dir.join(format.file_name());
+            fs::write(&path, export(theme, *format))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Find a theme by path to a theme file, or by name in the awesome-omarchy list
+pub async fn resolve_theme(client: &HttpClient, query: &str) -> Result<Theme> {
+    let path = Path::new(query);
+    if path.is_file() {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        // Omarchy keeps theme files inside a directory named after the theme
+        let name = path
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .or_else(|| path.file_stem())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let format =
+            ThemeFormat::from_path(&path.to_string_lossy()).unwrap_or(ThemeFormat::AlacrittyToml);
+        let mut theme = ThemeParser::new().parse_format(format, &name, &content)?;
+        theme.source_url = path.display().to_string();
+        return Ok(theme);
+    }
+
+    let wanted = slug(query);
+    let entries = client.fetch_themes_from_readme().await?;
+    let entry = entries
+        .iter()
+        .find(|entry| slug(&entry.name) == wanted)
+        .or_else(|| {
+            entries
+                .iter()
+                .find(|entry| slug(&entry.name).contains(&wanted))
+        })
+        .ok_or_else(|| anyhow!("No theme named \"{query}\" in awesome-omarchy"))?;
+    client.fetch_theme_colors(entry).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ThemeColors;
+
+    fn palette(colors: [&str; 8]) -> ThemeColorPalette {
+        let [black, red, green, yellow, blue, magenta, cyan, white] = colors.map(String::from);
+        ThemeColorPalette {
+            black,
+            red,
+            green,
+            yellow,
+            blue,
+            magenta,
+            cyan,
+            white,
+        }
+    }
+
+    fn tokyo_night() -> Theme {
+        Theme {
+            name: "Tokyo Night".to_string(),
+            colors: ThemeColors {
+                background: "#1A1B26".to_string(),
+                foreground: "#a9b1d6".to_string(),
+                normal: palette([
+                    "#32344a", "#f7768e", "#9ece6a", "#e0af68", "#7aa2f7", "#ad8ee6", "#449dab",
+                    "#787c99",
+                ]),
+                bright: palette([
+                    "#444b6a", "#ff7a93", "#b9f27c", "#ff9e64", "#7da6ff", "#bb9af7", "#0db9d7",
+                    "#acb0d0",
+                ]),
+            },
+            source_url: "https://github.com/user/omarchy-tokyo-night-theme".to_string(),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_line_based_formats() {
+        let theme = tokyo_night();
+
+        let kitty = export(&theme, ExportFormat::Kitty);
+        assert!(kitty.starts_with("# Tokyo Night\n"));
+        assert!(kitty.contains("background #1a1b26\n"));
+        assert!(kitty.contains("color9 #ff7a93\n"));
+
+        let foot = export(&theme, ExportFormat::Foot);
+        assert!(foot.contains("[colors]\nforeground=a9b1d6\nbackground=1a1b26\n"));
+        assert!(foot.contains("regular7=787c99\n") && foot.contains("bright0=444b6a\n"));
+
+        let ghostty = export(&theme, ExportFormat::Ghostty);
+        assert!(ghostty.contains("palette = 15=#acb0d0\n"));
+
+        let xresources = export(&theme, ExportFormat::Xresources);
+        assert!(xresources.starts_with("! Tokyo Night\n"));
+        assert!(xresources.contains("*.color4: #7aa2f7\n"));
+    }
+
+    #[test]
+    fn test_alacritty_round_trips_through_the_parser() {
+        let theme = tokyo_night();
+        let parsed = ThemeParser::new()
+            .parse_format(
+                ThemeFormat::AlacrittyToml,
+                "tokyo-night",
+                &export(&theme, ExportFormat::Alacritty),
+            )
+            .unwrap();
+        assert_eq!(parsed.colors.background, "#1a1b26");
+        assert_eq!(parsed.colors.normal.magenta, theme.colors.normal.magenta);
+        assert_eq!(parsed.colors.bright.white, theme.colors.bright.white);
+    }
+
+    #[test]
+    fn test_wezterm_round_trips_through_toml() {
+        #[derive(serde::Deserialize)]
+        struct Scheme {
+            colors: Colors,
+            metadata: Metadata,
+        }
+        #[derive(serde::Deserialize)]
+        struct Colors {
+            foreground: String,
+            background: String,
+            ansi: Vec<String>,
+            brights: Vec<String>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Metadata {
+            name: String,
+        }
+
+        let theme = tokyo_night();
+        let scheme: Scheme = toml::from_str(&export(&theme, ExportFormat::Wezterm)).unwrap();
+        assert_eq!(scheme.metadata.name, "Tokyo Night");
+        assert_eq!(scheme.colors.background, "#1a1b26");
+        assert_eq!(scheme.colors.foreground, theme.colors.foreground);
+        assert_eq!(scheme.colors.ansi, theme.colors.normal.colors());
+        assert_eq!(scheme.colors.brights, theme.colors.bright.colors());
+    }
+
+    #[test]
+    fn test_base16_round_trips_through_yaml() {
+        let theme = tokyo_night();
+        let scheme: std::collections::BTreeMap<String, String> =
+            serde_yaml::from_str(&export(&theme, ExportFormat::Base16)).unwrap();
+
+        assert_eq!(scheme["scheme"], "Tokyo Night");
+        assert_eq!(scheme["base00"], "1a1b26");
+        assert_eq!(scheme["base05"], "a9b1d6");
+        assert_eq!(scheme["base08"], "f7768e");
+        assert_eq!(scheme["base0D"], "7aa2f7");
+        // Orange sits halfway between red and yellow
+        assert_eq!(scheme["base09"], "ec937b");
+        assert_eq!(
+            scheme.keys().filter(|key| key.starts_with("base")).count(),
+            16
+        );
+    }
+
+    #[test]
+    fn test_kitty_and_ghostty_round_trip() {
+        let theme = tokyo_night();
+        let parser = ThemeParser::new();
+
+        for (format, parsed) in [
+            (
+                ExportFormat::Kitty,
+                parser.parse_kitty_conf("tokyo-night", &export(&theme, ExportFormat::Kitty)),
+            ),
+            (
+                ExportFormat::Ghostty,
+                parser.parse_ghostty("tokyo-night", &export(&theme, ExportFormat::Ghostty)),
+            ),
+        ] {
+            let parsed = parsed.unwrap();
+            let original = theme.colors.slots();
+            let normalized: Vec<_> = original
+                .iter()
+                .map(|(slot, color)| (*slot, color.to_lowercase()))
+                .collect();
+            let round_tripped: Vec<_> = parsed
+                .colors
+                .slots()
+                .iter()
+                .map(|(slot, color)| (*slot, color.to_string()))
+                .collect();
+            assert_eq!(round_tripped, normalized, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_base16_reader_recovers_the_accents() {
+        let theme = tokyo_night();
+        let parsed = ThemeParser::new()
+            .parse_base16_yaml("tokyo-night", &export(&theme, ExportFormat::Base16))
+            .unwrap();
+
+        assert_eq!(parsed.colors.background, "#1a1b26");
+        assert_eq!(parsed.colors.foreground, theme.colors.foreground);
+        assert_eq!(parsed.colors.normal.red, theme.colors.normal.red);
+        assert_eq!(parsed.colors.normal.cyan, theme.colors.normal.cyan);
+        assert_eq!(parsed.colors.bright.black, theme.colors.bright.black);
+        assert_eq!(parsed.colors.bright.white, theme.colors.bright.white);
+    }
+
+    #[test]
+    fn test_export_all_writes_each_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let written = export_all(&tokyo_night(), dir.path()).unwrap();
+        assert_eq!(written.len(), ExportFormat::ALL.len());
+        assert!(dir.path().join("wezterm.toml").is_file());
+        assert_eq!(slug("Tokyo Night (Storm)"), "tokyo-night-storm");
+    }
+}
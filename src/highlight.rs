@@ -0,0 +1,581 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Languages the editor preview can highlight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Lua,
+    Bash,
+    Toml,
+    Python,
+    PlainText,
+}
+
+/// Token classes, mirroring the highlight groups colorschemes style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    Type,
+    Function,
+    String,
+    Number,
+    Constant,
+    Comment,
+    /// Table keys and section headers in TOML, attributes elsewhere
+    Property,
+    Variable,
+    Operator,
+    Plain,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+}
+
+/// A snippet shown in the editor preview
+#[derive(Debug, Clone)]
+pub struct CodeSample {
+    pub title: String,
+    pub language: Language,
+    pub source: String,
+}
+
+/// Longest user sample shown, so huge files do not slow down drawing
+const MAX_SAMPLE_LINES: usize = 200;
+
+impl CodeSample {
+    /// The built-in sample for each highlighted language
+    pub fn builtins() -> Vec<CodeSample> {
+        Language::HIGHLIGHTED
+            .iter()
+            .map(|&language| CodeSample {
+                title: language.label().to_string(),
+                language,
+                source: language.sample().to_string(),
+            })
+            .collect()
+    }
+
+    /// Load a user's file, picking the language from its extension
+    pub fn from_file(path: &Path) -> Result<CodeSample> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sample {}", path.display()))?;
+        let source = content
+            .lines()
+            .take(MAX_SAMPLE_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(CodeSample {
+            title: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            language: Language::from_path(path),
+            source,
+        })
+    }
+}
+
+impl Language {
+    /// Languages with a tokenizer and a built-in sample, in cycling order
+    pub const HIGHLIGHTED: [Language; 5] = [
+        Language::Rust,
+        Language::Lua,
+        Language::Bash,
+        Language::Toml,
+        Language::Python,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::Lua => "Lua",
+            Language::Bash => "Bash",
+            Language::Toml => "TOML",
+            Language::Python => "Python",
+            Language::PlainText => "Text",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Language {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match extension.as_str() {
+            "rs" => Language::Rust,
+            "lua" => Language::Lua,
+            "sh" | "bash" | "zsh" => Language::Bash,
+            "toml" => Language::Toml,
+            "py" => Language::Python,
+            _ => Language::PlainText,
+        }
+    }
+
+    /// Language of a fenced code block's info string, e.g. `sh` or `rust`
+    pub fn from_name(name: &str) -> Language {
+        match name.to_lowercase().as_str() {
+            "rust" | "rs" => Language::Rust,
+            "lua" => Language::Lua,
+            "bash" | "sh" | "shell" | "zsh" | "console" => Language::Bash,
+            "toml" => Language::Toml,
+            "python" | "py" => Language::Python,
+            _ => Language::PlainText,
+        }
+    }
+
+    fn sample(&self) -> &'static str {
+        match self {
+            Language::Rust => RUST_SAMPLE,
+            Language::Lua => LUA_SAMPLE,
+            Language::Bash => BASH_SAMPLE,
+            Language::Toml => TOML_SAMPLE,
+            Language::Python => PYTHON_SAMPLE,
+            Language::PlainText => "",
+        }
+    }
+
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+                "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+                "trait", "type", "unsafe", "use", "where", "while",
+            ],
+            Language::Lua => &[
+                "and", "break", "do", "else", "elseif", "end", "for", "function", "goto", "if",
+                "in", "local", "not", "or", "repeat", "return", "then", "until", "while",
+            ],
+            Language::Bash => &[
+                "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function",
+                "if", "in", "local", "readonly", "return", "select", "then", "until", "while",
+            ],
+            Language::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def",
+                "del", "elif", "else", "except", "finally", "for", "from", "global", "if",
+                "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+                "try", "while", "with", "yield",
+            ],
+            Language::Toml | Language::PlainText => &[],
+        }
+    }
+
+    fn constants(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust | Language::Toml => &["true", "false"],
+            Language::Lua => &["true", "false", "nil"],
+            Language::Python => &["True", "False", "None"],
+            Language::Bash | Language::PlainText => &[],
+        }
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        match self {
+            Language::Rust => Some("//"),
+            Language::Lua => Some("--"),
+            Language::Bash | Language::Toml | Language::Python => Some("#"),
+            Language::PlainText => None,
+        }
+    }
+
+    /// Delimiters that may span lines: (open, close, kind)
+    fn block_delimiters(&self) -> &'static [(&'static str, &'static str, TokenKind)] {
+        match self {
+            Language::Rust => &[("/*", "*/", TokenKind::Comment)],
+            Language::Lua => &[
+                ("--[[", "]]", TokenKind::Comment),
+                ("[[", "]]", TokenKind::String),
+            ],
+            Language::Python => &[
+                ("\"\"\"", "\"\"\"", TokenKind::String),
+                ("'''", "'''", TokenKind::String),
+            ],
+            Language::Toml => &[("\"\"\"", "\"\"\"", TokenKind::String)],
+            Language::Bash | Language::PlainText => &[],
+        }
+    }
+}
+
+/// Split source into highlighted lines
+pub fn highlight(source: &str, language: Language) -> Vec<Vec<Token<'_>>> {
+    // Closing delimiter and kind of a construct still open from a previous line
+    let mut open: Option<(&'static str, TokenKind)> = None;
+    source
+        .lines()
+        .map(|line| tokenize_line(line, language, &mut open))
+        .collect()
+}
+
+fn tokenize_line<'a>(
+    line: &'a str,
+    language: Language,
+    open: &mut Option<(&'static str, TokenKind)>,
+) -> Vec<Token<'a>> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    let mut push = |kind: TokenKind, text: &'a str| {
+        if !text.is_empty() {
+            tokens.push(Token { kind, text });
+        }
+    };
+
+    if language == Language::PlainText {
+        push(TokenKind::Plain, line);
+        return tokens;
+    }
+
+    // Finish a block comment or string carried over from earlier lines
+    if let Some((close, kind)) = *open {
+        let Some(end) = rest.find(close) else {
+            push(kind, rest);
+            return tokens;
+        };
+        let end = end + close.len();
+        push(kind, &rest[..end]);
+        rest = &rest[end..];
+        *open = None;
+    }
+
+    let is_line_start = |rest: &str| line[..line.len() - rest.len()].trim().is_empty();
+    let mut previous_word = "";
+
+    while let Some(c) = rest.chars().next() {
+        // Block constructs first, since Lua's `--[[` also starts a line comment
+        if let Some(&(opener, close, kind)) = language
+            .block_delimiters()
+            .iter()
+            .find(|(opener, _, _)| rest.starts_with(opener))
+        {
+            let Some(end) = rest[opener.len()..].find(close) else {
+                push(kind, rest);
+                *open = Some((close, kind));
+                break;
+            };
+            let end = opener.len() + end + close.len();
+            push(kind, &rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        if language
+            .line_comment()
+            .is_some_and(|marker| rest.starts_with(marker))
+        {
+            push(TokenKind::Comment, rest);
+            break;
+        }
+
+        let (kind, len) = match c {
+            '\'' if language == Language::Rust && is_lifetime(rest) => {
+                (TokenKind::Type, 1 + word_length(&rest[1..], language))
+            }
+            '"' | '\'' => (TokenKind::String, string_length(rest, c)),
+            '$' if language == Language::Bash => (TokenKind::Variable, variable_length(rest)),
+            '[' if language == Language::Toml && is_line_start(rest) => {
+                (TokenKind::Property, bracket_length(rest))
+            }
+            '#' if language == Language::Rust && rest[1..].starts_with(['[', '!']) => {
+                (TokenKind::Property, bracket_length(rest))
+            }
+            c if c.is_ascii_digit() => {
+                let len = rest
+                    .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '.'))
+                    .unwrap_or(rest.len());
+                (TokenKind::Number, len)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = word_length(rest, language);
+                let word = &rest[..len];
+                let after = rest[len..].trim_start();
+                let kind = classify_word(language, word, after, previous_word, is_line_start(rest));
+                previous_word = word;
+                (kind, len)
+            }
+            c if OPERATORS.contains(c) => {
+                let len = rest
+                    .find(|ch: char| !OPERATORS.contains(ch))
+                    .unwrap_or(rest.len());
+                (TokenKind::Operator, len)
+            }
+            c => (TokenKind::Plain, c.len_utf8()),
+        };
+
+        push(kind, &rest[..len]);
+        rest = &rest[len..];
+    }
+
+    tokens
+}
+
+const OPERATORS: &str = "=+-*/%<>!&|^~:.";
+
+fn word_length(text: &str, language: Language) -> usize {
+    text.find(|c: char| {
+        !(c.is_alphanumeric() || c == '_' || (language == Language::Toml && c == '-'))
+    })
+    .unwrap_or(text.len())
+}
+
+/// Up to and including the closing `]`, or the rest of the line
+fn bracket_length(text: &str) -> usize {
+    text.find(']').map_or(text.len(), |end| end + 1)
+}
+
+/// Tell a Rust lifetime like `'a` from a char literal like `'a'`
+fn is_lifetime(text: &str) -> bool {
+    let mut chars = text[1..].chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), next) => (c.is_alphabetic() || c == '_') && next != Some('\''),
+        _ => false,
+    }
+}
+
+fn classify_word(
+    language: Language,
+    word: &str,
+    after: &str,
+    previous_word: &str,
+    line_start: bool,
+) -> TokenKind {
+    if language.keywords().contains(&word) {
+        TokenKind::Keyword
+    } else if language.constants().contains(&word) {
+        TokenKind::Constant
+    } else if language == Language::Toml {
+        // Bare keys before `=` are the only identifiers TOML has
+        if line_start || after.starts_with('=') {
+            TokenKind::Property
+        } else {
+            TokenKind::Plain
+        }
+    } else if language == Language::Rust && after.starts_with('!') {
+        // Macros highlight like functions
+        TokenKind::Function
+    } else if after.starts_with('(') || matches!(previous_word, "fn" | "def" | "function") {
+        TokenKind::Function
+    } else if language == Language::Bash && line_start {
+        // The first word of a command is what runs
+        TokenKind::Function
+    } else if word
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && word.len() > 1
+    {
+        TokenKind::Constant
+    } else if word.starts_with(char::is_uppercase) {
+        TokenKind::Type
+    } else {
+        TokenKind::Plain
+    }
+}
+
+/// Length of a quoted string including its quotes, honoring backslash escapes
+fn string_length(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            c if c == quote && !escaped => return i + c.len_utf8(),
+            _ => escaped = false,
+        }
+    }
+    text.len()
+}
+
+/// Length of `$NAME`, `${...}` or a special parameter like `$1`
+fn variable_length(text: &str) -> usize {
+    let rest = &text[1..];
+    if rest.starts_with('{') {
+        return rest.find('}').map_or(text.len(), |end| end + 2);
+    }
+    let name = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    if name > 0 {
+        1 + name
+    } else if rest.starts_with(['@', '#', '?', '$', '!', '*', '-']) {
+        2
+    } else {
+        1
+    }
+}
+
+const RUST_SAMPLE: &str = r##"use std::collections::HashMap;
+
+/// Theme preview implementation
+#[derive(Debug)]
+pub struct Palette<'a> {
+    name: &'a str,
+    colors: HashMap<String, u32>,
+}
+
+pub fn apply_theme(theme: &Palette) -> Option<usize> {
+    let count = theme.colors.len(); // slots in use
+    println!("Theme: {} ({count})", theme.name);
+    if count > MAX_THEMES { return None; }
+    Some(count * 2 + 0x10)
+}
+
+const MAX_THEMES: usize = 42;"##;
+
+const LUA_SAMPLE: &str = r##"-- Neovim colorscheme setup
+local M = {}
+
+--[[ Palette generated from
+     the Omarchy theme ]]
+M.colors = { bg = "#1a1b26", fg = "#a9b1d6" }
+
+function M.setup(opts)
+  opts = opts or {}
+  for name, hex in pairs(M.colors) do
+    vim.api.nvim_set_hl(0, name, { fg = hex, bold = true })
+  end
+  return #M.colors > 0 and nil
+end
+
+return M"##;
+
+const BASH_SAMPLE: &str = r##"#!/usr/bin/env bash
+set -euo pipefail
+
+THEME_DIR="${HOME}/.config/omarchy/themes"
+theme="${1:-tokyo-night}"
+
+# Link the selected theme into place
+if [[ -d "$THEME_DIR/$theme" ]]; then
+  ln -sfn "$THEME_DIR/$theme" "$HOME/.config/omarchy/current/theme"
+  echo "Switched to $theme ($?)"
+else
+  echo 'Theme not found' >&2
+  exit 1
+fi"##;
+
+const TOML_SAMPLE: &str = r##"# Alacritty colors
+[colors.primary]
+background = "#1a1b26"
+foreground = "#a9b1d6"
+
+[colors.normal]
+black = "#32344a"
+red = "#f7768e"
+
+[font]
+size = 11.5
+builtin_box_drawing = true
+offset = { x = 0, y = 1 }"##;
+
+const PYTHON_SAMPLE: &str = r##"import json
+from pathlib import Path
+
+MAX_THEMES = 42
+
+
+class Palette:
+    """Colors loaded from an Omarchy theme."""
+
+    def __init__(self, name: str, colors: dict) -> None:
+        self.name = name
+        self.colors = colors
+
+    def contrast(self, other) -> float:
+        # Placeholder for the WCAG ratio
+        return len(self.colors) / 3.5 if other else None
+
+
+print(f"Loaded {Palette('tokyo', {}).name!r}", json.dumps([1, 2.5]))"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds<'a>(tokens: &[Token<'a>]) -> Vec<(TokenKind, &'a str)> {
+        tokens
+            .iter()
+            .filter(|token| !token.text.trim().is_empty())
+            .map(|token| (token.kind, token.text.trim()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_tokens() {
+        let lines = highlight(
+            "pub fn apply(x: u8) -> Vec<u8> { \"hi\" } // done",
+            Language::Rust,
+        );
+        let tokens = kinds(&lines[0]);
+        assert_eq!(tokens[0], (TokenKind::Keyword, "pub"));
+        assert_eq!(tokens[2], (TokenKind::Function, "apply"));
+        assert!(tokens.contains(&(TokenKind::Type, "Vec")));
+        assert!(tokens.contains(&(TokenKind::String, "\"hi\"")));
+        assert_eq!(tokens.last(), Some(&(TokenKind::Comment, "// done")));
+    }
+
+    #[test]
+    fn test_block_comments_span_lines() {
+        let lines = highlight("a /* one\ntwo */ b", Language::Rust);
+        assert_eq!(kinds(&lines[0])[1], (TokenKind::Comment, "/* one"));
+        assert_eq!(
+            lines[1][0],
+            Token {
+                kind: TokenKind::Comment,
+                text: "two */"
+            }
+        );
+        assert_eq!(kinds(&lines[1]).last(), Some(&(TokenKind::Plain, "b")));
+
+        let lines = highlight("--[[ long\ncomment ]] x = 1", Language::Lua);
+        assert_eq!(lines[1][0].kind, TokenKind::Comment);
+        assert!(kinds(&lines[1]).contains(&(TokenKind::Number, "1")));
+    }
+
+    #[test]
+    fn test_bash_and_toml_tokens() {
+        let lines = highlight("echo \"$HOME\" ${1:-x} # note", Language::Bash);
+        let tokens = kinds(&lines[0]);
+        assert_eq!(tokens[0], (TokenKind::Function, "echo"));
+        assert!(tokens.contains(&(TokenKind::Variable, "${1:-x}")));
+        assert_eq!(tokens.last(), Some(&(TokenKind::Comment, "# note")));
+
+        let lines = highlight("[colors.primary]\nsize = 11.5", Language::Toml);
+        assert_eq!(
+            kinds(&lines[0]),
+            vec![(TokenKind::Property, "[colors.primary]")]
+        );
+        assert_eq!(kinds(&lines[1])[0], (TokenKind::Property, "size"));
+        assert_eq!(kinds(&lines[1])[2], (TokenKind::Number, "11.5"));
+    }
+
+    #[test]
+    fn test_every_sample_round_trips() {
+        for language in Language::HIGHLIGHTED {
+            let sample = language.sample();
+            let rebuilt: Vec<String> = highlight(sample, language)
+                .iter()
+                .map(|line| line.iter().map(|token| token.text).collect())
+                .collect();
+            assert_eq!(rebuilt.join("\n"), sample, "{language:?}");
+        }
+    }
+
+    #[test]
+    fn test_sample_file_language_from_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("init.lua");
+        std::fs::write(&path, "local x = 1\n").unwrap();
+
+        let sample = CodeSample::from_file(&path).unwrap();
+        assert_eq!(sample.title, "init.lua");
+        assert_eq!(sample.language, Language::Lua);
+        assert_eq!(
+            Language::from_path(Path::new("notes.md")),
+            Language::PlainText
+        );
+    }
+}
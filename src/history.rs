@@ -0,0 +1,590 @@
+//! Parsed README snapshots kept on disk, to tell what changed between visits
+//!
+//! A snapshot is written whenever the README's entries differ from the latest
+//! one, named after the second it was taken. The time of the last visit is kept
+//! next to them, so the next session knows which snapshot its user last saw.
+
+use crate::duplicates::url_key;
+use crate::markdown::{Block, Inline, InlineStyle};
+use crate::models::{ReadmeContent, Section};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Subdirectory of the cache directory holding the snapshots
+pub const HISTORY_DIR: &str = "history";
+
+/// Snapshots kept; older ones are removed
+const MAX_SNAPSHOTS: usize = 100;
+
+/// File holding the time of the last visit
+const LAST_VISIT_FILE: &str = "last_visit";
+
+/// Title of the virtual section listing what changed
+pub const CHANGES_SECTION: &str = "✨ New since last visit";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub title: String,
+    pub url: String,
+    /// Breadcrumb of the section listing the entry
+    pub section: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Unix time the snapshot was taken
+    pub taken_at: u64,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+impl Snapshot {
+    pub fn of(content: &ReadmeContent, taken_at: u64) -> Self {
+        let entries = content
+            .sections
+            .iter()
+            .enumerate()
+            .flat_map(|(index, section)| {
+                let breadcrumb = content.breadcrumb(index);
+                section.entries.iter().map(move |entry| SnapshotEntry {
+                    title: entry.title.clone(),
+                    url: entry.url.clone(),
+                    section: breadcrumb.clone(),
+                    description: entry.description.clone(),
+                })
+            })
+            .collect();
+        Self { taken_at, entries }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MovedEntry {
+    pub entry: SnapshotEntry,
+    pub from: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangedEntry {
+    pub entry: SnapshotEntry,
+    pub before: String,
+}
+
+/// Differences between two snapshots, entries matched by URL
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Changes {
+    pub added: Vec<SnapshotEntry>,
+    pub removed: Vec<SnapshotEntry>,
+    pub moved: Vec<MovedEntry>,
+    /// Entries whose description was reworded
+    pub changed: Vec<ChangedEntry>,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.changed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} moved, {} updated",
+            self.added.len(),
+            self.removed.len(),
+            self.moved.len(),
+            self.changed.len()
+        )
+    }
+}
+
+pub fn diff(old: &Snapshot, new: &Snapshot) -> Changes {
+    let index = |snapshot: &Snapshot| {
+        let mut by_url: HashMap<String, SnapshotEntry> = HashMap::new();
+        for entry in &snapshot.entries {
+            by_url
+                .entry(url_key(&entry.url))
+                .or_insert_with(|| entry.clone());
+        }
+        by_url
+    };
+    let (before, after) = (index(old), index(new));
+
+    let mut changes = Changes::default();
+    for entry in &new.entries {
+        let key = url_key(&entry.url);
+        // Entries listed twice are compared once, at their first place
+        if after.get(&key) != Some(entry) {
+            continue;
+        }
+        match before.get(&key) {
+            None => changes.added.push(entry.clone()),
+            Some(previous) => {
+                if previous.section != entry.section {
+                    changes.moved.push(MovedEntry {
+                        entry: entry.clone(),
+                        from: previous.section.clone(),
+                    });
+                }
+                if previous.description != entry.description {
+                    changes.changed.push(ChangedEntry {
+                        entry: entry.clone(),
+                        before: previous.description.clone(),
+                    });
+                }
+            }
+        }
+    }
+    changes.removed = old
+        .entries
+        .iter()
+        .filter(|entry| !after.contains_key(&url_key(&entry.url)))
+        .cloned()
+        .collect();
+    changes
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    /// A history under `cache_dir`, created on the first snapshot
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            dir: cache_dir.join(HISTORY_DIR),
+        }
+    }
+
+    /// Keep a snapshot of `content` unless its entries match the latest one;
+    /// returns whether one was written
+    pub fn record(&self, content: &ReadmeContent, now: u64) -> Result<bool> {
+        let snapshot = Snapshot::of(content, now);
+        if snapshot.entries.is_empty()
+            || self
+                .latest()
+                .is_some_and(|latest| latest.entries == snapshot.entries)
+        {
+            return Ok(false);
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            self.dir.join(format!("{now}.json")),
+            serde_json::to_string(&snapshot)?,
+        )?;
+
+        let times = self.times();
+        for old in &times[..times.len().saturating_sub(MAX_SNAPSHOTS)] {
+            fs::remove_file(self.dir.join(format!("{old}.json")))?;
+        }
+        Ok(true)
+    }
+
+    /// Times of the stored snapshots, oldest first
+    pub fn times(&self) -> Vec<u64> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut times: Vec<u64> = entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                name.to_str()?.strip_suffix(".json")?.parse().ok()
+            })
+            .collect();
+        times.sort_unstable();
+        times
+    }
+
+    pub fn load(&self, taken_at: u64) -> Option<Snapshot> {
+        let content = fs::read_to_string(self.dir.join(format!("{taken_at}.json"))).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn latest(&self) -> Option<Snapshot> {
+        self.load(*self.times().last()?)
+    }
+
+    /// The snapshot that was current at `time`
+    pub fn at(&self, time: u64) -> Option<Snapshot> {
+        let times = self.times();
+        let taken_at = times.iter().rev().find(|&&taken_at| taken_at <= time)?;
+        self.load(*taken_at)
+    }
+
+    pub fn last_visit(&self) -> Option<u64> {
+        fs::read_to_string(self.dir.join(LAST_VISIT_FILE))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    pub fn mark_visit(&self, now: u64) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(LAST_VISIT_FILE), now.to_string())?;
+        Ok(())
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Unix time of midnight UTC starting a `YYYY-MM-DD` date
+pub fn parse_date(date: &str) -> Result<u64> {
+    let invalid = || anyhow!("Invalid date {date:?}, expected YYYY-MM-DD");
+    let mut parts = date.trim().splitn(3, '-');
+    let mut next = || -> Result<i64> {
+        parts
+            .next()
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(invalid)
+    };
+    let (year, month, day) = (next()?, next()?, next()?);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return Err(invalid()),
+    };
+    if !(1..=month_days).contains(&day) || year < 1970 {
+        return Err(invalid());
+    }
+
+    // Days from the civil calendar, after Howard Hinnant's algorithm
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Ok(days as u64 * 86_400)
+}
+
+/// `YYYY-MM-DD` of a unix time, in UTC
+pub fn format_date(time: u64) -> String {
+    let days = (time / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Plain text listing of `changes`, one line per entry
+pub fn report(changes: &Changes) -> String {
+    let mut out = String::new();
+    let mut group = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            out.push_str(&format!("{title} ({})\n", lines.len()));
+            for line in lines {
+                out.push_str(&format!("  {line}\n"));
+            }
+        }
+    };
+    group(
+        "Added",
+        changes
+            .added
+            .iter()
+            .map(|e| {
+                format!(
+                    "+ {} - {} [{}] {}",
+                    e.title, e.description, e.section, e.url
+                )
+            })
+            .collect(),
+    );
+    group(
+        "Moved",
+        changes
+            .moved
+            .iter()
+            .map(|m| format!("~ {}: {} → {}", m.entry.title, m.from, m.entry.section))
+            .collect(),
+    );
+    group(
+        "Updated",
+        changes
+            .changed
+            .iter()
+            .map(|c| {
+                format!(
+                    "* {}: {} (was: {})",
+                    c.entry.title, c.entry.description, c.before
+                )
+            })
+            .collect(),
+    );
+    group(
+        "Removed",
+        changes
+            .removed
+            .iter()
+            .map(|e| format!("- {} [{}] {}", e.title, e.section, e.url))
+            .collect(),
+    );
+    out
+}
+
+/// The virtual section listing `changes` as a document
+pub fn changes_section(changes: &Changes, since: u64) -> Section {
+    let text = |text: &str| Inline {
+        text: text.to_string(),
+        style: InlineStyle::default(),
+    };
+    let link = |entry: &SnapshotEntry| Inline {
+        text: entry.title.clone(),
+        style: InlineStyle {
+            bold: true,
+            link: Some(entry.url.clone()),
+            ..InlineStyle::default()
+        },
+    };
+    let muted = |content: String| Inline {
+        text: content,
+        style: InlineStyle {
+            italic: true,
+            ..InlineStyle::default()
+        },
+    };
+    let list = |items: Vec<Vec<Inline>>| Block::List {
+        start: None,
+        items: items
+            .into_iter()
+            .map(|inlines| vec![Block::Paragraph(inlines)])
+            .collect(),
+    };
+
+    let mut blocks = vec![Block::Paragraph(vec![text(&format!(
+        "Since {}: {}.",
+        format_date(since),
+        changes.summary()
+    ))])];
+    let mut group = |title: &str, items: Vec<Vec<Inline>>| {
+        if !items.is_empty() {
+            blocks.push(Block::Heading {
+                level: 3,
+                text: format!("{title} ({})", items.len()),
+            });
+            blocks.push(list(items));
+        }
+    };
+    group(
+        "Added",
+        changes
+            .added
+            .iter()
+            .map(|entry| {
+                vec![
+                    link(entry),
+                    text(&format!(" - {} ", entry.description)),
+                    muted(format!("in {}", entry.section)),
+                ]
+            })
+            .collect(),
+    );
+    group(
+        "Moved",
+        changes
+            .moved
+            .iter()
+            .map(|moved| {
+                vec![
+                    link(&moved.entry),
+                    text(&format!(": {} → {}", moved.from, moved.entry.section)),
+                ]
+            })
+            .collect(),
+    );
+    group(
+        "Updated",
+        changes
+            .changed
+            .iter()
+            .map(|changed| {
+                vec![
+                    link(&changed.entry),
+                    text(&format!(" - {} ", changed.entry.description)),
+                    muted(format!("(was: {})", changed.before)),
+                ]
+            })
+            .collect(),
+    );
+    group(
+        "Removed",
+        changes
+            .removed
+            .iter()
+            .map(|entry| {
+                vec![
+                    Inline {
+                        text: entry.title.clone(),
+                        style: InlineStyle {
+                            strikethrough: true,
+                            ..InlineStyle::default()
+                        },
+                    },
+                    muted(format!(" from {}", entry.section)),
+                ]
+            })
+            .collect(),
+    );
+
+    Section {
+        blocks,
+        ..Section::new(CHANGES_SECTION.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RepositoryEntry;
+    use crate::tasks::{AppMessage, TaskManager};
+    use crate::{App, HttpClient};
+
+    fn entry(title: &str, section: &str, description: &str) -> SnapshotEntry {
+        SnapshotEntry {
+            title: title.to_string(),
+            url: format!("https://github.com/a/{title}"),
+            section: section.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_finds_every_kind_of_change() {
+        let old = Snapshot {
+            taken_at: 1,
+            entries: vec![
+                entry("kept", "Tools", "Same"),
+                entry("mover", "Tools", "Moves"),
+                entry("reworded", "Tools", "Old words"),
+                entry("gone", "Tools", "Removed"),
+            ],
+        };
+        let new = Snapshot {
+            taken_at: 2,
+            entries: vec![
+                entry("kept", "Tools", "Same"),
+                entry("reworded", "Tools", "New words"),
+                entry("fresh", "Tools", "Added"),
+                entry("mover", "Themes", "Moves"),
+            ],
+        };
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.added, [entry("fresh", "Tools", "Added")]);
+        assert_eq!(changes.removed, [entry("gone", "Tools", "Removed")]);
+        assert_eq!(changes.moved.len(), 1);
+        assert_eq!(changes.moved[0].from, "Tools");
+        assert_eq!(changes.changed[0].before, "Old words");
+        assert_eq!(changes.summary(), "1 added, 1 removed, 1 moved, 1 updated");
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_history_records_distinct_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::new(dir.path());
+        let mut content = ReadmeContent::default();
+        let mut section = Section::new("Tools".to_string());
+        section.entries.push(crate::models::RepositoryEntry::sample(
+            "one",
+            "https://github.com/a/one",
+            "",
+        ));
+        content.sections.push(section);
+
+        assert!(history.record(&content, 100).unwrap());
+        assert!(!history.record(&content, 200).unwrap());
+        content.sections[0].entries[0].description = "Reworded".to_string();
+        assert!(history.record(&content, 300).unwrap());
+
+        assert_eq!(history.times(), [100, 300]);
+        assert_eq!(history.at(250).unwrap().taken_at, 100);
+        assert!(history.at(50).is_none());
+
+        assert_eq!(history.last_visit(), None);
+        history.mark_visit(300).unwrap();
+        assert_eq!(history.last_visit(), Some(300));
+    }
+
+    #[test]
+    fn test_dates_round_trip() {
+        assert_eq!(parse_date("1970-01-01").unwrap(), 0);
+        assert_eq!(parse_date("2024-02-29").unwrap(), 1_709_164_800);
+        assert_eq!(format_date(1_709_164_800 + 3_600), "2024-02-29");
+        assert_eq!(format_date(parse_date("2026-10-18").unwrap()), "2026-10-18");
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("2024-02-31").is_err());
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("2000-02-29").is_ok());
+        assert!(parse_date("2100-02-29").is_err());
+        assert!(parse_date("2026-04-31").is_err());
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_new_entries_are_listed_and_badged() {
+        let readme = |sections: Vec<Section>| ReadmeContent {
+            sections,
+            ..ReadmeContent::default()
+        };
+        let section = |title: &str, entries: Vec<RepositoryEntry>| Section {
+            entry_count: entries.len(),
+            entries,
+            ..Section::new(title.to_string())
+        };
+        let tool = |name: &str| {
+            RepositoryEntry::sample(name, &format!("https://github.com/a/{name}"), "A tool")
+        };
+        let mut nested = Section::new("Nested".to_string());
+        nested.parent = Some(0);
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        app.baseline = Some(Snapshot::of(
+            &readme(vec![section("Tools", vec![tool("kept")])]),
+            parse_date("2026-01-02").unwrap(),
+        ));
+        app.handle_message(AppMessage::Readme(Ok(readme(vec![
+            section("Tools", vec![tool("kept"), tool("fresh")]),
+            nested,
+        ]))));
+
+        let readme = app.readme_content.as_ref().unwrap();
+        assert_eq!(readme.sections[0].title, CHANGES_SECTION);
+        assert_eq!(readme.sections[2].parent, Some(1));
+        assert_eq!(app.tabs[0].title, CHANGES_SECTION);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Since 2026-01-02: 1 added, 0 removed, 0 moved, 0 updated")
+        );
+
+        assert!(crate::ui::render_to_string(&mut app).contains("Added (1)"));
+        app.current_tab = 1;
+        let tools = crate::ui::render_to_string(&mut app);
+        assert_eq!(tools.matches(" NEW ").count(), 1);
+    }
+}
@@ -1,9 +1,27 @@
 pub mod app;
+pub mod builder;
+pub mod checker;
 pub mod client;
+pub mod color;
+pub mod config;
+pub mod duplicates;
 pub mod events;
+pub mod export;
+pub mod highlight;
+pub mod history;
+pub mod links;
+pub mod lint;
+pub mod markdown;
 pub mod models;
+pub mod omarchy;
 pub mod parser;
+pub mod tags;
+pub mod tasks;
+pub mod terminal;
+pub mod theme_cache;
 pub mod ui;
+pub mod wallpaper;
+pub mod workspace;
 
 pub use app::App;
 pub use client::HttpClient;
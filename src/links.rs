@@ -0,0 +1,225 @@
+//! What README links point at, judged from their host and path
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of page a link leads to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LinkKind {
+    GitHub,
+    GitLab,
+    Codeberg,
+    /// A package on the Arch User Repository
+    Aur,
+    Video,
+    /// A blog post or article
+    Article,
+    #[default]
+    Website,
+}
+
+impl LinkKind {
+    pub fn classify(url: &str) -> Self {
+        let Some(host) = host(url) else {
+            return LinkKind::Website;
+        };
+        let segments = path_segments(url);
+
+        match host.as_str() {
+            "github.com" if repo_path(url).is_some() => LinkKind::GitHub,
+            "gitlab.com" if segments.len() >= 2 => LinkKind::GitLab,
+            "codeberg.org" if segments.len() >= 2 => LinkKind::Codeberg,
+            "aur.archlinux.org" if segments.first() == Some(&"packages") => LinkKind::Aur,
+            "youtube.com" | "m.youtube.com" | "youtu.be" | "vimeo.com" => LinkKind::Video,
+            "medium.com" | "dev.to" => LinkKind::Article,
+            _ if host.ends_with(".medium.com")
+                || host.ends_with(".substack.com")
+                || host.ends_with(".hashnode.dev") =>
+            {
+                LinkKind::Article
+            }
+            _ if segments.iter().any(|s| matches!(*s, "blog" | "posts")) => LinkKind::Article,
+            _ => LinkKind::Website,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LinkKind::GitHub => "GitHub repository",
+            LinkKind::GitLab => "GitLab project",
+            LinkKind::Codeberg => "Codeberg repository",
+            LinkKind::Aur => "AUR package",
+            LinkKind::Video => "Video",
+            LinkKind::Article => "Article",
+            LinkKind::Website => "Website",
+        }
+    }
+
+    /// Whether the link can be cloned with git
+    pub fn is_repository(self) -> bool {
+        matches!(
+            self,
+            LinkKind::GitHub | LinkKind::GitLab | LinkKind::Codeberg
+        )
+    }
+}
+
+/// One link of a README item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub url: String,
+    /// The link's own text, e.g. "docs" or "mirror"
+    pub text: String,
+    pub kind: LinkKind,
+}
+
+impl Link {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            text: String::new(),
+            kind: LinkKind::classify(url),
+        }
+    }
+}
+
+/// Whether a link leaves the README: anchors, relative paths and `mailto:` do not
+pub fn is_web_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Lowercase host of a web URL, without `www.` or a port
+pub fn host(url: &str) -> Option<String> {
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority
+        .rsplit('@')
+        .next()?
+        .split(':')
+        .next()?
+        .to_lowercase();
+    let host = host
+        .strip_prefix("www.")
+        .map_or(host.clone(), str::to_string);
+    (!host.is_empty()).then_some(host)
+}
+
+fn path_segments(url: &str) -> Vec<&str> {
+    let Some((_, rest)) = url.split_once("://") else {
+        return Vec::new();
+    };
+    rest.split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .split('/')
+        .skip(1)
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// The project path of a forge URL: `owner/repo` on GitHub and Codeberg, the full
+/// group path on GitLab, which nests subgroups and ends project paths at `/-/`
+pub fn repo_path(url: &str) -> Option<String> {
+    let segments = path_segments(url);
+    match host(url)?.as_str() {
+        "github.com" => {
+            let [owner, repo, rest @ ..] = segments.as_slice() else {
+                return None;
+            };
+            // Links into issues, wikis and releases are pages about a repository
+            if rest
+                .first()
+                .is_some_and(|s| matches!(*s, "issues" | "wiki" | "releases"))
+            {
+                return None;
+            }
+            Some(format!("{owner}/{}", repo.trim_end_matches(".git")))
+        }
+        "codeberg.org" => {
+            let [owner, repo, ..] = segments.as_slice() else {
+                return None;
+            };
+            Some(format!("{owner}/{}", repo.trim_end_matches(".git")))
+        }
+        "gitlab.com" => {
+            let project: Vec<&str> = segments.into_iter().take_while(|s| *s != "-").collect();
+            (project.len() >= 2).then(|| project.join("/").trim_end_matches(".git").to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Short form of a URL for display: the project path on forges, the package on
+/// the AUR, host and path elsewhere
+pub fn display(url: &str) -> String {
+    if let Some(path) = repo_path(url) {
+        return path;
+    }
+    match LinkKind::classify(url) {
+        LinkKind::Aur => {
+            let package = path_segments(url).get(1).copied().unwrap_or_default();
+            format!("aur/{package}")
+        }
+        _ => url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .trim_start_matches("www.")
+            .trim_end_matches('/')
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_are_classified_by_host() {
+        let cases = [
+            ("https://github.com/basecamp/omarchy", LinkKind::GitHub),
+            (
+                "https://github.com/basecamp/omarchy/tree/dev/themes",
+                LinkKind::GitHub,
+            ),
+            (
+                "https://github.com/basecamp/omarchy/issues/12",
+                LinkKind::Website,
+            ),
+            ("https://github.com/basecamp", LinkKind::Website),
+            ("https://gitlab.com/group/sub/project", LinkKind::GitLab),
+            ("https://codeberg.org/user/dots", LinkKind::Codeberg),
+            (
+                "https://aur.archlinux.org/packages/omarchy-bin",
+                LinkKind::Aur,
+            ),
+            ("https://www.youtube.com/watch?v=abc", LinkKind::Video),
+            ("https://youtu.be/abc", LinkKind::Video),
+            ("https://someone.substack.com/p/omarchy", LinkKind::Article),
+            ("https://example.com/blog/ricing-omarchy", LinkKind::Article),
+            ("https://omarchy.org", LinkKind::Website),
+        ];
+        for (url, kind) in cases {
+            assert_eq!(LinkKind::classify(url), kind, "{url}");
+        }
+    }
+
+    #[test]
+    fn test_urls_are_shortened_per_host() {
+        assert_eq!(
+            host("https://user@WWW.example.com:8080/x").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(host("docs/readme.md"), None);
+        assert_eq!(display("https://github.com/user/repo.git"), "user/repo");
+        assert_eq!(
+            display("https://gitlab.com/group/sub/project/-/tree/main"),
+            "group/sub/project"
+        );
+        assert_eq!(
+            display("https://aur.archlinux.org/packages/omarchy-bin"),
+            "aur/omarchy-bin"
+        );
+        assert_eq!(display("https://www.omarchy.org/"), "omarchy.org");
+        assert!(is_web_url("http://omarchy.org"));
+        assert!(!is_web_url("#themes"));
+    }
+}
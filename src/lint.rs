@@ -0,0 +1,358 @@
+use crate::duplicates;
+use crate::parser::{ItemWalk, ReadmeParser};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Problems the lint reports, each with a stable code for tooling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// A list item in a content section without a link, so it never becomes an entry
+    UnparsedItem,
+    MissingDescription,
+    DuplicateUrl,
+    /// Another entry's title differs only in case, punctuation or a letter or so
+    SimilarTitle,
+    Unsorted,
+    TrailingPunctuation,
+    MissingFromToc,
+}
+
+impl LintRule {
+    pub fn code(self) -> &'static str {
+        match self {
+            LintRule::UnparsedItem => "unparsed-item",
+            LintRule::MissingDescription => "missing-description",
+            LintRule::DuplicateUrl => "duplicate-url",
+            LintRule::SimilarTitle => "similar-title",
+            LintRule::Unsorted => "unsorted",
+            LintRule::TrailingPunctuation => "trailing-punctuation",
+            LintRule::MissingFromToc => "missing-from-toc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintIssue {
+    /// 1-based line in the README
+    pub line: usize,
+    pub rule: LintRule,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: [{}] {}", self.line, self.rule.code(), self.message)
+    }
+}
+
+#[derive(Default)]
+struct Heading {
+    level: u32,
+    line: usize,
+    text: String,
+}
+
+/// Check a README against the awesome-list conventions the parser relies on
+pub fn lint(markdown: &str) -> Vec<LintIssue> {
+    let parser = ReadmeParser::new();
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(markdown.match_indices('\n').map(|(offset, _)| offset + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+
+    let mut issues = Vec::new();
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut heading: Option<Heading> = None;
+    // Anchors linked from the table of contents, once one is seen
+    let mut toc: Option<HashSet<String>> = None;
+    let mut in_toc = false;
+    // Title of the current content section, `None` in skipped ones
+    let mut section: Option<String> = None;
+    let mut items = ItemWalk::default();
+    // Last entry title of each open list, for ordering
+    let mut previous_titles: Vec<Option<String>> = Vec::new();
+    let mut seen_urls: HashMap<String, (String, usize)> = HashMap::new();
+    // Title, URL key, section and line of every entry so far
+    let mut seen_titles: Vec<(String, String, String, usize)> = Vec::new();
+
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    for (event, range) in Parser::new_ext(markdown, options).into_offset_iter() {
+        let item = if heading.is_none() {
+            items.event(&event, range.start)
+        } else {
+            None
+        };
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                heading = Some(Heading {
+                    level: level as u32,
+                    line: line_of(range.start),
+                    text: String::new(),
+                });
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let Some(mut done) = heading.take() else {
+                    continue;
+                };
+                done.text = done.text.trim().to_string();
+                in_toc = done.level >= 2 && is_toc_heading(&done.text);
+                if in_toc {
+                    toc.get_or_insert_with(HashSet::new);
+                }
+                if done.level >= 2 {
+                    section = parser
+                        .should_parse_section(&done.text)
+                        .then(|| done.text.clone());
+                }
+                headings.push(done);
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = &mut heading {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                if in_toc && let Some(anchor) = dest_url.strip_prefix('#') {
+                    toc.get_or_insert_with(HashSet::new)
+                        .insert(normalize_anchor(anchor));
+                }
+            }
+            Event::Start(Tag::List(_)) => previous_titles.push(None),
+            Event::End(TagEnd::List(_)) => {
+                previous_titles.pop();
+            }
+            _ => {}
+        }
+
+        // The walk folds an entry's sub-bullets into its links
+        let Some(item) = item else {
+            continue;
+        };
+        if in_toc {
+            continue;
+        }
+        let Some(section) = &section else {
+            continue;
+        };
+        let line = line_of(item.offset);
+
+        let text = item.text.trim();
+        if item.links.is_empty() {
+            // A plain label grouping linked sub-bullets is fine
+            if !item.linked_children && !text.is_empty() {
+                issues.push(LintIssue {
+                    line,
+                    rule: LintRule::UnparsedItem,
+                    message: format!(
+                        "\"{}\" has no link, so it is not an entry",
+                        first_line(text)
+                    ),
+                });
+            }
+            continue;
+        }
+
+        let entry = parser.extract_repository_entry(text, item.links);
+        check_description(text, &entry.title, &entry.description, line, &mut issues);
+
+        let key = duplicates::url_key(&entry.url);
+        match seen_urls.get(&key) {
+            Some((first_section, first_line)) => issues.push(LintIssue {
+                line,
+                rule: LintRule::DuplicateUrl,
+                message: format!(
+                    "{} is already listed in {first_section} (line {first_line})",
+                    entry.url
+                ),
+            }),
+            None => {
+                seen_urls.insert(key.clone(), (section.clone(), line));
+            }
+        }
+
+        // Same URLs are reported above already
+        if let Some((title, _, other_section, other_line)) =
+            seen_titles.iter().find(|(title, other_key, ..)| {
+                *other_key != key && duplicates::similar_titles(title, &entry.title)
+            })
+        {
+            issues.push(LintIssue {
+                line,
+                rule: LintRule::SimilarTitle,
+                message: format!(
+                    "\"{}\" looks like \"{title}\" in {other_section} (line {other_line})",
+                    entry.title
+                ),
+            });
+        }
+        seen_titles.push((entry.title.clone(), key, section.clone(), line));
+
+        if let Some(previous) = previous_titles.last_mut() {
+            let title = entry.title.to_lowercase();
+            if let Some(before) = previous.as_deref()
+                && title < before.to_lowercase()
+            {
+                issues.push(LintIssue {
+                    line,
+                    rule: LintRule::Unsorted,
+                    message: format!("\"{}\" should come before \"{before}\"", entry.title),
+                });
+            }
+            *previous = Some(entry.title);
+        }
+    }
+
+    // Every content heading below the table of contents should be linked from it
+    if let Some(anchors) = toc {
+        let toc_line = headings
+            .iter()
+            .find(|h| h.level >= 2 && is_toc_heading(&h.text))
+            .map_or(0, |h| h.line);
+        for heading in headings
+            .iter()
+            .filter(|h| h.level >= 2 && h.line > toc_line)
+        {
+            if parser.should_parse_section(&heading.text)
+                && !anchors.contains(&normalize_anchor(&slug(&heading.text)))
+            {
+                issues.push(LintIssue {
+                    line: heading.line,
+                    rule: LintRule::MissingFromToc,
+                    message: format!("\"{}\" is missing from the table of contents", heading.text),
+                });
+            }
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+fn check_description(
+    text: &str,
+    title: &str,
+    description: &str,
+    line: usize,
+    issues: &mut Vec<LintIssue>,
+) {
+    if !text.contains(" - ") || description.is_empty() {
+        let hint = if text.contains(": ") {
+            "uses \": \" instead of a \" - \" description"
+        } else {
+            "has no \" - \" description"
+        };
+        issues.push(LintIssue {
+            line,
+            rule: LintRule::MissingDescription,
+            message: format!("\"{title}\" {hint}"),
+        });
+        return;
+    }
+
+    let trimmed = description.trim_end_matches(['.', '!', '?']);
+    let marks = &description[trimmed.len()..];
+    let problem = if marks.chars().count() > 1 && marks != "..." {
+        Some(format!("repeated \"{marks}\""))
+    } else {
+        description
+            .chars()
+            .last()
+            .filter(|c| matches!(c, ',' | ';' | ':' | '-'))
+            .map(|c| format!("dangling \"{c}\""))
+    };
+    if let Some(problem) = problem {
+        issues.push(LintIssue {
+            line,
+            rule: LintRule::TrailingPunctuation,
+            message: format!("description of \"{title}\" ends with {problem}"),
+        });
+    }
+}
+
+fn is_toc_heading(text: &str) -> bool {
+    text.to_lowercase().contains("contents")
+}
+
+/// GitHub's anchor for a heading: lowercase, punctuation and emoji dropped, spaces as `-`
+fn slug(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Anchors compare without the hyphens leading emoji leave behind
+fn normalize_anchor(anchor: &str) -> String {
+    anchor.trim_matches('-').to_lowercase()
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = r#"# Awesome Omarchy
+
+## Contents
+
+- [Tools](#-tools)
+- [Themes](#themes)
+
+## 🛠 Tools
+
+- [beta](https://github.com/a/beta) - A tool.
+- [alpha](https://github.com/a/alpha) - Another tool,
+- [gamma](https://github.com/a/gamma): No dash
+- Just some text
+- Bars
+  - [bar](https://github.com/a/bar) - A bar.
+
+## Themes
+
+- [dusk](https://github.com/a/beta/) - Same repository again!!
+
+## Utilities
+
+- [util](https://codeberg.org/a/util) - Fine.
+- [Util](https://gitlab.com/b/util) - Same name, other host.
+"#;
+
+    #[test]
+    fn test_lint_reports_each_rule_with_its_line() {
+        let found: Vec<(usize, &str)> = lint(README)
+            .iter()
+            .map(|issue| (issue.line, issue.rule.code()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (11, "trailing-punctuation"),
+                (11, "unsorted"),
+                (12, "missing-description"),
+                (13, "unparsed-item"),
+                (19, "trailing-punctuation"),
+                (19, "duplicate-url"),
+                (21, "missing-from-toc"),
+                (24, "similar-title"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clean_readme_has_no_issues() {
+        let readme = "# Awesome\n\n## Tools\n\n- [a](https://github.com/x/a) - One.\n- [b](https://gitlab.com/x/b) - Two.\n";
+        assert!(lint(readme).is_empty());
+        assert_eq!(slug("🛠 Tools & Utilities"), "-tools--utilities");
+    }
+}
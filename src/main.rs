@@ -1,13 +1,16 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{Terminal, backend::CrosstermBackend};
-use std::io;
-use tokio::time::Duration;
+use ratatui::{Terminal, backend::CrosstermBackend, layout::Rect};
+use std::io::{self, Write};
+use std::path::PathBuf;
 
 #[cfg(feature = "updater")]
 use sha2::{Digest, Sha256};
@@ -16,16 +19,39 @@ use std::{fs, path::Path};
 
 mod app;
 mod boot_screen;
+mod builder;
+mod checker;
 mod client;
+mod color;
+mod config;
+mod duplicates;
 mod events;
+mod export;
+mod highlight;
+mod history;
+mod links;
+mod lint;
+mod markdown;
 mod models;
+mod omarchy;
 mod parser;
+mod tags;
+mod tasks;
+mod terminal;
+mod theme_cache;
 mod ui;
+mod wallpaper;
+mod workspace;
 
 use app::App;
 use boot_screen::BootScreen;
 use client::HttpClient;
+use config::Config;
 use events::EventHandler;
+use export::ExportFormat;
+use tasks::TaskManager;
+use terminal::GraphicsProtocol;
+use workspace::Workspace;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -35,6 +61,14 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Directory repositories are cloned into (default: $AWSOMARCHY_WORKSPACE or ~/src)
+    #[arg(long, global = true, value_name = "DIR")]
+    workspace: Option<PathBuf>,
+
+    /// File to highlight in the theme preview's code editor
+    #[arg(long, global = true, value_name = "FILE")]
+    sample: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -48,6 +82,45 @@ enum Commands {
     },
     /// Show version information
     Version,
+    /// Write a theme's palette in another terminal's config format
+    Export {
+        /// Theme name from awesome-omarchy, or a path to an alacritty, kitty, Ghostty, base16 or btop theme file
+        theme: String,
+        #[arg(short, long, value_enum)]
+        format: ExportFormat,
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Check an awesome-list README for formatting problems (exit code 1 when any are found)
+    Lint {
+        /// The README.md to check
+        readme: PathBuf,
+        /// Print the problems as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Find dead links, redirects and archived or moved repositories (exit code 1 when any are found)
+    Check {
+        /// Check this README instead of the published awesome-omarchy one
+        #[arg(long, value_name = "FILE")]
+        readme: Option<PathBuf>,
+        /// Requests in flight at once
+        #[arg(long, default_value_t = checker::CHECK_REQUESTS)]
+        parallel: usize,
+        /// Print every result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List entries added, removed, moved or reworded in the README since a date
+    Changes {
+        /// Compare against the README as it was on this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: String,
+        /// Print the changes as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[tokio::main]
@@ -71,16 +144,158 @@ async fn main() -> Result<()> {
             println!("• 🔄 Self-updating capability");
             return Ok(());
         }
+        Some(Commands::Export {
+            theme,
+            format,
+            output,
+        }) => {
+            let theme = export::resolve_theme(&HttpClient::new(), &theme).await?;
+            let content = export::export(&theme, format);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content)?;
+                    eprintln!(
+                        "Wrote {} as {} to {}",
+                        theme.name,
+                        format.label(),
+                        path.display()
+                    );
+                }
+                None => print!("{content}"),
+            }
+            return Ok(());
+        }
+        Some(Commands::Lint { readme, json }) => {
+            let markdown = match std::fs::read_to_string(&readme) {
+                Ok(markdown) => markdown,
+                Err(e) => {
+                    eprintln!("Cannot read {}: {e}", readme.display());
+                    std::process::exit(2);
+                }
+            };
+            let issues = lint::lint(&markdown);
+            if json {
+                let report = serde_json::json!({
+                    "file": readme.display().to_string(),
+                    "count": issues.len(),
+                    "issues": issues,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                for issue in &issues {
+                    println!("{}:{issue}", readme.display());
+                }
+                match issues.len() {
+                    0 => println!("✅ No problems found in {}", readme.display()),
+                    count => println!("\n❌ {count} problem(s) found"),
+                }
+            }
+            if !issues.is_empty() {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Check {
+            readme,
+            parallel,
+            json,
+        }) => {
+            let client = HttpClient::new();
+            let content = match readme {
+                Some(path) => parser::ReadmeParser::new().parse(&std::fs::read_to_string(path)?)?,
+                None => client.fetch_readme(false).await?,
+            };
+            let urls: Vec<String> = content
+                .sections
+                .iter()
+                .flat_map(|section| &section.entries)
+                .map(|entry| entry.url.clone())
+                .collect();
+            eprintln!("Checking {} links...", urls.len());
+
+            let mut link_checker = checker::Checker::new(client);
+            link_checker.parallel = parallel;
+            let statuses = link_checker.check_all(urls).await;
+            let broken = statuses
+                .values()
+                .filter(|status| status.is_broken())
+                .count();
+            if json {
+                let mut results: Vec<_> = statuses.iter().collect();
+                results.sort_by_key(|(url, _)| url.as_str());
+                let results: Vec<_> = results
+                    .into_iter()
+                    .map(|(url, status)| serde_json::json!({ "url": url, "result": status }))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&results)?);
+            } else if statuses.values().all(checker::LinkStatus::is_ok) {
+                println!("✅ {}", checker::summary(&statuses));
+            } else {
+                print!("{}", checker::report(&content, &statuses));
+                println!("{}", checker::summary(&statuses));
+            }
+            if broken > 0 {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Changes { since, json }) => {
+            let since = history::parse_date(&since)?;
+            let client = HttpClient::new();
+            // Snapshot the current README first, so the comparison reaches today
+            if let Err(e) = client.fetch_readme(true).await {
+                eprintln!("Comparing saved snapshots only, the README could not be fetched: {e:#}");
+            }
+            let history = client.history();
+            let (Some(oldest), Some(latest)) = (history.times().first().copied(), history.latest())
+            else {
+                anyhow::bail!("No README snapshots saved yet");
+            };
+            let baseline = match history.at(since) {
+                Some(baseline) => baseline,
+                None => {
+                    eprintln!(
+                        "History starts on {}, comparing against that",
+                        history::format_date(oldest)
+                    );
+                    history
+                        .load(oldest)
+                        .ok_or_else(|| anyhow::anyhow!("Cannot read the oldest snapshot"))?
+                }
+            };
+            let changes = history::diff(&baseline, &latest);
+            if json {
+                let report = serde_json::json!({
+                    "since": history::format_date(baseline.taken_at),
+                    "until": history::format_date(latest.taken_at),
+                    "changes": changes,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else if changes.is_empty() {
+                println!(
+                    "No changes since {}",
+                    history::format_date(baseline.taken_at)
+                );
+            } else {
+                println!(
+                    "Since {}: {}\n",
+                    history::format_date(baseline.taken_at),
+                    changes.summary()
+                );
+                print!("{}", history::report(&changes));
+            }
+            return Ok(());
+        }
         None => {
             // Run the TUI application
-            run_tui().await?;
+            run_tui(cli.workspace, cli.sample).await?;
         }
     }
 
     Ok(())
 }
 
-async fn run_tui() -> Result<()> {
+async fn run_tui(workspace: Option<PathBuf>, sample: Option<PathBuf>) -> Result<()> {
     // Get terminal size for boot screen scaling
     let (width, height) = crossterm::terminal::size()?;
 
@@ -100,13 +315,37 @@ async fn run_tui() -> Result<()> {
     // Now start the main TUI application
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste,
+        EnableFocusChange
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     let client = HttpClient::new();
-    let mut app = App::new(client).await?;
-    let mut event_handler = EventHandler::new();
+    let (tasks, messages) = TaskManager::channel();
+    let mut app = App::new(client, tasks);
+    if let Some(root) = workspace {
+        app.workspace = Workspace::new(root);
+    }
+    if let Some(path) = Config::default_path()
+        && let Err(e) = app.load_config(path)
+    {
+        app.status_message = Some(format!("Using default settings: {e:#}"));
+    }
+    app.color_depth = app.config.color_depth.unwrap_or_else(terminal::color_depth);
+    app.graphics = terminal::graphics_protocol();
+    if let Some(path) = sample.or_else(|| app.config.sample_file.clone())
+        && let Err(e) = app.load_code_sample(&path)
+    {
+        app.status_message = Some(format!("{e:#}"));
+    }
+    app.start_visit();
+    app.load_readme(false);
+    let mut event_handler = EventHandler::new(messages);
 
     let result = run_app(&mut terminal, &mut app, &mut event_handler).await;
 
@@ -114,7 +353,9 @@ async fn run_tui() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
 
@@ -629,33 +870,45 @@ async fn run_app(
     app: &mut App,
     event_handler: &mut EventHandler,
 ) -> Result<()> {
-    let mut tick_interval = tokio::time::interval(Duration::from_millis(250));
+    let mut needs_redraw = true;
+    let hyperlinks = terminal::supports_hyperlinks();
+    // Wallpaper currently drawn with a graphics protocol
+    let mut placed: Option<(Rect, String)> = None;
 
     loop {
-        terminal.draw(|f| ui::draw(f, app))?;
+        // Only redraw when an event actually changed something
+        if needs_redraw {
+            let mut frame = terminal.draw(|f| ui::draw(f, app))?;
+
+            if app.layout.wallpaper != placed {
+                if app.graphics == GraphicsProtocol::Sixel && placed.is_some() {
+                    // Repaint every cell so the old sixel pixels disappear
+                    terminal.clear()?;
+                    frame = terminal.draw(|f| ui::draw(f, app))?;
+                }
+                placed = app.layout.wallpaper.clone();
+                let wallpaper = placed
+                    .as_ref()
+                    .and_then(|(_, name)| app.wallpapers.get(name).flatten());
+                let area = placed.as_ref().map(|(area, _)| *area).unwrap_or_default();
+                let overlay = terminal::graphics_overlay(app.graphics, area, wallpaper)?;
+                let mut stdout = io::stdout();
+                stdout.write_all(&overlay)?;
+                stdout.flush()?;
+            }
 
-        tokio::select! {
-                    _ = tick_interval.tick() => {
-                        app.on_tick().await;
-                    }
-                    event_result = event_handler.next() => {
-                        match event_result? {
-                            Event::Key(key) => {
-                                match key.code {
-                                    KeyCode::Char('q') => return Ok(()),
-                                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
-                                    _ => {
-                                        app.handle_key_event(key).await?;
+            // Popups cover the links, so only decorate the plain list view
+            if hyperlinks && app.layout.popup.is_none() && !app.layout.hyperlinks.is_empty() {
+                let overlay = terminal::hyperlink_overlay(frame.buffer, &app.layout.hyperlinks);
+                let mut stdout = io::stdout();
+                stdout.write_all(&overlay)?;
+                stdout.flush()?;
             }
         }
-                            }
-                            Event::Resize(width, height) => {
-                                app.handle_resize(width, height);
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+
+        event_handler.set_ticking(app.is_animating());
+        let event = event_handler.next().await?;
+        needs_redraw = app.handle_event(event).await?;
 
         if app.should_quit() {
             return Ok(());
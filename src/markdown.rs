@@ -0,0 +1,332 @@
+use pulldown_cmark::{CodeBlockKind, Event, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+
+/// A run of text sharing one style
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Inline {
+    pub text: String,
+    #[serde(default)]
+    pub style: InlineStyle,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct InlineStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub strikethrough: bool,
+    pub code: bool,
+    /// Target of the link or image the text belongs to
+    pub link: Option<String>,
+    pub image: bool,
+}
+
+/// Block level markdown of a section, kept for rendering it as a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Block {
+    /// Inline runs; a `"\n"` run is a hard line break
+    Paragraph(Vec<Inline>),
+    /// A heading that did not start a section of its own
+    Heading {
+        level: u32,
+        text: String,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+    /// Items of blocks, numbered from `start` when ordered
+    List {
+        start: Option<u64>,
+        items: Vec<Vec<Block>>,
+    },
+    Quote(Vec<Block>),
+    Table {
+        header: Vec<Vec<Inline>>,
+        rows: Vec<Vec<Vec<Inline>>>,
+    },
+    Rule,
+}
+
+/// Containers open while their events are read
+#[derive(Debug)]
+enum Container {
+    Quote(Vec<Block>),
+    List {
+        start: Option<u64>,
+        items: Vec<Vec<Block>>,
+    },
+    Item(Vec<Block>),
+}
+
+#[derive(Debug, Default)]
+struct TableState {
+    header: Vec<Vec<Inline>>,
+    rows: Vec<Vec<Vec<Inline>>>,
+    row: Vec<Vec<Inline>>,
+}
+
+/// Builds blocks from the body events of one section
+///
+/// Heading events are skipped, since headings decide where sections start;
+/// the README parser pushes the ones inside a section with [`BlockBuilder::push`].
+#[derive(Debug, Default)]
+pub struct BlockBuilder {
+    blocks: Vec<Block>,
+    containers: Vec<Container>,
+    inlines: Vec<Inline>,
+    style: InlineStyle,
+    bold: usize,
+    italic: usize,
+    strikethrough: usize,
+    code: Option<(Option<String>, String)>,
+    table: Option<TableState>,
+}
+
+impl BlockBuilder {
+    pub fn event(&mut self, event: &Event) {
+        match event {
+            Event::Start(Tag::Paragraph | Tag::Heading { .. })
+            | Event::End(TagEnd::Paragraph | TagEnd::Heading(_)) => self.flush(),
+            Event::Start(Tag::BlockQuote(_)) => {
+                self.flush();
+                self.containers.push(Container::Quote(Vec::new()));
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                self.flush();
+                if let Some(Container::Quote(blocks)) = self.containers.pop() {
+                    self.push(Block::Quote(blocks));
+                }
+            }
+            Event::Start(Tag::List(start)) => {
+                self.flush();
+                self.containers.push(Container::List {
+                    start: *start,
+                    items: Vec::new(),
+                });
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.flush();
+                if let Some(Container::List { start, items }) = self.containers.pop() {
+                    self.push(Block::List { start, items });
+                }
+            }
+            Event::Start(Tag::Item) => {
+                self.flush();
+                self.containers.push(Container::Item(Vec::new()));
+            }
+            Event::End(TagEnd::Item) => {
+                self.flush();
+                if let Some(Container::Item(blocks)) = self.containers.pop()
+                    && let Some(Container::List { items, .. }) = self.containers.last_mut()
+                {
+                    items.push(blocks);
+                }
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                self.flush();
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .map(|language| language.to_string()),
+                    CodeBlockKind::Indented => None,
+                };
+                self.code = Some((language, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, mut code)) = self.code.take() {
+                    code.truncate(code.trim_end().len());
+                    self.push(Block::Code { language, code });
+                }
+            }
+            Event::Start(Tag::Table(_)) => {
+                self.flush();
+                self.table = Some(TableState::default());
+            }
+            Event::End(TagEnd::TableCell) => {
+                let cell = std::mem::take(&mut self.inlines);
+                if let Some(table) = &mut self.table {
+                    table.row.push(cell);
+                }
+            }
+            Event::End(TagEnd::TableHead) => {
+                if let Some(table) = &mut self.table {
+                    table.header = std::mem::take(&mut table.row);
+                }
+            }
+            Event::End(TagEnd::TableRow) => {
+                if let Some(table) = &mut self.table {
+                    let row = std::mem::take(&mut table.row);
+                    table.rows.push(row);
+                }
+            }
+            Event::End(TagEnd::Table) => {
+                if let Some(table) = self.table.take() {
+                    self.push(Block::Table {
+                        header: table.header,
+                        rows: table.rows,
+                    });
+                }
+            }
+            Event::Start(Tag::Strong) => self.bold += 1,
+            Event::End(TagEnd::Strong) => self.bold = self.bold.saturating_sub(1),
+            Event::Start(Tag::Emphasis) => self.italic += 1,
+            Event::End(TagEnd::Emphasis) => self.italic = self.italic.saturating_sub(1),
+            Event::Start(Tag::Strikethrough) => self.strikethrough += 1,
+            Event::End(TagEnd::Strikethrough) => {
+                self.strikethrough = self.strikethrough.saturating_sub(1);
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                self.style.link = Some(dest_url.to_string());
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                self.style.link = Some(dest_url.to_string());
+                self.style.image = true;
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                self.style.link = None;
+                self.style.image = false;
+            }
+            Event::Text(text) => match &mut self.code {
+                Some((_, code)) => code.push_str(text),
+                None => self.text(text, false),
+            },
+            Event::Code(code) => self.text(code, true),
+            Event::SoftBreak => self.text(" ", false),
+            Event::HardBreak => self.text("\n", false),
+            Event::Rule => {
+                self.flush();
+                self.push(Block::Rule);
+            }
+            _ => {}
+        }
+    }
+
+    /// Add a finished block at the current position
+    pub fn push(&mut self, block: Block) {
+        self.flush();
+        match self.containers.last_mut() {
+            Some(Container::Quote(blocks) | Container::Item(blocks)) => blocks.push(block),
+            // Lists only hold items; anything else closes them
+            Some(Container::List { .. }) | None => self.blocks.push(block),
+        }
+    }
+
+    pub fn finish(mut self) -> Vec<Block> {
+        self.flush();
+        self.blocks
+    }
+
+    fn text(&mut self, text: &str, code: bool) {
+        let style = InlineStyle {
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            strikethrough: self.strikethrough > 0,
+            code,
+            ..self.style.clone()
+        };
+        // Runs in one style join up, as pulldown splits text at entities and breaks
+        match self.inlines.last_mut() {
+            Some(last) if last.style == style && text != "\n" && last.text != "\n" => {
+                last.text.push_str(text);
+            }
+            _ => self.inlines.push(Inline {
+                text: text.to_string(),
+                style,
+            }),
+        }
+    }
+
+    /// Close the paragraph being read, unless it is a table cell
+    fn flush(&mut self) {
+        if self.table.is_some() || self.inlines.is_empty() {
+            return;
+        }
+        let inlines = std::mem::take(&mut self.inlines);
+        if inlines.iter().all(|inline| inline.text.trim().is_empty()) {
+            return;
+        }
+        self.push(Block::Paragraph(inlines));
+    }
+}
+
+/// Plain text of inline runs
+pub fn plain_text(inlines: &[Inline]) -> String {
+    inlines.iter().map(|inline| inline.text.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::{Options, Parser};
+
+    fn blocks(markdown: &str) -> Vec<Block> {
+        let mut builder = BlockBuilder::default();
+        for event in Parser::new_ext(markdown, Options::ENABLE_TABLES) {
+            builder.event(&event);
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_inline_styles_and_code_blocks() {
+        let blocks = blocks(
+            "Install **with** *care*, see [docs](https://x.dev) and `omarchy-update`.\n\n\
+             ```bash\nsudo pacman -S foo\n```\n\n> Quoted\n\n---\n",
+        );
+        let Block::Paragraph(inlines) = &blocks[0] else {
+            panic!("expected a paragraph, got {:?}", blocks[0]);
+        };
+        let styled: Vec<(&str, bool, bool, bool, bool)> = inlines
+            .iter()
+            .map(|i| {
+                let s = &i.style;
+                (i.text.as_str(), s.bold, s.italic, s.code, s.link.is_some())
+            })
+            .collect();
+        assert_eq!(
+            styled,
+            [
+                ("Install ", false, false, false, false),
+                ("with", true, false, false, false),
+                (" ", false, false, false, false),
+                ("care", false, true, false, false),
+                (", see ", false, false, false, false),
+                ("docs", false, false, false, true),
+                (" and ", false, false, false, false),
+                ("omarchy-update", false, false, true, false),
+                (".", false, false, false, false),
+            ]
+        );
+        assert_eq!(
+            blocks[1],
+            Block::Code {
+                language: Some("bash".to_string()),
+                code: "sudo pacman -S foo".to_string()
+            }
+        );
+        assert!(matches!(&blocks[2], Block::Quote(inner) if inner.len() == 1));
+        assert_eq!(blocks[3], Block::Rule);
+    }
+
+    #[test]
+    fn test_nested_lists_and_tables() {
+        let blocks = blocks(
+            "1. First\n   - nested\n2. Second\n\n| Key | Action |\n|-----|--------|\n| `j` | Down |\n",
+        );
+        let Block::List { start, items } = &blocks[0] else {
+            panic!("expected a list, got {:?}", blocks[0]);
+        };
+        assert_eq!(*start, Some(1));
+        assert_eq!(items.len(), 2);
+        assert!(matches!(&items[0][1], Block::List { start: None, items } if items.len() == 1));
+
+        let Block::Table { header, rows } = &blocks[1] else {
+            panic!("expected a table, got {:?}", blocks[1]);
+        };
+        let header: Vec<String> = header.iter().map(|cell| plain_text(cell)).collect();
+        assert_eq!(header, ["Key", "Action"]);
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0][0][0].style.code);
+    }
+}
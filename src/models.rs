@@ -1,5 +1,13 @@
+use crate::builder::ThemeBuilder;
+use crate::color::{ContrastReport, Deficiency};
+use crate::duplicates::DuplicateGroup;
+use crate::links::{self, Link, LinkKind};
+use crate::markdown::Block;
+use crate::workspace::GitAction;
+use ratatui::layout::{Direction, Position, Rect};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum FocusArea {
@@ -50,17 +58,99 @@ impl Default for ReadmeMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepositoryEntry {
     pub title: String,
+    /// The item's first link, which it is opened and cloned by
     pub url: String,
     pub description: String,
     pub tags: Vec<String>,
+    /// What `url` points at
+    #[serde(default)]
+    pub kind: LinkKind,
+    /// Every link of the item, its nested sub-bullets' included, `url` first
+    #[serde(default)]
+    pub links: Vec<Link>,
 }
 
+impl RepositoryEntry {
+    /// The `owner/repo` part of a forge URL, if it has one
+    pub fn owner_repo(&self) -> Option<String> {
+        links::repo_path(&self.url)
+    }
+
+    /// An untagged entry with a single link, as tests build them
+    #[cfg(test)]
+    pub fn sample(title: &str, url: &str, description: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            url: url.to_string(),
+            description: description.to_string(),
+            tags: Vec::new(),
+            kind: LinkKind::classify(url),
+            links: Vec::new(),
+        }
+    }
+}
+
+/// What the yank action copies for the selected entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YankFormat {
+    Url,
+    OwnerRepo,
+    Markdown,
+    CloneCommand,
+}
+
+impl YankFormat {
+    /// Pick the format for the key pressed after `y`
+    pub fn from_key(key: char) -> Option<Self> {
+        match key {
+            'u' | 'y' => Some(YankFormat::Url),
+            'r' => Some(YankFormat::OwnerRepo),
+            'm' => Some(YankFormat::Markdown),
+            'c' => Some(YankFormat::CloneCommand),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            YankFormat::Url => "URL",
+            YankFormat::OwnerRepo => "owner/repo",
+            YankFormat::Markdown => "markdown link",
+            YankFormat::CloneCommand => "clone command",
+        }
+    }
+
+    pub fn render(&self, entry: &RepositoryEntry) -> String {
+        match self {
+            YankFormat::Url => entry.url.clone(),
+            YankFormat::OwnerRepo => entry.owner_repo().unwrap_or_else(|| entry.url.clone()),
+            YankFormat::Markdown => format!("[{}]({})", entry.title, entry.url),
+            YankFormat::CloneCommand => {
+                format!("git clone {}", crate::workspace::clone_url(&entry.url))
+            }
+        }
+    }
+}
+
+/// A heading of the README and the entries listed directly under it.
+///
+/// Sections are stored in document order; `parent` links each one to the
+/// nearest enclosing heading of a lower level, making the list a tree.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Section {
     pub title: String,
     pub entries: Vec<RepositoryEntry>,
     pub raw_content: String,
+    /// The section body as markdown blocks, rendered when it has no entries
+    #[serde(default)]
+    pub blocks: Vec<Block>,
     pub entry_count: usize,
+    /// Heading level, 2 for `##`
+    #[serde(default = "Section::default_level")]
+    pub level: u32,
+    /// Index of the enclosing section, `None` at the top level
+    #[serde(default)]
+    pub parent: Option<usize>,
 }
 
 impl Section {
@@ -69,8 +159,72 @@ impl Section {
             title,
             entries: Vec::new(),
             raw_content: String::new(),
+            blocks: Vec::new(),
             entry_count: 0,
+            level: Self::default_level(),
+            parent: None,
+        }
+    }
+
+    fn default_level() -> u32 {
+        2
+    }
+}
+
+impl ReadmeContent {
+    /// Indices of the sections enclosing `index`, nearest first
+    pub fn ancestors(&self, index: usize) -> Vec<usize> {
+        let mut ancestors = Vec::new();
+        let mut current = self.sections.get(index).and_then(|s| s.parent);
+        while let Some(parent) = current {
+            ancestors.push(parent);
+            current = self.sections.get(parent).and_then(|s| s.parent);
         }
+        ancestors
+    }
+
+    pub fn depth(&self, index: usize) -> usize {
+        self.ancestors(index).len()
+    }
+
+    pub fn has_children(&self, index: usize) -> bool {
+        self.sections
+            .get(index + 1)
+            .is_some_and(|next| next.parent == Some(index))
+    }
+
+    /// Entries of a section and of every section nested in it
+    pub fn total_entry_count(&self, index: usize) -> usize {
+        self.sections
+            .iter()
+            .enumerate()
+            .skip(index)
+            .take_while(|&(i, _)| i == index || self.ancestors(i).contains(&index))
+            .map(|(_, section)| section.entries.len())
+            .sum()
+    }
+
+    /// Put `section` before every other one, shifting the indices pointing at sections
+    pub fn prepend_section(&mut self, section: Section) {
+        for parent in self.sections.iter_mut().filter_map(|s| s.parent.as_mut()) {
+            *parent += 1;
+        }
+        for location in self.search_index.terms.values_mut().flatten() {
+            location.section_index += 1;
+        }
+        self.sections.insert(0, section);
+    }
+
+    /// Titles from the top-level section down to `index`
+    pub fn breadcrumb(&self, index: usize) -> String {
+        let mut path = self.ancestors(index);
+        path.reverse();
+        path.push(index);
+        path.iter()
+            .filter_map(|&i| self.sections.get(i))
+            .map(|section| section.title.as_str())
+            .collect::<Vec<_>>()
+            .join(" › ")
     }
 }
 
@@ -294,6 +448,46 @@ impl ListState {
     }
 }
 
+/// A clickable screen region mapped to the list index it displays
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HitRegion {
+    pub area: Rect,
+    pub index: usize,
+}
+
+/// Screen regions recorded while drawing the last frame, used for mouse hit-testing
+#[derive(Debug, Clone, Default)]
+pub struct UiLayout {
+    pub sidebar: Rect,
+    pub content: Rect,
+    pub tabs: Vec<HitRegion>,
+    pub entries: Vec<HitRegion>,
+    pub links: Vec<HitRegion>,
+    pub popup: Option<Rect>,
+    pub popup_items: Vec<HitRegion>,
+    /// Cells showing a URL, re-emitted as OSC 8 hyperlinks after each draw
+    pub hyperlinks: Vec<(Rect, String)>,
+    /// Cells a theme's wallpaper is drawn over with a terminal graphics protocol
+    pub wallpaper: Option<(Rect, String)>,
+}
+
+impl UiLayout {
+    /// Find the index of the region under the given cell
+    pub fn hit(regions: &[HitRegion], column: u16, row: u16) -> Option<usize> {
+        regions
+            .iter()
+            .find(|region| region.area.contains(Position::new(column, row)))
+            .map(|region| region.index)
+    }
+
+    /// Whether the cell lies on the border between sidebar and content
+    pub fn is_divider(&self, column: u16, row: u16) -> bool {
+        let on_row = row >= self.content.y && row < self.content.bottom();
+        let divider_columns = [self.sidebar.right().saturating_sub(1), self.content.x];
+        on_row && self.sidebar.width > 0 && divider_columns.contains(&column)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SearchResult {
     pub section_index: usize,
@@ -320,6 +514,74 @@ pub struct ThemeColors {
     pub bright: ThemeColorPalette,
 }
 
+impl ThemeColors {
+    /// Every color slot with its display name, in terminal order
+    pub fn slots(&self) -> Vec<(&'static str, &str)> {
+        let mut slots = vec![
+            ("background", self.background.as_str()),
+            ("foreground", self.foreground.as_str()),
+        ];
+        slots.extend(
+            ThemeColorPalette::NAMES
+                .iter()
+                .zip(self.normal.colors())
+                .map(|(name, hex)| (name.0, hex)),
+        );
+        slots.extend(
+            ThemeColorPalette::NAMES
+                .iter()
+                .zip(self.bright.colors())
+                .map(|(name, hex)| (name.1, hex)),
+        );
+        slots
+    }
+
+    /// Number of slots `slots` lists
+    pub const SLOT_COUNT: usize = 18;
+
+    /// Mutable access to a slot by its position in `slots`
+    pub fn slot_mut(&mut self, index: usize) -> Option<&mut String> {
+        let palette = match index {
+            0 => return Some(&mut self.background),
+            1 => return Some(&mut self.foreground),
+            2..10 => &mut self.normal,
+            10..18 => &mut self.bright,
+            _ => return None,
+        };
+        let color = match (index - 2) % 8 {
+            0 => &mut palette.black,
+            1 => &mut palette.red,
+            2 => &mut palette.green,
+            3 => &mut palette.yellow,
+            4 => &mut palette.blue,
+            5 => &mut palette.magenta,
+            6 => &mut palette.cyan,
+            _ => &mut palette.white,
+        };
+        Some(color)
+    }
+
+    /// Replace every color in place, e.g. to simulate or quantize a palette
+    pub fn map_colors(&mut self, f: impl Fn(&str) -> String) {
+        self.background = f(&self.background);
+        self.foreground = f(&self.foreground);
+        for palette in [&mut self.normal, &mut self.bright] {
+            for color in [
+                &mut palette.black,
+                &mut palette.red,
+                &mut palette.green,
+                &mut palette.yellow,
+                &mut palette.blue,
+                &mut palette.magenta,
+                &mut palette.cyan,
+                &mut palette.white,
+            ] {
+                *color = f(color);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemeColorPalette {
     pub black: String,
@@ -332,6 +594,43 @@ pub struct ThemeColorPalette {
     pub white: String,
 }
 
+/// What the sidebar lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SidebarView {
+    #[default]
+    Sections,
+    Tags,
+    Duplicates,
+}
+
+/// Tags of all entries, browsable from the sidebar
+#[derive(Debug, Clone, Default)]
+pub struct TagBrowserState {
+    /// Every tag with the number of entries carrying it, most used first
+    pub counts: Vec<(String, usize)>,
+    pub selected: usize,
+    /// Tag whose entries `n`/`N` step through, across sections
+    pub active: Option<String>,
+}
+
+/// Entries listed more than once, browsable from the sidebar
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateBrowserState {
+    pub groups: Vec<DuplicateGroup>,
+    pub selected: usize,
+    /// Member of the selected group `Enter` jumps to next
+    pub member: usize,
+}
+
+impl DuplicateBrowserState {
+    /// The group holding an entry, by section and entry index
+    pub fn group_of(&self, section: usize, entry: usize) -> Option<&DuplicateGroup> {
+        self.groups
+            .iter()
+            .find(|group| group.members.contains(&(section, entry)))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ThemeBrowserState {
     pub themes: Vec<Theme>,
@@ -343,6 +642,52 @@ pub struct ThemeBrowserState {
     pub search_query: String,
     pub filtered_themes: Vec<usize>, // Indices of themes matching search
     pub filtered_selected: Option<usize>, // Selected index in filtered results
+    /// Theme held in place while another is previewed next to it
+    pub pinned_theme: Option<Box<Theme>>,
+    pub compare_split: Direction,
+    /// Show the slot-by-slot diff table instead of the two previews
+    pub compare_diff: bool,
+    /// Colors fetched so far, keyed by theme name
+    pub loaded_themes: HashMap<String, Box<Theme>>,
+    /// Contrast of each theme in `loaded_themes`, worked out once as it loads
+    pub contrast: HashMap<String, ContrastReport>,
+    /// Preview the palette as seen with a color-vision deficiency
+    pub simulation: Option<Deficiency>,
+    /// Hide themes known to fall below this contrast ratio
+    pub min_contrast: Option<f64>,
+    pub sort_by_contrast: bool,
+    /// Which of the app's code samples the editor preview shows
+    pub sample_index: usize,
+    /// Palette being edited in the preview, as a new theme based on the previewed one
+    pub palette_editor: Option<ThemeBuilder>,
+}
+
+impl ThemeColorPalette {
+    /// Slot names for the normal and bright variants, in ANSI order
+    pub const NAMES: [(&'static str, &'static str); 8] = [
+        ("black", "bright black"),
+        ("red", "bright red"),
+        ("green", "bright green"),
+        ("yellow", "bright yellow"),
+        ("blue", "bright blue"),
+        ("magenta", "bright magenta"),
+        ("cyan", "bright cyan"),
+        ("white", "bright white"),
+    ];
+
+    /// The eight colors in ANSI order
+    pub fn colors(&self) -> [&str; 8] {
+        [
+            &self.black,
+            &self.red,
+            &self.green,
+            &self.yellow,
+            &self.blue,
+            &self.magenta,
+            &self.cyan,
+            &self.white,
+        ]
+    }
 }
 
 /// Global theme applicator for in-memory theme switching
@@ -368,7 +713,7 @@ impl ThemeApplicator {
         *self = Self::default();
     }
 
-    fn create_default_theme() -> Theme {
+    pub(crate) fn create_default_theme() -> Theme {
         Theme {
             name: "Default".to_string(),
             description: "Default TUI theme".to_string(),
@@ -403,17 +748,47 @@ impl ThemeApplicator {
 
 /// Simple theme entry extracted from README
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 #[allow(dead_code)]
 pub enum PreviewState {
+    #[default]
     None,
-    Loading,             // Theme loading in progress
+    Loading(String),     // Theme loading in progress (theme name)
     Applied(Box<Theme>), // Theme currently applied
-    Error,               // Error loading theme
+    Error(String),       // Why the theme could not be previewed
 }
 
-impl Default for PreviewState {
-    fn default() -> Self {
-        Self::None
+/// Log panel for cloning, updating and installing the selected repository
+#[derive(Debug, Clone)]
+pub struct CommandPanel {
+    pub name: String,
+    pub url: String,
+    pub checkout: PathBuf,
+    pub log: Vec<String>,
+    /// Lines scrolled up from the end of the log
+    pub scroll: usize,
+    pub state: CommandPanelState,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandPanelState {
+    /// A checkout already exists; waiting for the user to confirm a pull
+    ConfirmPull,
+    Running(GitAction),
+    /// The checkout ships an install script, shown for review before running it
+    ConfirmInstall(String),
+    Finished,
+}
+
+impl CommandPanel {
+    pub fn new(name: String, url: String, checkout: PathBuf, state: CommandPanelState) -> Self {
+        Self {
+            name,
+            url,
+            checkout,
+            log: Vec::new(),
+            scroll: 0,
+            state,
+        }
     }
 }
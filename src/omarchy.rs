@@ -0,0 +1,266 @@
+use crate::color::Rgb;
+use crate::export::{base16_palette, slug};
+use crate::models::Theme;
+use anyhow::{Context, Result, anyhow, bail};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Written into every generated file so a theme made here can be regenerated in place
+const MARKER: &str = "generated by awesome-omarchy-tui";
+
+/// The files of an Omarchy theme directory and the templates they are rendered from
+const TEMPLATES: [(&str, &str); 6] = [
+    (
+        "alacritty.toml",
+        include_str!("../templates/omarchy/alacritty.toml"),
+    ),
+    (
+        "btop.theme",
+        include_str!("../templates/omarchy/btop.theme"),
+    ),
+    (
+        "hyprland.conf",
+        include_str!("../templates/omarchy/hyprland.conf"),
+    ),
+    (
+        "waybar.css",
+        include_str!("../templates/omarchy/waybar.css"),
+    ),
+    (
+        "neovim.lua",
+        include_str!("../templates/omarchy/neovim.lua"),
+    ),
+    ("mako.ini", include_str!("../templates/omarchy/mako.ini")),
+];
+
+/// `~/.config/omarchy/themes`, where Omarchy looks for installed themes
+pub fn themes_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("omarchy").join("themes"))
+}
+
+/// Where a theme named `name` is installed
+pub fn theme_dir(name: &str) -> Result<PathBuf> {
+    let themes =
+        themes_dir().ok_or_else(|| anyhow!("No config directory to write the theme into"))?;
+    theme_dir_in(&themes, name)
+}
+
+/// The directory under `themes` for `name`; a name without letters or digits has none,
+/// as it would otherwise be the themes directory itself
+fn theme_dir_in(themes: &Path, name: &str) -> Result<PathBuf> {
+    let slug = slug(name);
+    if slug.is_empty() {
+        bail!("\"{name}\" needs a letter or digit to name a theme directory");
+    }
+    Ok(themes.join(slug))
+}
+
+/// Template variables: every slot as `#rrggbb` and, suffixed `_rgb`, bare hex for
+/// Hyprland's `rgb()`, plus the derived base16 palette as `base00`..`base0F`
+fn variables(theme: &Theme) -> HashMap<String, String> {
+    let mut variables = HashMap::from([("name".to_string(), theme.name.clone())]);
+    for (name, color) in theme.colors.slots() {
+        let hex = Rgb::from_hex(color).map_or_else(|| color.to_string(), Rgb::to_hex);
+        let key = name.replace(' ', "_");
+        variables.insert(
+            format!("{key}_rgb"),
+            hex.trim_start_matches('#').to_string(),
+        );
+        variables.insert(key, hex);
+    }
+    for (i, color) in base16_palette(theme).into_iter().enumerate() {
+        variables.insert(format!("base{i:02X}"), color.to_hex());
+    }
+    variables
+}
+
+/// Replace each `{{variable}}` in a template
+fn render(template: &str, variables: &HashMap<String, String>) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow!("Unclosed {{{{ in template"))?;
+        let key = after[..end].trim();
+        let value = variables
+            .get(key)
+            .ok_or_else(|| anyhow!("Unknown template variable {key}"))?;
+        out.push_str(value);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Render every file of an Omarchy theme, paired with its file name
+pub fn render_theme(theme: &Theme) -> Result<Vec<(&'static str, String)>> {
+    let variables = variables(theme);
+    TEMPLATES
+        .iter()
+        .map(|(file, template)| Ok((*file, render(template, &variables)?)))
+        .collect()
+}
+
+/// Write a full Omarchy theme into `dir`, returning the files written.
+///
+/// A directory holding a theme that was not generated here is left alone.
+pub fn write_theme(theme: &Theme, dir: &Path) -> Result<Vec<PathBuf>> {
+    let existing = dir.join(TEMPLATES[0].0);
+    if existing.exists()
+        && !fs::read_to_string(&existing).is_ok_and(|content| content.contains(MARKER))
+    {
+        bail!(
+            "{} already holds a theme; rename yours to write it elsewhere",
+            dir.display()
+        );
+    }
+
+    let files = render_theme(theme)?;
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    files
+        .into_iter()
+        .map(|(file, content)| {
+            let path = dir.join(file);
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ThemeColorPalette, ThemeColors};
+    use crate::parser::{ThemeFormat, ThemeParser};
+
+    fn palette(colors: [&str; 8]) -> ThemeColorPalette {
+        let [black, red, green, yellow, blue, magenta, cyan, white] = colors.map(String::from);
+        ThemeColorPalette {
+            black,
+            red,
+            green,
+            yellow,
+            blue,
+            magenta,
+            cyan,
+            white,
+        }
+    }
+
+    fn kanagawa() -> Theme {
+        Theme {
+            name: "Kanagawa Dusk".to_string(),
+            colors: ThemeColors {
+                background: "#1F1F28".to_string(),
+                foreground: "#dcd7ba".to_string(),
+                normal: palette([
+                    "#16161d", "#c34043", "#76946a", "#c0a36e", "#7e9cd8", "#957fb8", "#6a9589",
+                    "#c8c093",
+                ]),
+                bright: palette([
+                    "#727169", "#e82424", "#98bb6c", "#e6c384", "#7fb4ca", "#938aa9", "#7aa89f",
+                    "#dcd7ba",
+                ]),
+            },
+            source_url: String::new(),
+            description: String::new(),
+        }
+    }
+
+    fn read(dir: &Path, file: &str) -> String {
+        fs::read_to_string(dir.join(file)).unwrap()
+    }
+
+    #[test]
+    fn test_write_theme_generates_every_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = kanagawa();
+        let written = write_theme(&theme, dir.path()).unwrap();
+        assert_eq!(written.len(), TEMPLATES.len());
+
+        for (file, _) in TEMPLATES {
+            let content = read(dir.path(), file);
+            assert!(content.contains(MARKER), "{file}");
+            assert!(!content.contains("{{"), "{file}");
+        }
+
+        // Omarchy's own readers get the palette back
+        let parser = ThemeParser::new();
+        let alacritty = parser
+            .parse_format(
+                ThemeFormat::AlacrittyToml,
+                "kanagawa",
+                &read(dir.path(), "alacritty.toml"),
+            )
+            .unwrap();
+        assert_eq!(alacritty.colors.background, "#1f1f28");
+        assert_eq!(alacritty.colors.normal.blue, "#7e9cd8");
+        assert_eq!(alacritty.colors.bright.black, "#727169");
+
+        let btop = parser
+            .parse_btop_theme("kanagawa", &read(dir.path(), "btop.theme"))
+            .unwrap();
+        assert_eq!(btop.colors.background, "#1f1f28");
+        assert_eq!(btop.colors.foreground, "#dcd7ba");
+
+        let hyprland = read(dir.path(), "hyprland.conf");
+        assert!(hyprland.contains("$activeBorderColor = rgb(7e9cd8)"));
+        assert!(hyprland.contains("col.active_border = $activeBorderColor"));
+
+        let waybar = read(dir.path(), "waybar.css");
+        assert!(waybar.contains("@define-color background #1f1f28;"));
+        assert!(waybar.contains("@define-color foreground #dcd7ba;"));
+
+        let neovim = read(dir.path(), "neovim.lua");
+        assert!(neovim.contains(r##"base00 = "#1f1f28","##));
+        assert!(neovim.contains(r##"base0D = "#7e9cd8","##));
+
+        let mako = read(dir.path(), "mako.ini");
+        assert!(mako.contains("background-color=#1f1f28"));
+        assert!(mako.contains("border-color=#7e9cd8"));
+    }
+
+    #[test]
+    fn test_write_theme_only_replaces_its_own_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut theme = kanagawa();
+        write_theme(&theme, dir.path()).unwrap();
+
+        // Regenerating a theme made here overwrites it
+        theme.colors.background = "#000000".to_string();
+        write_theme(&theme, dir.path()).unwrap();
+        assert!(read(dir.path(), "waybar.css").contains("#000000"));
+
+        // A hand-written theme is never clobbered
+        fs::write(dir.path().join("alacritty.toml"), "[colors.primary]\n").unwrap();
+        assert!(write_theme(&theme, dir.path()).is_err());
+        assert_eq!(read(dir.path(), "alacritty.toml"), "[colors.primary]\n");
+    }
+
+    #[test]
+    fn test_theme_dir_needs_a_slug() {
+        let themes = Path::new("/themes");
+        assert_eq!(
+            theme_dir_in(themes, "Kanagawa Dusk").unwrap(),
+            themes.join("kanagawa-dusk")
+        );
+        assert!(theme_dir_in(themes, "!!!").is_err());
+        assert!(theme_dir_in(themes, "🌙 ✨").is_err());
+    }
+
+    #[test]
+    fn test_render_rejects_unknown_variables() {
+        let variables = variables(&kanagawa());
+        assert_eq!(
+            render("rgb({{ bright_blue_rgb }})", &variables).unwrap(),
+            "rgb(7fb4ca)"
+        );
+        assert!(render("{{purple}}", &variables).is_err());
+        assert!(render("{{red", &variables).is_err());
+    }
+}
@@ -1,11 +1,117 @@
+use crate::color::Rgb;
+use crate::links::{self, Link, LinkKind};
+use crate::markdown::{Block, BlockBuilder};
 use crate::models::ThemeEntry;
 use crate::models::{
     ReadmeContent, ReadmeMetadata, RepositoryEntry, SearchIndex, SearchLocation, SearchPriority,
     Section,
 };
 use crate::models::{Theme, ThemeColorPalette, ThemeColors};
+use crate::tags::Taxonomy;
 use anyhow::{Result, anyhow};
-use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use std::collections::HashMap;
+
+/// A list item while its events are read
+#[derive(Default)]
+struct ItemState {
+    offset: usize,
+    text: String,
+    links: Vec<Link>,
+    /// Inside one of `links`, whose text is being read
+    in_link: bool,
+    /// Whether any nested item carried a link
+    linked_children: bool,
+}
+
+impl ItemState {
+    fn push_str(&mut self, text: &str) {
+        self.text.push_str(text);
+        if self.in_link
+            && let Some(link) = self.links.last_mut()
+        {
+            link.text.push_str(text);
+        }
+    }
+}
+
+/// A list item read to its end, outside any item with links of its own
+#[derive(Debug, Default)]
+pub(crate) struct ListItem {
+    /// Byte offset of the item in the markdown
+    pub offset: usize,
+    pub text: String,
+    /// Web links in the item and its sub-bullets, the leading one first
+    pub links: Vec<Link>,
+    /// Whether any nested item carried a link
+    pub linked_children: bool,
+}
+
+/// Reads list items out of markdown events, shared by the parser and the lint
+#[derive(Default)]
+pub(crate) struct ItemWalk {
+    /// List items being read, nested sub-bullets on top
+    items: Vec<ItemState>,
+}
+
+impl ItemWalk {
+    /// Feed an event starting at `offset`, returning the item it closes if that item stands alone
+    pub(crate) fn event(&mut self, event: &Event, offset: usize) -> Option<ListItem> {
+        match event {
+            Event::Start(Tag::Item) => self.items.push(ItemState {
+                offset,
+                ..ItemState::default()
+            }),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                // Anchors and relative links stay inside the README
+                if let Some(item) = self.items.last_mut() {
+                    item.in_link = links::is_web_url(dest_url);
+                    if item.in_link {
+                        item.links.push(Link::new(dest_url));
+                    }
+                }
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some(item) = self.items.last_mut() {
+                    item.in_link = false;
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(item) = self.items.last_mut() {
+                    item.push_str(text);
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(item) = self.items.last_mut() {
+                    item.text.push('\n');
+                }
+            }
+            Event::End(TagEnd::Item) => {
+                let item = self.items.pop()?;
+                match self.items.last_mut() {
+                    // Sub-bullets of a linked item list more links for it, e.g. docs or mirrors
+                    Some(parent) if !parent.links.is_empty() => {
+                        parent.links.extend(item.links);
+                    }
+                    parent => {
+                        if let Some(parent) = parent {
+                            parent.linked_children |=
+                                !item.links.is_empty() || item.linked_children;
+                        }
+                        return Some(ListItem {
+                            offset: item.offset,
+                            text: item.text,
+                            links: item.links,
+                            linked_children: item.linked_children,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
 
 /// Parser for awesome-omarchy README markdown content
 pub struct ReadmeParser {
@@ -13,6 +119,8 @@ pub struct ReadmeParser {
     known_sections: Vec<&'static str>,
     /// Patterns to exclude from parsing (e.g., TOC, badges)
     exclusion_patterns: Vec<&'static str>,
+    /// Tags entries are matched against
+    taxonomy: Taxonomy,
 }
 
 impl ReadmeParser {
@@ -49,6 +157,7 @@ impl ReadmeParser {
                 "Awesome",
                 "Badge",
             ],
+            taxonomy: Taxonomy::default(),
         }
     }
 
@@ -58,67 +167,106 @@ impl ReadmeParser {
             return Err(anyhow!("Empty markdown content"));
         }
 
-        let parser = Parser::new(markdown_content);
+        let parser = Parser::new_ext(
+            markdown_content,
+            Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+        )
+        .into_offset_iter();
         let mut readme_content = ReadmeContent::default();
         let mut current_section: Option<Section> = None;
         let mut current_text = String::new();
         let mut is_in_header = false;
         let mut current_header_level = 0;
         let mut header_text = String::new();
-        let mut link_url = String::new();
-        let mut is_in_list_item = false;
-        let mut current_item_text = String::new(); // Text for current list item only
+        let mut items = ItemWalk::default();
         let mut metadata = ReadmeMetadata::default();
+        // Level and index of the sections enclosing the current position
+        let mut open_sections: Vec<(u32, usize)> = Vec::new();
+
+        // Body of the current section as markdown blocks
+        let mut blocks = BlockBuilder::default();
 
         // Extract title from first heading
         let mut title_extracted = false;
 
-        for event in parser {
+        for (event, range) in parser {
+            if !is_in_header {
+                blocks.event(&event);
+                // Every linked item is an entry, nested under a plain label or not
+                if let Some(item) = items.event(&event, range.start)
+                    && !item.links.is_empty()
+                    && !item.text.trim().is_empty()
+                    && let Some(ref mut section) = current_section
+                {
+                    let entry = self.extract_repository_entry(&item.text, item.links);
+                    section.entries.push(entry);
+                    section.entry_count += 1;
+                }
+            }
             match event {
                 Event::Start(Tag::Heading { level, .. }) => {
                     is_in_header = true;
                     current_header_level = level as u32;
                     header_text.clear();
                 }
-                Event::End(TagEnd::Heading(_)) => {
-                    if is_in_header {
-                        let header = header_text.trim().to_string();
+                Event::End(TagEnd::Heading(_)) if is_in_header => {
+                    let header = header_text.trim().to_string();
 
-                        // Extract title from first H1
-                        if !title_extracted && current_header_level == 1 {
-                            metadata.title = header.clone();
-                            title_extracted = true;
-                        }
+                    // Extract title from first H1
+                    if !title_extracted && current_header_level == 1 {
+                        metadata.title = header.clone();
+                        title_extracted = true;
+                    }
 
-                        // Check if this is a section header we should parse
-                        if current_header_level >= 2 && self.should_parse_section(&header) {
-                            // Save previous section if exists
-                            if let Some(section) = current_section.take() {
-                                readme_content.sections.push(section);
-                            }
+                    // A heading closes every open section at its level or deeper
+                    if current_header_level >= 2 {
+                        while open_sections
+                            .last()
+                            .is_some_and(|&(level, _)| level >= current_header_level)
+                        {
+                            open_sections.pop();
+                        }
+                    }
 
-                            // Start new section
-                            current_section = Some(Section::new(header));
-                            current_text.clear();
+                    // Check if this is a section header we should parse
+                    let starts_section =
+                        current_header_level >= 2 && self.should_parse_section(&header);
+
+                    // Save previous section if this heading ends it, even when the
+                    // heading itself is excluded (e.g. "## Contributing")
+                    let ends_section = current_section
+                        .as_ref()
+                        .is_some_and(|section| current_header_level <= section.level);
+                    if starts_section || ends_section {
+                        let body = std::mem::take(&mut blocks).finish();
+                        if let Some(mut section) = current_section.take() {
+                            section.blocks = body;
+                            readme_content.sections.push(section);
                         }
+                    }
 
-                        is_in_header = false;
+                    if starts_section {
+                        // Start new section, nested in the nearest open one above it
+                        let mut section = Section::new(header);
+                        section.level = current_header_level;
+                        section.parent = open_sections.last().map(|&(_, index)| index);
+                        open_sections.push((current_header_level, readme_content.sections.len()));
+                        current_section = Some(section);
+                        current_text.clear();
+                    } else if current_section.is_some() {
+                        blocks.push(Block::Heading {
+                            level: current_header_level,
+                            text: header,
+                        });
                     }
-                }
-                Event::Start(Tag::Link { dest_url, .. }) => {
-                    link_url = dest_url.to_string();
-                }
-                Event::End(TagEnd::Link) => {
-                    // Link ended, continue accumulating text for description
+
+                    is_in_header = false;
                 }
                 Event::Text(text) => {
                     if is_in_header {
                         header_text.push_str(&text);
                     } else {
                         current_text.push_str(&text);
-                        if is_in_list_item {
-                            current_item_text.push_str(&text); // Accumulate ALL text for current item
-                        }
                         if let Some(ref mut section) = current_section {
                             section.raw_content.push_str(&text);
                         }
@@ -129,60 +277,27 @@ impl ReadmeParser {
                         header_text.push_str(&code);
                     } else {
                         current_text.push_str(&code);
-                        if is_in_list_item {
-                            current_item_text.push_str(&code); // Accumulate code for current item only
-                        }
                         if let Some(ref mut section) = current_section {
                             section.raw_content.push_str(&code);
                         }
                     }
                 }
-                Event::Start(Tag::List(_)) => {
-                    if !is_in_header && !current_text.trim().is_empty() {
-                        current_text.push('\n');
-                    }
+                Event::Start(Tag::List(_)) if !is_in_header && !current_text.trim().is_empty() => {
+                    current_text.push('\n');
                 }
-                Event::Start(Tag::Item) => {
-                    if !is_in_header {
-                        current_text.push_str("• ");
-                        is_in_list_item = true;
-                        current_item_text.clear(); // Clear item text for new list item
-                    }
+                Event::Start(Tag::Item) if !is_in_header => {
+                    current_text.push_str("• ");
                 }
-                Event::End(TagEnd::Item) => {
-                    if !is_in_header {
-                        current_text.push('\n');
-                        if let Some(ref mut section) = current_section {
-                            section.raw_content.push('\n');
-                        }
-
-                        // Process the accumulated text for the current item if we have a GitHub link
-                        if is_in_list_item
-                            && !link_url.is_empty()
-                            && !current_item_text.trim().is_empty()
-                            && let Some(ref mut section) = current_section
-                            && self.is_github_link(&link_url)
-                        {
-                            let entry =
-                                self.extract_repository_entry(&current_item_text, &link_url);
-                            section.entries.push(entry);
-                            section.entry_count += 1;
-                        }
-
-                        is_in_list_item = false;
-                        current_item_text.clear();
-                        link_url.clear();
+                Event::End(TagEnd::Item) if !is_in_header => {
+                    current_text.push('\n');
+                    if let Some(ref mut section) = current_section {
+                        section.raw_content.push('\n');
                     }
                 }
-                Event::SoftBreak | Event::HardBreak => {
-                    if !is_in_header {
-                        current_text.push('\n');
-                        if is_in_list_item {
-                            current_item_text.push('\n');
-                        }
-                        if let Some(ref mut section) = current_section {
-                            section.raw_content.push('\n');
-                        }
+                Event::SoftBreak | Event::HardBreak if !is_in_header => {
+                    current_text.push('\n');
+                    if let Some(ref mut section) = current_section {
+                        section.raw_content.push('\n');
                     }
                 }
                 _ => {}
@@ -190,7 +305,8 @@ impl ReadmeParser {
         }
 
         // Save last section
-        if let Some(section) = current_section {
+        if let Some(mut section) = current_section {
+            section.blocks = blocks.finish();
             readme_content.sections.push(section);
         }
 
@@ -209,7 +325,7 @@ impl ReadmeParser {
         Ok(readme_content)
     }
 
-    fn should_parse_section(&self, header: &str) -> bool {
+    pub(crate) fn should_parse_section(&self, header: &str) -> bool {
         // Skip excluded patterns
         for pattern in &self.exclusion_patterns {
             if header.to_lowercase().contains(&pattern.to_lowercase()) {
@@ -255,23 +371,25 @@ impl ReadmeParser {
 
     /// Check if URL is a GitHub repository link
     fn is_github_link(&self, url: &str) -> bool {
-        url.starts_with("https://github.com/") && 
-            url.matches('/').count() >= 4 && // github.com/user/repo
-            !url.contains("/issues") && 
-            !url.contains("/wiki") && 
-            !url.contains("/releases")
+        LinkKind::classify(url) == LinkKind::GitHub
     }
 
-    /// Extract repository entry from link text and URL
-    fn extract_repository_entry(&self, text: &str, url: &str) -> RepositoryEntry {
+    /// Extract repository entry from item text and its links, the first one leading
+    pub(crate) fn extract_repository_entry(&self, text: &str, links: Vec<Link>) -> RepositoryEntry {
         let (title, description) = self.split_title_description(text);
         let tags = self.extract_tags(&description);
+        let (url, kind) = links
+            .first()
+            .map(|link| (link.url.clone(), link.kind))
+            .unwrap_or_default();
 
         RepositoryEntry {
             title,
-            url: url.to_string(),
+            url,
             description,
             tags,
+            kind,
+            links,
         }
     }
 
@@ -298,37 +416,9 @@ impl ReadmeParser {
         (text.to_string(), String::new())
     }
 
-    /// Extract tags from description text
+    /// Tags earned by description text, as whole words of the taxonomy
     fn extract_tags(&self, description: &str) -> Vec<String> {
-        let mut tags = Vec::new();
-        let description_lower = description.to_lowercase();
-
-        // Common tag patterns in awesome lists (removed generic "go" to prevent spurious matches)
-        let tag_indicators = vec![
-            ("rust", "rust"),
-            ("python", "python"),
-            ("javascript", "javascript"),
-            ("typescript", "typescript"),
-            ("golang", "go"), // More specific pattern for Go language
-            ("java", "java"),
-            ("c++", "cpp"),
-            ("cli", "command-line"),
-            ("web", "web"),
-            ("api", "api"),
-            ("tool", "tool"),
-            ("library", "library"),
-            ("framework", "framework"),
-            ("plugin", "plugin"),
-            ("extension", "extension"),
-        ];
-
-        for (pattern, tag) in tag_indicators {
-            if description_lower.contains(pattern) {
-                tags.push(tag.to_string());
-            }
-        }
-
-        tags
+        self.taxonomy.tags_for(description)
     }
 
     /// Extract theme entries from README content
@@ -504,6 +594,233 @@ impl ThemeParser {
         })
     }
 
+    /// Parse a theme file in any supported format
+    pub fn parse_format(
+        &self,
+        format: ThemeFormat,
+        theme_name: &str,
+        content: &str,
+    ) -> Result<Theme> {
+        match format {
+            ThemeFormat::AlacrittyToml => self.parse_alacritty_theme(theme_name, content),
+            ThemeFormat::AlacrittyYaml => self.parse_alacritty_yaml(theme_name, content),
+            ThemeFormat::Kitty => self.parse_kitty_conf(theme_name, content),
+            ThemeFormat::Ghostty => self.parse_ghostty(theme_name, content),
+            ThemeFormat::Base16 => self.parse_base16_yaml(theme_name, content),
+            ThemeFormat::Btop => self.parse_btop_theme(theme_name, content),
+        }
+    }
+
+    /// Parse kitty.conf `foreground`, `background` and `color0`-`color15` lines
+    pub fn parse_kitty_conf(&self, theme_name: &str, content: &str) -> Result<Theme> {
+        let mut background = None;
+        let mut foreground = None;
+        let mut indexed: [Option<String>; 16] = Default::default();
+
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            match key {
+                "background" => background = parse_hex(value),
+                "foreground" => foreground = parse_hex(value),
+                _ => {
+                    if let Some(slot) = key
+                        .strip_prefix("color")
+                        .and_then(|index| index.parse::<usize>().ok())
+                        .and_then(|index| indexed.get_mut(index))
+                    {
+                        *slot = parse_hex(value);
+                    }
+                }
+            }
+        }
+
+        self.theme_from_indexed(theme_name, background, foreground, indexed)
+    }
+
+    /// Parse a Ghostty config's `background`, `foreground` and `palette = N=#hex` lines
+    pub fn parse_ghostty(&self, theme_name: &str, content: &str) -> Result<Theme> {
+        let mut background = None;
+        let mut foreground = None;
+        let mut indexed: [Option<String>; 16] = Default::default();
+
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key.trim() {
+                "background" => background = parse_hex(value),
+                "foreground" => foreground = parse_hex(value),
+                "palette" => {
+                    if let Some((index, color)) = value.split_once('=')
+                        && let Some(slot) = index
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| indexed.get_mut(index))
+                    {
+                        *slot = parse_hex(color);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.theme_from_indexed(theme_name, background, foreground, indexed)
+    }
+
+    /// Parse a base16 scheme, either flat `base00: "..."` keys or under `palette:`
+    pub fn parse_base16_yaml(&self, theme_name: &str, content: &str) -> Result<Theme> {
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str(content).map_err(|e| anyhow!("Failed to parse YAML: {}", e))?;
+        let slots = yaml.get("palette").unwrap_or(&yaml);
+        let base = |index: usize| {
+            slots
+                .get(format!("base{index:02X}"))
+                .and_then(|value| value.as_str())
+                .and_then(parse_hex)
+        };
+
+        // The same slots base16-shell assigns to the 16 ANSI colors
+        const ANSI_SLOTS: [usize; 16] = [
+            0x00, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x05, 0x03, 0x08, 0x0B, 0x0A, 0x0D, 0x0E,
+            0x0C, 0x07,
+        ];
+        let indexed = ANSI_SLOTS.map(base);
+        self.theme_from_indexed(theme_name, base(0x00), base(0x05), indexed)
+    }
+
+    /// Derive a palette from btop's `theme[key]="#hex"` lines.
+    ///
+    /// btop has no ANSI palette, so each hue slot takes the btop color closest to
+    /// it, and black and white come from the meter background and main text.
+    pub fn parse_btop_theme(&self, theme_name: &str, content: &str) -> Result<Theme> {
+        let mut values = HashMap::new();
+        for line in content.lines().map(str::trim) {
+            let Some((key, value)) = line
+                .strip_prefix("theme[")
+                .and_then(|rest| rest.split_once("]="))
+            else {
+                continue;
+            };
+            if let Some(color) = parse_btop_color(value) {
+                values.insert(key.to_string(), color);
+            }
+        }
+
+        let get = |keys: &[&str]| keys.iter().find_map(|key| values.get(*key).copied());
+        let (Some(background), Some(foreground)) = (get(&["main_bg"]), get(&["main_fg"])) else {
+            return Err(anyhow!(
+                "No colors found: btop theme without main_bg and main_fg"
+            ));
+        };
+
+        let accents: Vec<(Rgb, [f64; 3])> = values
+            .values()
+            .map(|&color| (color, color.to_hsl()))
+            .filter(|(_, [_, saturation, lightness])| {
+                *saturation > 0.25 && (0.2..0.85).contains(lightness)
+            })
+            .collect();
+        if accents.is_empty() {
+            return Err(anyhow!("No colors found: btop theme without accent colors"));
+        }
+        let nearest = |target: f64| {
+            accents
+                .iter()
+                .min_by(|(a, [hue_a, ..]), (b, [hue_b, ..])| {
+                    let distance = |hue: f64| {
+                        let d = (hue - target).rem_euclid(360.0);
+                        d.min(360.0 - d)
+                    };
+                    distance(*hue_a)
+                        .total_cmp(&distance(*hue_b))
+                        .then_with(|| a.to_hex().cmp(&b.to_hex()))
+                })
+                .map(|(color, _)| color.to_hex())
+                .unwrap_or_default()
+        };
+
+        let black = get(&["meter_bg", "div_line", "inactive_fg"]).unwrap_or(background);
+        let bright_black = get(&["inactive_fg", "div_line"]).unwrap_or(black);
+        let bright_white = get(&["title", "hi_fg"]).unwrap_or(foreground);
+        let hues = [0.0, 120.0, 60.0, 240.0, 300.0, 180.0].map(nearest);
+        let [red, green, yellow, blue, magenta, cyan] = hues;
+        let palette = |black: Rgb, white: Rgb| {
+            palette_from([
+                black.to_hex(),
+                red.clone(),
+                green.clone(),
+                yellow.clone(),
+                blue.clone(),
+                magenta.clone(),
+                cyan.clone(),
+                white.to_hex(),
+            ])
+        };
+
+        Ok(Theme {
+            name: self.format_theme_name(theme_name),
+            description: format!("Theme colors from {theme_name}"),
+            source_url: String::new(),
+            colors: ThemeColors {
+                background: background.to_hex(),
+                foreground: foreground.to_hex(),
+                normal: palette(black, foreground),
+                bright: palette(bright_black, bright_white),
+            },
+        })
+    }
+
+    /// Build a theme from terminal-style indexed colors.
+    ///
+    /// The eight normal colors are required; brights fall back to them and the
+    /// background and foreground to colors 0 and 7, as terminals do.
+    fn theme_from_indexed(
+        &self,
+        theme_name: &str,
+        background: Option<String>,
+        foreground: Option<String>,
+        indexed: [Option<String>; 16],
+    ) -> Result<Theme> {
+        if background.is_none() && foreground.is_none() && indexed.iter().all(Option::is_none) {
+            return Err(anyhow!("No colors found"));
+        }
+        let missing: Vec<String> = (0..8)
+            .filter(|&i| indexed[i].is_none())
+            .map(|i| format!("color{i}"))
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "Incomplete palette, missing {}",
+                missing.join(", ")
+            ));
+        }
+
+        let normal: [String; 8] = std::array::from_fn(|i| indexed[i].clone().unwrap_or_default());
+        let bright: [String; 8] =
+            std::array::from_fn(|i| indexed[i + 8].clone().unwrap_or_else(|| normal[i].clone()));
+
+        Ok(Theme {
+            name: self.format_theme_name(theme_name),
+            description: format!("Theme colors from {theme_name}"),
+            source_url: String::new(),
+            colors: ThemeColors {
+                background: background.unwrap_or_else(|| normal[0].clone()),
+                foreground: foreground.unwrap_or_else(|| normal[7].clone()),
+                normal: palette_from(normal),
+                bright: palette_from(bright),
+            },
+        })
+    }
+
     fn extract_colors_from_json(&self, json: &serde_json::Value) -> Result<ThemeColors> {
         // Try to find colors in common TOML structures
         let colors = if let Some(colors) = json.get("colors") {
@@ -518,6 +835,16 @@ impl ThemeParser {
             json
         };
 
+        // A config without any palette (fonts, keybindings...) has nothing to preview
+        let has_colors = self
+            .extract_color(colors, &["primary", "background"])
+            .is_some()
+            || self.extract_color(colors, &["background"]).is_some()
+            || colors.get("normal").is_some();
+        if !has_colors {
+            return Err(anyhow!("No colors found"));
+        }
+
         // Extract background and foreground
         let background = self
             .extract_color(colors, &["primary", "background"])
@@ -655,6 +982,87 @@ impl ThemeParser {
     }
 }
 
+/// Theme file formats colors can be read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeFormat {
+    AlacrittyToml,
+    AlacrittyYaml,
+    Kitty,
+    Ghostty,
+    Base16,
+    Btop,
+}
+
+impl ThemeFormat {
+    /// Guess the format from a file name as found in theme repositories
+    pub fn from_path(path: &str) -> Option<Self> {
+        let file = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+        let is_yaml = file.ends_with(".yaml") || file.ends_with(".yml");
+
+        if file.ends_with(".theme") {
+            Some(ThemeFormat::Btop)
+        } else if file.starts_with("kitty") && file.ends_with(".conf") {
+            Some(ThemeFormat::Kitty)
+        } else if file.starts_with("ghostty") {
+            Some(ThemeFormat::Ghostty)
+        } else if file.contains("base16") && is_yaml {
+            Some(ThemeFormat::Base16)
+        } else if file.ends_with(".toml") {
+            Some(ThemeFormat::AlacrittyToml)
+        } else if is_yaml {
+            Some(ThemeFormat::AlacrittyYaml)
+        } else {
+            None
+        }
+    }
+}
+
+/// `#rrggbb` from `#rrggbb`, `rrggbb` or the `#rgb` shorthand
+fn parse_hex(value: &str) -> Option<String> {
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    let digits = value.strip_prefix('#').unwrap_or(value);
+    let expanded: String = if digits.len() == 3 {
+        digits.chars().flat_map(|c| [c, c]).collect()
+    } else {
+        digits.to_string()
+    };
+    Rgb::from_hex(&expanded).map(Rgb::to_hex)
+}
+
+fn palette_from(colors: [String; 8]) -> ThemeColorPalette {
+    let [black, red, green, yellow, blue, magenta, cyan, white] = colors;
+    ThemeColorPalette {
+        black,
+        red,
+        green,
+        yellow,
+        blue,
+        magenta,
+        cyan,
+        white,
+    }
+}
+
+/// btop colors are `#rrggbb`, `#gg` grayscale or decimal `r g b`
+fn parse_btop_color(value: &str) -> Option<Rgb> {
+    let value = value.trim().trim_matches('"').trim();
+    if let Some(gray) = value.strip_prefix('#').filter(|digits| digits.len() == 2) {
+        let level = u8::from_str_radix(gray, 16).ok()?;
+        return Some(Rgb::new(level, level, level));
+    }
+    if let Some(color) = Rgb::from_hex(value) {
+        return Some(color);
+    }
+    let channels: Vec<u8> = value
+        .split_whitespace()
+        .map(|channel| channel.parse().ok())
+        .collect::<Option<_>>()?;
+    match channels[..] {
+        [r, g, b] => Some(Rgb::new(r, g, b)),
+        _ => None,
+    }
+}
+
 impl Default for ThemeParser {
     fn default() -> Self {
         Self::new()
@@ -665,6 +1073,127 @@ impl Default for ThemeParser {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_theme_format_from_path() {
+        assert_eq!(
+            ThemeFormat::from_path("btop.theme"),
+            Some(ThemeFormat::Btop)
+        );
+        assert_eq!(
+            ThemeFormat::from_path("kitty-theme.conf"),
+            Some(ThemeFormat::Kitty)
+        );
+        assert_eq!(
+            ThemeFormat::from_path("ghostty"),
+            Some(ThemeFormat::Ghostty)
+        );
+        assert_eq!(
+            ThemeFormat::from_path("base16.yml"),
+            Some(ThemeFormat::Base16)
+        );
+        assert_eq!(
+            ThemeFormat::from_path(".config/alacritty/alacritty.yml"),
+            Some(ThemeFormat::AlacrittyYaml)
+        );
+        assert_eq!(ThemeFormat::from_path("hyprland.conf"), None);
+    }
+
+    #[test]
+    fn test_parse_kitty_conf() {
+        let parser = ThemeParser::new();
+        let mut conf = String::from("# Nord\nbackground #2E3440\nforeground   #d8dee9\n");
+        for i in 0..8 {
+            conf.push_str(&format!("color{i} #10101{i}\n"));
+        }
+        conf.push_str("color9 #bf616a\ncursor #fff\n");
+
+        let theme = parser.parse_kitty_conf("nord", &conf).unwrap();
+        assert_eq!(theme.colors.background, "#2e3440");
+        assert_eq!(theme.colors.normal.white, "#101017");
+        assert_eq!(theme.colors.bright.red, "#bf616a");
+        // Brights the file leaves out fall back to the normal colors
+        assert_eq!(theme.colors.bright.green, "#101012");
+
+        let error = parser
+            .parse_kitty_conf("nord", "background #000000\ncolor0 #000000\n")
+            .unwrap_err();
+        assert!(error.to_string().contains("missing color1"));
+    }
+
+    #[test]
+    fn test_parse_ghostty_and_base16() {
+        let parser = ThemeParser::new();
+        let mut config = String::from("background = 1a1b26\nforeground = #c0caf5\n");
+        for i in 0..16 {
+            config.push_str(&format!("palette = {i}=#0000{i:02x}\n"));
+        }
+        let theme = parser.parse_ghostty("tokyo-night", &config).unwrap();
+        assert_eq!(theme.name, "Tokyo Night");
+        assert_eq!(theme.colors.background, "#1a1b26");
+        assert_eq!(theme.colors.bright.white, "#00000f");
+
+        let flat = "scheme: Gruvbox\nbase00: \"282828\"\nbase03: \"665c54\"\nbase05: \"d5c4a1\"\nbase07: \"fbf1c7\"\nbase08: \"fb4934\"\nbase0A: \"fabd2f\"\nbase0B: \"b8bb26\"\nbase0C: \"8ec07c\"\nbase0D: \"83a598\"\nbase0E: \"d3869b\"\n";
+        let nested = format!(
+            "system: base16\npalette:\n{}",
+            flat.lines()
+                .skip(1)
+                .map(|line| format!("  {}\n", line.replace(": \"", ": \"#")))
+                .collect::<String>()
+        );
+        for content in [flat.to_string(), nested] {
+            let theme = parser.parse_base16_yaml("gruvbox", &content).unwrap();
+            assert_eq!(theme.colors.background, "#282828");
+            assert_eq!(theme.colors.foreground, "#d5c4a1");
+            assert_eq!(theme.colors.normal.red, "#fb4934");
+            assert_eq!(theme.colors.bright.black, "#665c54");
+            assert_eq!(theme.colors.bright.white, "#fbf1c7");
+        }
+    }
+
+    #[test]
+    fn test_parse_btop_theme_maps_hues() {
+        let parser = ThemeParser::new();
+        let content = r##"
+# Main background, empty for terminal default
+theme[main_bg]="#1a1b26"
+theme[main_fg]="#cfc9c2"
+theme[title]="#ffffff"
+theme[inactive_fg]="#565f89"
+theme[meter_bg]="#32344a"
+theme[temp_end]="#f7768e"
+theme[cpu_start]="#9ece6a"
+theme[cpu_mid]="#e0af68"
+theme[mem_box]="#7aa2f7"
+theme[proc_box]="#bb9af7"
+theme[net_box]="#7dcfff"
+theme[div_line]="#24"
+"##;
+        let theme = parser.parse_btop_theme("tokyo-night", content).unwrap();
+        assert_eq!(theme.colors.background, "#1a1b26");
+        assert_eq!(theme.colors.normal.black, "#32344a");
+        assert_eq!(theme.colors.normal.red, "#f7768e");
+        assert_eq!(theme.colors.normal.green, "#9ece6a");
+        assert_eq!(theme.colors.normal.blue, "#7aa2f7");
+        assert_eq!(theme.colors.normal.magenta, "#bb9af7");
+        assert_eq!(theme.colors.normal.cyan, "#7dcfff");
+        assert_eq!(theme.colors.bright.white, "#ffffff");
+
+        assert!(
+            parser
+                .parse_btop_theme("x", "theme[main_bg]=\"#000000\"")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_alacritty_without_colors_is_rejected() {
+        let parser = ThemeParser::new();
+        let error = parser
+            .parse_alacritty_theme("fonts", "[font]\nsize = 11\n")
+            .unwrap_err();
+        assert!(error.to_string().contains("No colors found"));
+    }
+
     #[test]
     fn test_parse_empty_content() {
         let parser = ReadmeParser::new();
@@ -697,19 +1226,204 @@ mod tests {
         assert_eq!(result.sections[1].entries.len(), 1);
     }
 
+    #[test]
+    fn test_excluded_heading_ends_the_section_above_it() {
+        let parser = ReadmeParser::new();
+        let markdown = r#"
+# Awesome Omarchy
+
+## Development Tools
+
+- [CLI Tool](https://github.com/omarchy/cli) - Command line interface for Omarchy
+
+## Contributing
+
+- [Guidelines](https://github.com/omarchy/awesome/blob/main/CONTRIBUTING.md) - How to add a link
+"#;
+
+        let result = parser.parse(markdown).unwrap();
+        assert_eq!(result.sections.len(), 1);
+        assert_eq!(result.sections[0].title, "Development Tools");
+        assert_eq!(result.sections[0].entries.len(), 1);
+        assert_eq!(result.sections[0].entries[0].title, "CLI Tool");
+        assert!(!result.sections[0].raw_content.contains("Guidelines"));
+    }
+
     #[test]
     fn test_extract_repository_entry() {
         let parser = ReadmeParser::new();
         let text = "Awesome Tool - A comprehensive tool for doing awesome things";
         let url = "https://github.com/user/awesome-tool";
 
-        let entry = parser.extract_repository_entry(text, url);
+        let entry = parser.extract_repository_entry(text, vec![Link::new(url)]);
         assert_eq!(entry.title, "Awesome Tool");
         assert_eq!(
             entry.description,
             "A comprehensive tool for doing awesome things"
         );
         assert_eq!(entry.url, url);
+        assert_eq!(entry.tags, ["tool"]);
+    }
+
+    #[test]
+    fn test_sections_keep_their_hierarchy() {
+        let parser = ReadmeParser::new();
+        let markdown = r#"
+# Awesome Omarchy
+
+## Tools
+
+- [top](https://github.com/user/top) - A top level tool
+
+### Bars
+
+- [bar](https://github.com/user/bar) - A status bar
+
+#### Widgets
+
+- [clock](https://github.com/user/clock) - A clock widget
+- [battery](https://github.com/user/battery) - A battery widget
+
+### Launchers
+
+- [launch](https://github.com/user/launch) - A launcher
+
+## Themes
+
+- [dusk](https://github.com/user/dusk) - A theme
+"#;
+        let content = parser.parse(markdown).unwrap();
+        let shape: Vec<(&str, u32, Option<usize>)> = content
+            .sections
+            .iter()
+            .map(|s| (s.title.as_str(), s.level, s.parent))
+            .collect();
+        assert_eq!(
+            shape,
+            [
+                ("Tools", 2, None),
+                ("Bars", 3, Some(0)),
+                ("Widgets", 4, Some(1)),
+                ("Launchers", 3, Some(0)),
+                ("Themes", 2, None),
+            ]
+        );
+
+        assert_eq!(content.total_entry_count(0), 5);
+        assert_eq!(content.total_entry_count(1), 3);
+        assert_eq!(content.total_entry_count(4), 1);
+        assert!(content.has_children(1));
+        assert!(!content.has_children(3));
+        assert_eq!(content.depth(2), 2);
+        assert_eq!(content.breadcrumb(2), "Tools › Bars › Widgets");
+
+        // Search results point at the nested section holding the entry
+        let results = content.search_index.search("battery");
+        assert_eq!(results[0].section_index, 2);
+        assert_eq!(
+            content.sections[2].entries[results[0].entry_index.unwrap()].title,
+            "battery"
+        );
+    }
+
+    #[test]
+    fn test_entries_keep_every_kind_of_link() {
+        let parser = ReadmeParser::new();
+        let markdown = r#"
+# Awesome Omarchy
+
+## Resources
+
+- [hyprflow](https://gitlab.com/group/sub/hyprflow/-/tree/main) - A GitLab project
+- [dots](https://codeberg.org/someone/dots) - Dotfiles on Codeberg
+- [Omarchy](https://omarchy.org) - The official website
+- [Ricing guide](https://blog.example.com/posts/ricing) - A blog post
+- [Walkthrough](https://www.youtube.com/watch?v=abc123) - A video tour
+- [omarchy-bin](https://aur.archlinux.org/packages/omarchy-bin) - Packaged for Arch
+- [Issues](#issues) - An anchor, not an entry
+"#;
+        let content = parser.parse(markdown).unwrap();
+        let shape: Vec<(&str, LinkKind, String)> = content.sections[0]
+            .entries
+            .iter()
+            .map(|e| (e.title.as_str(), e.kind, links::display(&e.url)))
+            .collect();
+        assert_eq!(
+            shape,
+            [
+                (
+                    "hyprflow",
+                    LinkKind::GitLab,
+                    "group/sub/hyprflow".to_string()
+                ),
+                ("dots", LinkKind::Codeberg, "someone/dots".to_string()),
+                ("Omarchy", LinkKind::Website, "omarchy.org".to_string()),
+                (
+                    "Ricing guide",
+                    LinkKind::Article,
+                    "blog.example.com/posts/ricing".to_string()
+                ),
+                (
+                    "Walkthrough",
+                    LinkKind::Video,
+                    "youtube.com/watch?v=abc123".to_string()
+                ),
+                ("omarchy-bin", LinkKind::Aur, "aur/omarchy-bin".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entries_collect_multiple_and_nested_links() {
+        let parser = ReadmeParser::new();
+        let markdown = r#"
+# Awesome Omarchy
+
+## Tools
+
+- [tool](https://github.com/user/tool) - A tool, also on [AUR](https://aur.archlinux.org/packages/tool)
+  - [Docs](https://tool.dev/docs)
+  - [Demo](https://youtu.be/xyz)
+- Bars worth a look
+  - [waybar-a](https://github.com/a/waybar-a) - A bar
+  - [waybar-b](https://codeberg.org/b/waybar-b) - Another bar
+"#;
+        let content = parser.parse(markdown).unwrap();
+        let entries = &content.sections[0].entries;
+        assert_eq!(entries.len(), 3);
+
+        // The first link leads, the rest and the sub-bullets are kept alongside
+        let tool = entries.iter().find(|e| e.title == "tool").unwrap();
+        assert_eq!(tool.url, "https://github.com/user/tool");
+        assert_eq!(tool.kind, LinkKind::GitHub);
+        let kinds: Vec<(LinkKind, &str)> = tool
+            .links
+            .iter()
+            .map(|l| (l.kind, l.text.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (LinkKind::GitHub, "tool"),
+                (LinkKind::Aur, "AUR"),
+                (LinkKind::Website, "Docs"),
+                (LinkKind::Video, "Demo"),
+            ]
+        );
+
+        // Sub-bullets of a plain label are entries of their own
+        let bars: Vec<(&str, LinkKind)> = entries
+            .iter()
+            .filter(|e| e.title.starts_with("waybar"))
+            .map(|e| (e.title.as_str(), e.kind))
+            .collect();
+        assert_eq!(
+            bars,
+            [
+                ("waybar-a", LinkKind::GitHub),
+                ("waybar-b", LinkKind::Codeberg)
+            ]
+        );
     }
 
     #[test]
@@ -0,0 +1,261 @@
+//! Tags for README entries, from a taxonomy of tags and the aliases that earn them
+//!
+//! Aliases match whole words only, so `java` does not tag a JavaScript project
+//! and `api` does not tag one that mentions a capital.
+
+use crate::models::ReadmeContent;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Shipped taxonomy, used unless the user provides their own
+const BUILTIN: &str = include_str!("../data/tags.toml");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Taxonomy {
+    /// Each tag and the lowercase words and phrases that earn it
+    tags: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for Taxonomy {
+    fn default() -> Self {
+        Self::parse(BUILTIN).expect("built-in taxonomy is valid")
+    }
+}
+
+impl Taxonomy {
+    /// `~/.config/awesome-omarchy-tui/tags.toml` on Linux
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("awesome-omarchy-tui").join("tags.toml"))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut taxonomy: Self = toml::from_str(content)?;
+        for (tag, aliases) in &mut taxonomy.tags {
+            // A tag always matches its own name
+            aliases.push(tag.clone());
+            for alias in aliases.iter_mut() {
+                *alias = alias.trim().to_lowercase();
+            }
+            aliases.retain(|alias| !alias.is_empty());
+            aliases.sort();
+            aliases.dedup();
+        }
+        Ok(taxonomy)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Tags whose aliases appear as whole words in `text`, in taxonomy order
+    pub fn tags_for(&self, text: &str) -> Vec<String> {
+        let text = text.to_lowercase();
+        self.tags
+            .iter()
+            .filter(|(_, aliases)| aliases.iter().any(|alias| contains_word(&text, alias)))
+            .map(|(tag, _)| tag.clone())
+            .collect()
+    }
+
+    /// The tag a GitHub topic stands for; topics outside the taxonomy are kept as they are
+    pub fn canonical(&self, topic: &str) -> String {
+        let topic = topic.to_lowercase();
+        // Topics use dashes where descriptions use spaces
+        let spaced = topic.replace('-', " ");
+        self.tags
+            .iter()
+            .find(|(_, aliases)| aliases.iter().any(|a| *a == topic || *a == spaced))
+            .map_or(topic, |(tag, _)| tag.clone())
+    }
+}
+
+/// Whether `phrase` occurs in `text` with no letter or digit directly on either side
+fn contains_word(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Retag every entry from its description and, when known, its repository's topics.
+///
+/// `topics` is keyed by lowercase `owner/repo`.
+pub fn tag_entries(
+    content: &mut ReadmeContent,
+    taxonomy: &Taxonomy,
+    topics: &HashMap<String, Vec<String>>,
+) {
+    for entry in content
+        .sections
+        .iter_mut()
+        .flat_map(|s| s.entries.iter_mut())
+    {
+        let mut tags = taxonomy.tags_for(&entry.description);
+        let repo_topics = entry
+            .owner_repo()
+            .and_then(|repo| topics.get(&repo.to_lowercase()));
+        for topic in repo_topics.into_iter().flatten() {
+            let tag = taxonomy.canonical(topic);
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        entry.tags = tags;
+    }
+}
+
+/// Every tag in use with the number of entries carrying it, most used first
+pub fn tag_counts(content: &ReadmeContent) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in content.sections.iter().flat_map(|s| &s.entries) {
+        for tag in &entry.tags {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(tag, count)| (tag.to_string(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::App;
+    use crate::models::{ReadmeContent, RepositoryEntry, Section, SidebarView};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn test_aliases_match_whole_words_only() {
+        let taxonomy = Taxonomy::default();
+        assert_eq!(taxonomy.tags_for("A JavaScript port"), ["javascript"]);
+        assert_eq!(taxonomy.tags_for("Written in Java."), ["java"]);
+        assert!(taxonomy.tags_for("Capital letters, golfing").is_empty());
+        assert_eq!(
+            taxonomy.tags_for("CLI for the Hyprland API, in C++"),
+            ["api", "command-line", "cpp", "hyprland"]
+        );
+        assert_eq!(
+            taxonomy.tags_for("A command line tool"),
+            ["command-line", "tool"]
+        );
+    }
+
+    #[test]
+    fn test_aliases_are_deduplicated_after_normalizing() {
+        let taxonomy = Taxonomy::parse(
+            r#"
+            [tags]
+            waybar = ["Waybar", "status bar", " waybar", "Status Bar"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(taxonomy.tags["waybar"], ["status bar", "waybar"]);
+    }
+
+    #[test]
+    fn test_custom_taxonomy_and_topics() {
+        let taxonomy = Taxonomy::parse(
+            r#"
+            [tags]
+            status-bar = ["Waybar", "status bar"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(taxonomy.tags_for("A waybar config"), ["status-bar"]);
+        assert_eq!(taxonomy.tags_for("My status-bar"), ["status-bar"]);
+        assert_eq!(taxonomy.canonical("Status-Bar"), "status-bar");
+        assert_eq!(taxonomy.canonical("waybar"), "status-bar");
+        assert_eq!(taxonomy.canonical("omarchy"), "omarchy");
+        assert!(Taxonomy::parse("[tags]\nrust = \"rust\"").is_err());
+
+        let mut section = Section::new("Tools".to_string());
+        section.entries = vec![
+            RepositoryEntry::sample("", "https://github.com/a/bar", "Waybar modules"),
+            RepositoryEntry::sample("", "https://github.com/b/dots", "Dotfiles"),
+        ];
+        let mut content = ReadmeContent {
+            sections: vec![section],
+            ..Default::default()
+        };
+        let topics = HashMap::from([(
+            "b/dots".to_string(),
+            vec!["omarchy".to_string(), "waybar".to_string()],
+        )]);
+        tag_entries(&mut content, &taxonomy, &topics);
+
+        let tags: Vec<&Vec<String>> = content.sections[0]
+            .entries
+            .iter()
+            .map(|e| &e.tags)
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                &vec!["status-bar".to_string()],
+                &vec!["omarchy".to_string(), "status-bar".to_string()]
+            ]
+        );
+        assert_eq!(
+            tag_counts(&content),
+            [("status-bar".to_string(), 2), ("omarchy".to_string(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tag_browser_steps_across_sections() {
+        let described = |title: &str, descriptions: &[&str]| {
+            let entries: Vec<_> = descriptions
+                .iter()
+                .map(|d| RepositoryEntry::sample("", "https://example.com", d))
+                .collect();
+            Section {
+                entry_count: entries.len(),
+                entries,
+                ..Section::new(title.to_string())
+            }
+        };
+        let mut app = App::with_readme(ReadmeContent {
+            sections: vec![
+                described("Tools", &["A Rust CLI", "A JavaScript app"]),
+                described("Themes", &["Neovim colors", "Rust port"]),
+            ],
+            ..ReadmeContent::default()
+        });
+        assert_eq!(app.tag_browser.counts[0], ("rust".to_string(), 2));
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        app.handle_key_event(key(KeyCode::Char('#'))).await.unwrap();
+        assert_eq!(app.sidebar_view, SidebarView::Tags);
+        app.handle_key_event(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.tag_browser.active.as_deref(), Some("rust"));
+        assert_eq!(app.selected_entry().unwrap().description, "A Rust CLI");
+
+        app.handle_key_event(key(KeyCode::Char('n'))).await.unwrap();
+        assert_eq!(app.current_tab, 1);
+        assert_eq!(app.selected_entry().unwrap().description, "Rust port");
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .starts_with("#rust 2/2")
+        );
+
+        // Wraps around to the first section
+        app.handle_key_event(key(KeyCode::Char('n'))).await.unwrap();
+        assert_eq!(app.current_tab, 0);
+        app.handle_key_event(key(KeyCode::Char('N'))).await.unwrap();
+        assert_eq!(app.current_tab, 1);
+
+        app.handle_key_event(key(KeyCode::Esc)).await.unwrap();
+        assert!(app.tag_browser.active.is_none());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::checker::LinkStatus;
+use crate::models::{ReadmeContent, Theme, ThemeEntry};
+use crate::wallpaper::Wallpaper;
+use crate::workspace::GitAction;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::task::AbortHandle;
+
+/// Results reported back to the UI loop by background tasks
+#[derive(Debug)]
+pub enum AppMessage {
+    Readme(Result<ReadmeContent>),
+    ThemeList(Result<Vec<ThemeEntry>>),
+    ThemeColors {
+        name: String,
+        result: Result<Box<Theme>>,
+    },
+    /// Palettes of the themes next to the cursor, fetched ahead of a preview
+    ThemesPrefetched(Vec<(String, Box<Theme>)>),
+    /// Palettes of listed themes found in the disk cache
+    CachedThemes(Vec<(String, Box<Theme>)>),
+    /// A palette extracted from a local image for the theme builder
+    ImagePalette(Result<Box<Theme>>),
+    /// A theme's decoded wallpaper, or `None` when it ships no `backgrounds/`
+    Wallpaper {
+        name: String,
+        result: Result<Option<Box<Wallpaper>>>,
+    },
+    /// GitHub topics by lowercase `owner/repo`
+    Topics(Result<HashMap<String, Vec<String>>>),
+    /// Status of every entry URL
+    LinkCheck(HashMap<String, LinkStatus>),
+    /// A line printed by a running clone, pull or install
+    CommandOutput(String),
+    CommandFinished {
+        action: GitAction,
+        result: Result<()>,
+    },
+}
+
+impl AppMessage {
+    /// The task slot this message completes
+    pub fn kind(&self) -> TaskKind {
+        match self {
+            AppMessage::Readme(_) => TaskKind::Readme,
+            AppMessage::ThemeList(_) => TaskKind::ThemeList,
+            AppMessage::ThemeColors { .. } => TaskKind::ThemeColors,
+            AppMessage::ThemesPrefetched(_) => TaskKind::Prefetch,
+            AppMessage::CachedThemes(_) => TaskKind::CachedThemes,
+            AppMessage::ImagePalette(_) => TaskKind::ImagePalette,
+            AppMessage::Wallpaper { .. } => TaskKind::Wallpaper,
+            AppMessage::Topics(_) => TaskKind::Topics,
+            AppMessage::LinkCheck(_) => TaskKind::LinkCheck,
+            AppMessage::CommandOutput(_) | AppMessage::CommandFinished { .. } => TaskKind::Command,
+        }
+    }
+
+    /// Whether this is the last message its task sends
+    pub fn is_final(&self) -> bool {
+        !matches!(self, AppMessage::CommandOutput(_))
+    }
+}
+
+/// A message together with the spawn it came from
+#[derive(Debug)]
+pub struct TaskMessage {
+    pub id: u64,
+    pub message: AppMessage,
+}
+
+/// Reports progress of one spawned task, tagging everything it sends with its id
+#[derive(Debug, Clone)]
+pub struct TaskSender {
+    id: u64,
+    sender: UnboundedSender<TaskMessage>,
+}
+
+impl TaskSender {
+    pub fn new(id: u64, sender: UnboundedSender<TaskMessage>) -> Self {
+        Self { id, sender }
+    }
+
+    /// Send a message; fails once the UI loop is gone
+    pub fn send(&self, message: AppMessage) -> Result<()> {
+        self.sender
+            .send(TaskMessage {
+                id: self.id,
+                message,
+            })
+            .map_err(|_| anyhow::anyhow!("The app is shutting down"))
+    }
+}
+
+/// Kinds of background work; at most one task of each kind runs at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskKind {
+    Readme,
+    ThemeList,
+    ThemeColors,
+    Prefetch,
+    CachedThemes,
+    ImagePalette,
+    Wallpaper,
+    Topics,
+    LinkCheck,
+    Command,
+}
+
+/// Spawns network work off the UI loop and tracks it for cancellation
+///
+/// Every spawn gets a fresh id, so messages of a task that was replaced after it
+/// had already finished are told apart from those of its successor.
+pub struct TaskManager {
+    sender: UnboundedSender<TaskMessage>,
+    running: HashMap<TaskKind, (u64, AbortHandle)>,
+    next_id: u64,
+}
+
+impl TaskManager {
+    pub fn new(sender: UnboundedSender<TaskMessage>) -> Self {
+        Self {
+            sender,
+            running: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Create a manager together with the receiver the UI loop should poll
+    pub fn channel() -> (Self, UnboundedReceiver<TaskMessage>) {
+        let (sender, receiver) = unbounded_channel();
+        (Self::new(sender), receiver)
+    }
+
+    /// Spawn a task, replacing (and aborting) any running task of the same kind
+    pub fn spawn<F>(&mut self, kind: TaskKind, future: F)
+    where
+        F: Future<Output = AppMessage> + Send + 'static,
+    {
+        self.spawn_with(kind, |_| future);
+    }
+
+    /// Like `spawn`, but the task gets a sender to report progress before its final message
+    pub fn spawn_with<F, Fut>(&mut self, kind: TaskKind, task: F)
+    where
+        F: FnOnce(TaskSender) -> Fut,
+        Fut: Future<Output = AppMessage> + Send + 'static,
+    {
+        self.cancel(kind);
+
+        self.next_id += 1;
+        let sender = TaskSender::new(self.next_id, self.sender.clone());
+        let future = task(sender.clone());
+        let handle = tokio::spawn(async move {
+            // The receiver is gone only when the app is shutting down
+            let _ = sender.send(future.await);
+        });
+        self.running
+            .insert(kind, (self.next_id, handle.abort_handle()));
+    }
+
+    /// Abort a running task; returns whether anything was cancelled
+    pub fn cancel(&mut self, kind: TaskKind) -> bool {
+        match self.running.remove(&kind) {
+            Some((_, handle)) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether spawn `id` is the task running for `kind`, rather than one replaced or cancelled
+    pub fn is_current(&self, kind: TaskKind, id: u64) -> bool {
+        self.running
+            .get(&kind)
+            .is_some_and(|&(running, _)| running == id)
+    }
+
+    /// Mark a task as done once its final message arrived; messages of other spawns are ignored
+    pub fn finish(&mut self, kind: TaskKind, id: u64) -> bool {
+        let current = self.is_current(kind, id);
+        if current {
+            self.running.remove(&kind);
+        }
+        current
+    }
+
+    pub fn is_running(&self, kind: TaskKind) -> bool {
+        self.running.contains_key(&kind)
+    }
+
+    pub fn any_running(&self) -> bool {
+        !self.running.is_empty()
+    }
+}
+
+impl Drop for TaskManager {
+    fn drop(&mut self) {
+        for (_, handle) in self.running.values() {
+            handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_spawned_task_reports_back() {
+        let (mut tasks, mut receiver) = TaskManager::channel();
+        tasks.spawn(TaskKind::ThemeList, async {
+            AppMessage::ThemeList(Ok(Vec::new()))
+        });
+        assert!(tasks.is_running(TaskKind::ThemeList));
+
+        let TaskMessage { id, message } = receiver.recv().await.unwrap();
+        assert_eq!(message.kind(), TaskKind::ThemeList);
+        assert!(tasks.finish(message.kind(), id));
+        assert!(!tasks.any_running());
+    }
+
+    #[tokio::test]
+    async fn test_late_message_of_replaced_task_is_ignored() {
+        let (mut tasks, mut receiver) = TaskManager::channel();
+        tasks.spawn(TaskKind::Readme, async {
+            AppMessage::Readme(Ok(ReadmeContent::default()))
+        });
+        // The first task has already reported by the time it is replaced
+        let stale = receiver.recv().await.unwrap();
+        tasks.spawn(TaskKind::Readme, async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            AppMessage::Readme(Ok(ReadmeContent::default()))
+        });
+
+        assert!(!tasks.finish(stale.message.kind(), stale.id));
+        assert!(tasks.is_running(TaskKind::Readme));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_task_never_reports() {
+        let (mut tasks, mut receiver) = TaskManager::channel();
+        tasks.spawn(TaskKind::Readme, async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            AppMessage::Readme(Ok(ReadmeContent::default()))
+        });
+        assert!(tasks.cancel(TaskKind::Readme));
+        assert!(!tasks.cancel(TaskKind::Readme));
+
+        let message = tokio::time::timeout(Duration::from_millis(50), receiver.recv()).await;
+        assert!(message.is_err());
+    }
+}
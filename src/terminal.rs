@@ -0,0 +1,378 @@
+use crate::color::ColorDepth;
+use crate::wallpaper::Wallpaper;
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::text::Span;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Environment override for hyperlink detection ("0" disables, "1" forces)
+const HYPERLINKS_ENV: &str = "AWSOMARCHY_HYPERLINKS";
+/// Environment override to stop emitting OSC 52 ("0" disables)
+const OSC52_ENV: &str = "AWSOMARCHY_OSC52";
+/// Environment override for the image protocol: "kitty", "sixel" or "halfblocks"
+const GRAPHICS_ENV: &str = "AWSOMARCHY_GRAPHICS";
+
+/// Whether the terminal is known to render OSC 8 hyperlinks
+pub fn supports_hyperlinks() -> bool {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+
+    match var(HYPERLINKS_ENV).as_str() {
+        "0" | "false" => return false,
+        "1" | "true" => return true,
+        _ => {}
+    }
+
+    let term_program = var("TERM_PROGRAM");
+    let term = var("TERM");
+    let vte_version = var("VTE_VERSION").parse::<u32>().unwrap_or(0);
+
+    matches!(
+        term_program.as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+    ) || ["kitty", "foot", "alacritty", "ghostty", "wezterm"]
+        .iter()
+        .any(|name| term.contains(name))
+        || vte_version >= 5000
+        || !var("WT_SESSION").is_empty()
+        || !var("KONSOLE_VERSION").is_empty()
+}
+
+/// Guess the color depth from `COLORTERM` and `TERM`
+pub fn color_depth() -> ColorDepth {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    let colorterm = var("COLORTERM").to_lowercase();
+    let term = var("TERM");
+
+    if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+        ColorDepth::TrueColor
+    } else if term.contains("256") {
+        ColorDepth::Ansi256
+    } else if term.is_empty() || term == "linux" || term == "dumb" || term.starts_with("vt") {
+        // The Linux console and plain VTs only have the 16 ANSI colors
+        ColorDepth::Ansi16
+    } else {
+        ColorDepth::Ansi256
+    }
+}
+
+/// Ways of drawing images in the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// Colored `▀` cells, understood by every terminal
+    HalfBlocks,
+    Kitty,
+    Sixel,
+}
+
+/// Guess the graphics protocol from the environment
+pub fn graphics_protocol() -> GraphicsProtocol {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+
+    match var(GRAPHICS_ENV).to_lowercase().as_str() {
+        "kitty" => return GraphicsProtocol::Kitty,
+        "sixel" => return GraphicsProtocol::Sixel,
+        "halfblocks" | "0" => return GraphicsProtocol::HalfBlocks,
+        _ => {}
+    }
+
+    let term = var("TERM");
+    let term_program = var("TERM_PROGRAM");
+
+    // Multiplexers swallow or misplace image escapes
+    if !var("TMUX").is_empty() || term.starts_with("screen") {
+        GraphicsProtocol::HalfBlocks
+    } else if !var("KITTY_WINDOW_ID").is_empty()
+        || term.contains("kitty")
+        || term.contains("ghostty")
+        || matches!(term_program.as_str(), "ghostty" | "WezTerm")
+    {
+        GraphicsProtocol::Kitty
+    } else if ["foot", "mlterm", "contour"]
+        .iter()
+        .any(|name| term.contains(name))
+        || term_program == "iTerm.app"
+    {
+        GraphicsProtocol::Sixel
+    } else {
+        GraphicsProtocol::HalfBlocks
+    }
+}
+
+/// Pixel size of one cell, assuming 10×20 when the terminal does not report it
+pub fn cell_size() -> (u32, u32) {
+    match ratatui::crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            u32::from(size.width / size.columns),
+            u32::from(size.height / size.rows),
+        ),
+        _ => (10, 20),
+    }
+}
+
+/// Replace the previously drawn image with `wallpaper` scaled over `area`.
+///
+/// Kitty images live outside the cell grid and must be deleted explicitly; sixel
+/// pixels are overwritten once ratatui redraws the cells beneath them.
+pub fn graphics_overlay(
+    protocol: GraphicsProtocol,
+    area: Rect,
+    wallpaper: Option<&Wallpaper>,
+) -> Result<Vec<u8>> {
+    use ratatui::crossterm::{cursor::MoveTo, queue};
+
+    let mut out = Vec::new();
+    if protocol == GraphicsProtocol::Kitty {
+        out.extend_from_slice(b"\x1b_Ga=d,d=A,q=2\x1b\\");
+    }
+    let Some(wallpaper) = wallpaper.filter(|_| !area.is_empty()) else {
+        return Ok(out);
+    };
+
+    queue!(out, MoveTo(area.x, area.y))?;
+    match protocol {
+        GraphicsProtocol::Kitty => {
+            out.extend(wallpaper.kitty_sequence(area.width, area.height)?);
+        }
+        GraphicsProtocol::Sixel => {
+            let (width, height) = cell_size();
+            out.extend(wallpaper.sixel_sequence(
+                u32::from(area.width) * width,
+                u32::from(area.height) * height,
+            ));
+        }
+        GraphicsProtocol::HalfBlocks => {}
+    }
+    Ok(out)
+}
+
+/// Re-emit the already drawn cells of each link area wrapped in OSC 8 sequences.
+///
+/// The visible content is identical to what ratatui drew, so its diffing stays
+/// valid; only the hyperlink attribute is added on terminals that support it.
+pub fn hyperlink_overlay(buffer: &Buffer, links: &[(Rect, String)]) -> Vec<u8> {
+    use ratatui::crossterm::{
+        cursor::MoveTo,
+        queue,
+        style::{
+            Attribute, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+            SetUnderlineColor,
+        },
+    };
+    use ratatui::style::Modifier;
+
+    let mut out = Vec::new();
+    let area = buffer.area;
+
+    for (rect, url) in links {
+        let rect = rect.intersection(area);
+        for y in rect.top()..rect.bottom() {
+            let _ = queue!(
+                out,
+                MoveTo(rect.x, y),
+                Print(format!("\x1b]8;;{url}\x1b\\"))
+            );
+
+            let mut x = rect.left();
+            while x < rect.right() {
+                let cell = &buffer[(x, y)];
+                let _ = queue!(
+                    out,
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(cell.fg.into()),
+                    SetBackgroundColor(cell.bg.into()),
+                    SetUnderlineColor(cell.underline_color.into())
+                );
+                for (modifier, attribute) in [
+                    (Modifier::BOLD, Attribute::Bold),
+                    (Modifier::DIM, Attribute::Dim),
+                    (Modifier::ITALIC, Attribute::Italic),
+                    (Modifier::UNDERLINED, Attribute::Underlined),
+                ] {
+                    if cell.modifier.contains(modifier) {
+                        let _ = queue!(out, SetAttribute(attribute));
+                    }
+                }
+                let _ = queue!(out, Print(cell.symbol()));
+
+                // Wide graphemes cover the following cell, just like in ratatui's diff
+                x += (Span::raw(cell.symbol()).width() as u16).max(1);
+            }
+
+            let _ = queue!(out, Print("\x1b]8;;\x1b\\"), SetAttribute(Attribute::Reset));
+        }
+    }
+
+    out
+}
+
+/// Build the OSC 52 sequence that asks the terminal to set the clipboard
+pub fn osc52_sequence(text: &str, in_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if in_tmux {
+        // tmux only forwards escape sequences wrapped in its passthrough
+        format!("\x1bPtmux;\x1b{sequence}\x1b\\")
+    } else {
+        sequence
+    }
+}
+
+/// How a clipboard copy was delivered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipboardMethod {
+    Osc52,
+    WlCopy,
+    Xclip,
+}
+
+impl ClipboardMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClipboardMethod::Osc52 => "OSC 52",
+            ClipboardMethod::WlCopy => "wl-copy",
+            ClipboardMethod::Xclip => "xclip",
+        }
+    }
+}
+
+/// Copy text to the clipboard.
+///
+/// OSC 52 is always emitted so copying works over SSH. On a local session the
+/// text is also piped into wl-copy or xclip when available, since some
+/// terminals ignore OSC 52.
+pub fn copy_to_clipboard(text: &str) -> Result<Vec<ClipboardMethod>> {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    let mut methods = Vec::new();
+
+    if var(OSC52_ENV) != "0" {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(osc52_sequence(text, !var("TMUX").is_empty()).as_bytes())?;
+        stdout.flush()?;
+        methods.push(ClipboardMethod::Osc52);
+    }
+
+    let over_ssh = !var("SSH_TTY").is_empty() || !var("SSH_CONNECTION").is_empty();
+    if !over_ssh {
+        let local_tool = if !var("WAYLAND_DISPLAY").is_empty() {
+            Some((ClipboardMethod::WlCopy, "wl-copy", &[][..]))
+        } else if !var("DISPLAY").is_empty() {
+            Some((
+                ClipboardMethod::Xclip,
+                "xclip",
+                &["-selection", "clipboard"][..],
+            ))
+        } else {
+            None
+        };
+
+        if let Some((method, program, args)) = local_tool
+            && pipe_to(program, args, text).is_ok()
+        {
+            methods.push(method);
+        }
+    }
+
+    if methods.is_empty() {
+        return Err(anyhow!("No clipboard method available"));
+    }
+    Ok(methods)
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    // Closing stdin hands the text over; reap the helper off the event loop in case it lingers
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Style};
+
+    /// Drop CSI and OSC sequences, leaving the printed text
+    fn strip_escapes(output: &str) -> String {
+        let mut visible = String::new();
+        let mut chars = output.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                visible.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('[') => while chars.next().is_some_and(|c| !c.is_ascii_alphabetic()) {},
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        visible
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hello", false), "\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(
+            osc52_sequence("hello", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGVsbG8=\x07\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_yank_formats() {
+        use crate::links::LinkKind;
+        use crate::models::{RepositoryEntry, YankFormat};
+
+        let entry = RepositoryEntry {
+            title: "Omarchy".to_string(),
+            url: "https://github.com/basecamp/omarchy".to_string(),
+            description: String::new(),
+            tags: Vec::new(),
+            kind: LinkKind::GitHub,
+            links: Vec::new(),
+        };
+        let render = |key| YankFormat::from_key(key).unwrap().render(&entry);
+
+        assert_eq!(render('u'), "https://github.com/basecamp/omarchy");
+        assert_eq!(render('r'), "basecamp/omarchy");
+        assert_eq!(
+            render('m'),
+            "[Omarchy](https://github.com/basecamp/omarchy)"
+        );
+        assert_eq!(render('c'), "git clone https://github.com/basecamp/omarchy");
+        assert_eq!(YankFormat::from_key('x'), None);
+    }
+
+    #[test]
+    fn test_hyperlink_overlay_reprints_drawn_cells() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 20, 1));
+        buffer.set_string(2, 0, "user/repo", Style::default().fg(Color::Blue));
+
+        let links = vec![(
+            Rect::new(2, 0, 9, 1),
+            "https://github.com/user/repo".to_string(),
+        )];
+        let output = String::from_utf8(hyperlink_overlay(&buffer, &links)).unwrap();
+
+        assert!(output.contains("\x1b]8;;https://github.com/user/repo\x1b\\"));
+        assert!(output.ends_with("\x1b]8;;\x1b\\\x1b[0m"));
+        assert_eq!(strip_escapes(&output), "user/repo");
+    }
+}
@@ -0,0 +1,199 @@
+//! Parsed theme palettes kept on disk, keyed by repository and commit
+//!
+//! Each repository keeps a single file named after the commit its colors were
+//! read from, so a palette is reused until the theme's default branch moves.
+
+use crate::models::Theme;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Subdirectory of the cache directory holding the palettes
+pub const THEMES_DIR: &str = "themes";
+
+#[derive(Debug, Clone)]
+pub struct ThemeCache {
+    dir: PathBuf,
+}
+
+impl ThemeCache {
+    /// A cache under `cache_dir`, created on the first store
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            dir: cache_dir.join(THEMES_DIR),
+        }
+    }
+
+    /// The palette read from `commit` of `owner/repo`, if cached
+    pub fn load(&self, owner: &str, repo: &str, commit: &str) -> Option<Theme> {
+        let path = self
+            .dir
+            .join(format!("{}{commit}.json", repo_prefix(owner, repo)));
+        read_theme(&path)
+    }
+
+    /// Whatever palette is cached for `owner/repo`, regardless of commit
+    pub fn latest(&self, owner: &str, repo: &str) -> Option<Theme> {
+        self.repo_files(owner, repo)
+            .into_iter()
+            .find_map(|path| read_theme(&path))
+    }
+
+    /// Every cached palette by lowercase `(owner, repo)`, read in one pass
+    pub fn all(&self) -> HashMap<(String, String), Theme> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return HashMap::new();
+        };
+        entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let name = path.file_name()?.to_str()?;
+                let (owner, rest) = name.split_once("__")?;
+                let (repo, _) = rest.split_once('@')?;
+                Some(((owner.to_string(), repo.to_string()), read_theme(&path)?))
+            })
+            .collect()
+    }
+
+    /// Cache a palette, replacing the one from any older commit
+    pub fn store(&self, owner: &str, repo: &str, commit: &str, theme: &Theme) -> Result<()> {
+        self.remove(owner, repo)?;
+        fs::create_dir_all(&self.dir)?;
+        let path = self
+            .dir
+            .join(format!("{}{commit}.json", repo_prefix(owner, repo)));
+        fs::write(path, serde_json::to_string_pretty(theme)?)?;
+        Ok(())
+    }
+
+    /// Drop every cached palette of `owner/repo`
+    pub fn remove(&self, owner: &str, repo: &str) -> Result<()> {
+        for path in self.repo_files(owner, repo) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn repo_files(&self, owner: &str, repo: &str) -> Vec<PathBuf> {
+        let prefix = repo_prefix(owner, repo);
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix))
+            })
+            .collect()
+    }
+}
+
+/// GitHub names are case-insensitive; owners cannot contain `_` and repos cannot contain `@`
+fn repo_prefix(owner: &str, repo: &str) -> String {
+    format!("{}__{}@", owner.to_lowercase(), repo.to_lowercase())
+}
+
+fn read_theme(path: &Path) -> Option<Theme> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// The commit `HEAD` points at, from a smart HTTP ref advertisement
+/// (`info/refs?service=git-upload-pack`)
+pub fn head_commit(advertisement: &str) -> Option<&str> {
+    let end = advertisement.find(" HEAD\0")?;
+    let commit = advertisement.get(end.checked_sub(40)?..end)?;
+    commit
+        .chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then_some(commit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ThemeColorPalette, ThemeColors};
+
+    fn theme(background: &str) -> Theme {
+        let palette = ThemeColorPalette {
+            black: "#000000".to_string(),
+            red: "#ff0000".to_string(),
+            green: "#00ff00".to_string(),
+            yellow: "#ffff00".to_string(),
+            blue: "#0000ff".to_string(),
+            magenta: "#ff00ff".to_string(),
+            cyan: "#00ffff".to_string(),
+            white: "#ffffff".to_string(),
+        };
+        Theme {
+            name: "Test".to_string(),
+            description: String::new(),
+            source_url: "https://github.com/owner/test".to_string(),
+            colors: ThemeColors {
+                background: background.to_string(),
+                foreground: "#ffffff".to_string(),
+                normal: palette.clone(),
+                bright: palette,
+            },
+        }
+    }
+
+    #[test]
+    fn test_palettes_are_keyed_by_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ThemeCache::new(dir.path());
+        assert!(cache.latest("owner", "test").is_none());
+
+        cache
+            .store("owner", "test", "aaa", &theme("#111111"))
+            .unwrap();
+        let cached = cache.load("Owner", "Test", "aaa").unwrap();
+        assert_eq!(cached.colors.background, "#111111");
+        assert!(cache.load("owner", "test", "bbb").is_none());
+
+        // A newer commit replaces the old palette
+        cache
+            .store("owner", "test", "bbb", &theme("#222222"))
+            .unwrap();
+        assert!(cache.load("owner", "test", "aaa").is_none());
+        assert_eq!(
+            cache.latest("owner", "test").unwrap().colors.background,
+            "#222222"
+        );
+
+        // Other repositories sharing a prefix are left alone
+        cache
+            .store("owner", "test-2", "ccc", &theme("#333333"))
+            .unwrap();
+        cache.remove("owner", "test").unwrap();
+        assert!(cache.latest("owner", "test").is_none());
+        assert!(cache.latest("owner", "test-2").is_some());
+
+        cache
+            .store("Other", "Theme", "ddd", &theme("#444444"))
+            .unwrap();
+        let all = cache.all();
+        assert_eq!(all.len(), 2);
+        assert_eq!(
+            all[&("other".to_string(), "theme".to_string())]
+                .colors
+                .background,
+            "#444444"
+        );
+    }
+
+    #[test]
+    fn test_head_commit_from_ref_advertisement() {
+        let sha = "4f2c1e0b9a8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b";
+        let advertisement = format!(
+            "001e# service=git-upload-pack\n0000015b{sha} HEAD\0multi_ack symref=HEAD:refs/heads/main\n003f{sha} refs/heads/main\n0000"
+        );
+        assert_eq!(head_commit(&advertisement), Some(sha));
+        assert_eq!(head_commit("001e# service=git-upload-pack\n0000"), None);
+        assert_eq!(head_commit("0008nothex HEAD\0"), None);
+    }
+}
@@ -1,14 +1,24 @@
 use crate::{
     App,
-    models::{AppState, FocusArea},
+    builder::{Channel, ThemeBuilder},
+    checker::LinkStatus,
+    color::{AA_LARGE, AA_TEXT, ColorDepth, ContrastGrade, Rgb, diff_themes},
+    duplicates,
+    highlight::{CodeSample, Language, TokenKind, highlight},
+    links,
+    markdown::{Block as MarkdownBlock, Inline, plain_text},
+    models::{AppState, CommandPanelState, FocusArea, HitRegion, SidebarView, UiLayout},
+    tasks::TaskKind,
+    terminal::GraphicsProtocol,
 };
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, Wrap},
 };
+use std::collections::HashMap;
 
 /// Dynamic color scheme that adapts to applied themes
 pub struct ThemeColors {
@@ -48,15 +58,65 @@ impl ThemeColors {
     }
 }
 
+impl ThemeColors {
+    /// Map a theme's terminal palette onto the interface's semantic roles
+    pub fn from_theme(theme: &crate::models::Theme) -> Self {
+        let defaults = Self::new();
+        let colors = &theme.colors;
+        let color = |hex: &str, fallback: Color| parse_hex_color(hex).unwrap_or(fallback);
+
+        Self {
+            background: color(&colors.background, defaults.background),
+            foreground: color(&colors.foreground, defaults.foreground),
+            primary: color(&colors.normal.blue, defaults.primary),
+            secondary: color(&colors.normal.cyan, defaults.secondary),
+            accent: color(&colors.bright.blue, defaults.accent),
+            success: color(&colors.normal.green, defaults.success),
+            warning: color(&colors.normal.yellow, defaults.warning),
+            error: color(&colors.normal.red, defaults.error),
+            muted: color(&colors.bright.black, defaults.muted),
+            highlight: color(&colors.bright.green, defaults.highlight),
+
+            border_focused: color(&colors.normal.green, defaults.border_focused),
+            border_normal: color(&colors.bright.black, defaults.border_normal),
+        }
+    }
+}
+
 impl Default for ThemeColors {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Braille spinner frames shown while background work is in flight
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+fn spinner(app: &App) -> &'static str {
+    SPINNER_FRAMES[app.spinner_frame % SPINNER_FRAMES.len()]
+}
+
+/// Draw the app on a 100x30 test screen and read it back, one line per row
+#[cfg(test)]
+pub fn render_to_string(app: &mut App) -> String {
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|f| draw(f, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content()
+        .chunks(buffer.area.width as usize)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub fn draw(f: &mut Frame, app: &mut App) {
-    // Use default theme colors for main UI - themes only affect preview panels
-    let default_theme_colors = ThemeColors::default();
+    // An applied theme (previewed or saved) colors the whole interface
+    let themed = app.theme_applicator.current_theme.as_ref();
+    let theme_colors = themed.map(ThemeColors::from_theme).unwrap_or_default();
+
+    // Hit regions are rebuilt every frame as widgets are drawn
+    app.layout = UiLayout::default();
 
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -67,26 +127,98 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         ])
         .split(f.area());
 
-    draw_header(f, main_chunks[0], app, &default_theme_colors);
+    draw_header(f, main_chunks[0], app, &theme_colors);
 
     // Split the main content area horizontally for sidebar and content
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Percentage(20), // Left sidebar
-            Constraint::Percentage(80), // Right content area
+            Constraint::Percentage(app.sidebar_width), // Left sidebar (draggable)
+            Constraint::Percentage(100 - app.sidebar_width), // Right content area
         ])
         .split(main_chunks[1]);
+    app.layout.sidebar = content_chunks[0];
+    app.layout.content = content_chunks[1];
 
-    draw_sidebar(f, content_chunks[0], app, &default_theme_colors);
-    draw_main_content(f, content_chunks[1], app, &default_theme_colors);
-    draw_footer(f, main_chunks[2], app, &default_theme_colors);
+    draw_sidebar(f, content_chunks[0], app, &theme_colors);
+    draw_main_content(f, content_chunks[1], app, &theme_colors);
+    draw_footer(f, main_chunks[2], app, &theme_colors);
 
-    if app.theme_browser_mode {
-        draw_theme_browser_popup(f, app, &default_theme_colors);
+    if app.builder.is_some() {
+        draw_theme_builder_popup(f, app, &theme_colors);
+    } else if app.theme_browser_mode {
+        draw_theme_browser_popup(f, app, &theme_colors);
     } else if app.search_mode {
-        draw_search_popup(f, app, &default_theme_colors);
+        draw_search_popup(f, app, &theme_colors);
+    }
+
+    if app.command_panel.is_some() {
+        draw_command_panel(f, app, &theme_colors);
+        // Images sit above the cell grid and would cover the panel
+        app.layout.wallpaper = None;
+    }
+
+    if app.theme_applicator.current_theme.is_some() {
+        fill_base_colors(f.buffer_mut(), &theme_colors);
+    }
+
+    if app.color_depth != ColorDepth::TrueColor {
+        quantize_colors(f.buffer_mut(), app.color_depth);
+    }
+}
+
+/// Approximate every color the terminal cannot show with its nearest palette entry
+fn quantize_colors(buffer: &mut ratatui::buffer::Buffer, depth: ColorDepth) {
+    // A frame uses a handful of colors over thousands of cells
+    let mut nearest: HashMap<Color, Color> = HashMap::new();
+    let mut quantize = |color: Color| {
+        *nearest
+            .entry(color)
+            .or_insert_with(|| depth.quantize(color))
+    };
+    for cell in buffer.content.iter_mut() {
+        cell.fg = quantize(cell.fg);
+        cell.bg = quantize(cell.bg);
+        cell.underline_color = quantize(cell.underline_color);
+    }
+}
+
+/// Give cells left at the terminal's default colors the theme's background and foreground
+fn fill_base_colors(buffer: &mut ratatui::buffer::Buffer, theme: &ThemeColors) {
+    for cell in buffer.content.iter_mut() {
+        if cell.bg == Color::Reset {
+            cell.bg = theme.background;
+        }
+        if cell.fg == Color::Reset {
+            cell.fg = theme.foreground;
+        }
+    }
+}
+
+/// Map the rows of a bordered list back to item indices for mouse hit-testing
+fn list_hit_regions(area: Rect, offset: usize, heights: Vec<u16>) -> Vec<HitRegion> {
+    let inner = area.inner(Margin::new(1, 1));
+    let mut regions = Vec::new();
+    let mut y = inner.y;
+
+    for (index, height) in heights.into_iter().enumerate().skip(offset) {
+        if y >= inner.bottom() {
+            break;
+        }
+        let height = height.min(inner.bottom() - y);
+        regions.push(HitRegion {
+            area: Rect {
+                x: inner.x,
+                y,
+                width: inner.width,
+                height,
+            },
+            index,
+        });
+        y += height;
     }
+
+    regions
 }
 
 fn draw_header(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
@@ -135,19 +267,26 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
         Style::default().fg(theme.muted)
     };
 
-    let meta = Paragraph::new(Line::from(vec![
+    let mut meta_spans = vec![
         Span::styled("│ ", Style::default().fg(theme.accent)),
         Span::styled(title_text, meta_style),
-        Span::styled(" │", Style::default().fg(theme.accent)),
-    ]))
-    .alignment(Alignment::Center);
+    ];
+    if app.is_refreshing() && !app.state.is_loading() {
+        meta_spans.push(Span::styled(
+            format!(" {} Refreshing… (ESC to cancel)", spinner(app)),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    meta_spans.push(Span::styled(" │", Style::default().fg(theme.accent)));
+
+    let meta = Paragraph::new(Line::from(meta_spans)).alignment(Alignment::Center);
     f.render_widget(meta, chunks[1]);
 }
 
 fn draw_sidebar(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
     match &app.state {
         AppState::Loading => {
-            let loading = Paragraph::new("🔄 Loading...")
+            let loading = Paragraph::new(format!("{} Loading...", spinner(app)))
                 .style(Style::default().fg(theme.warning))
                 .alignment(Alignment::Center)
                 .block(
@@ -158,27 +297,41 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
                 );
             f.render_widget(loading, area);
         }
+        AppState::Ready if app.sidebar_view == SidebarView::Tags => {
+            draw_tag_list(f, area, app, theme);
+        }
+        AppState::Ready if app.sidebar_view == SidebarView::Duplicates => {
+            draw_duplicate_list(f, area, app, theme);
+        }
         AppState::Ready => {
             if !app.tabs.is_empty() {
-                // Create sidebar items with section names and entry counts
-                let items: Vec<ListItem> = app
-                    .tabs
+                // Sections as a tree: indented by depth, parents folded with ▸ and counting
+                // every entry beneath them
+                let visible = app.visible_tabs();
+                let items: Vec<ListItem> = visible
                     .iter()
-                    .enumerate()
-                    .map(|(i, tab)| {
+                    .map(|&i| {
+                        let tab = &app.tabs[i];
                         let is_selected = i == app.current_tab;
-                        let entry_count = if let Some(ref readme) = app.readme_content {
-                            readme
-                                .sections
-                                .get(tab.section_index)
-                                .map(|section| section.entries.len())
-                                .unwrap_or(0)
-                        } else {
-                            0
+                        let (depth, has_children, entry_count) = match &app.readme_content {
+                            Some(readme) => (
+                                readme.depth(tab.section_index),
+                                readme.has_children(tab.section_index),
+                                readme.total_entry_count(tab.section_index),
+                            ),
+                            None => (0, false, 0),
+                        };
+                        let fold = match (has_children, app.collapsed.contains(&tab.section_index))
+                        {
+                            (false, _) => "  ",
+                            (true, false) => "▾ ",
+                            (true, true) => "▸ ",
                         };
+                        let indent = Span::raw(format!("{}{fold}", "  ".repeat(depth)));
 
                         if is_selected {
                             ListItem::new(Line::from(vec![
+                                indent,
                                 Span::styled(
                                     "◆ ",
                                     Style::default()
@@ -203,6 +356,7 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
                             ]))
                         } else {
                             ListItem::new(Line::from(vec![
+                                indent,
                                 Span::styled("◇ ", Style::default().fg(theme.muted)),
                                 Span::styled(
                                     &tab.title,
@@ -249,7 +403,11 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
                     .highlight_symbol("")
                     .direction(ratatui::widgets::ListDirection::TopToBottom);
 
-                f.render_widget(sidebar_list, area);
+                // Keep the selected section scrolled into view in long trees
+                let mut state = ratatui::widgets::ListState::default()
+                    .with_selected(visible.iter().position(|&i| i == app.current_tab));
+                f.render_stateful_widget(sidebar_list, area, &mut state);
+                app.layout.tabs = list_hit_regions(area, state.offset(), vec![1; visible.len()]);
             } else {
                 let empty = Paragraph::new("No sections available")
                     .style(Style::default().fg(theme.muted))
@@ -279,10 +437,174 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
     }
 }
 
+/// Sidebar listing every tag with the number of entries carrying it
+fn draw_tag_list(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
+    let active = app.tag_browser.active.as_deref();
+    let rows = app
+        .tag_browser
+        .counts
+        .iter()
+        .enumerate()
+        .map(|(i, (tag, count))| {
+            let is_selected = i == app.tag_browser.selected;
+            let (mut marker, name_style) = sidebar_row_style(is_selected, theme);
+            if active == Some(tag.as_str()) {
+                marker = Span::styled("● ", Style::default().fg(theme.success));
+            }
+            let count_color = if is_selected {
+                theme.accent
+            } else {
+                theme.muted
+            };
+            Line::from(vec![
+                marker,
+                Span::styled(format!("#{tag}"), name_style),
+                Span::styled(format!(" [{count}]"), Style::default().fg(count_color)),
+            ])
+        })
+        .collect();
+
+    let list = SidebarList {
+        icon: "🏷 ",
+        title: "Tags",
+        hint: " (# sections)",
+        empty: "No tagged entries",
+        rows,
+        selected: app.tag_browser.selected,
+    };
+    draw_sidebar_list(f, area, app, theme, list);
+}
+
+fn draw_duplicate_list(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
+    let rows = app
+        .duplicates
+        .groups
+        .iter()
+        .enumerate()
+        .map(|(i, group)| {
+            let title = app
+                .readme_content
+                .as_ref()
+                .and_then(|readme| {
+                    let &(section, entry) = group.members.first()?;
+                    readme.sections.get(section)?.entries.get(entry)
+                })
+                .map(|entry| entry.title.clone())
+                .unwrap_or_default();
+            let (marker, name_style) = sidebar_row_style(i == app.duplicates.selected, theme);
+            Line::from(vec![
+                marker,
+                Span::styled(title, name_style),
+                Span::styled(
+                    format!(" ×{}", group.members.len()),
+                    Style::default().fg(theme.warning),
+                ),
+                Span::styled(
+                    format!(" · {}", group.reason.label()),
+                    Style::default().fg(theme.muted),
+                ),
+            ])
+        })
+        .collect();
+
+    let list = SidebarList {
+        icon: "⧉ ",
+        title: "Duplicates",
+        hint: " (D sections)",
+        empty: "No duplicate entries",
+        rows,
+        selected: app.duplicates.selected,
+    };
+    draw_sidebar_list(f, area, app, theme, list);
+}
+
+/// A list of one-line rows shown in the sidebar in place of the sections
+struct SidebarList {
+    icon: &'static str,
+    title: &'static str,
+    /// Muted note after the title, e.g. the key going back to the sections
+    hint: &'static str,
+    /// Shown when there are no rows
+    empty: &'static str,
+    rows: Vec<Line<'static>>,
+    selected: usize,
+}
+
+/// Marker and name style of a sidebar row
+fn sidebar_row_style(is_selected: bool, theme: &ThemeColors) -> (Span<'static>, Style) {
+    if is_selected {
+        (
+            Span::styled(
+                "◆ ",
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Style::default()
+                .fg(theme.foreground)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        (
+            Span::styled("◇ ", Style::default().fg(theme.muted)),
+            Style::default()
+                .fg(theme.foreground)
+                .add_modifier(Modifier::DIM),
+        )
+    }
+}
+
+/// Draw a sidebar list, recording its rows as click targets
+fn draw_sidebar_list(
+    f: &mut Frame,
+    area: Rect,
+    app: &mut App,
+    theme: &ThemeColors,
+    list: SidebarList,
+) {
+    let border_style = if app.focus_area == FocusArea::Sidebar {
+        Style::default().fg(theme.border_focused)
+    } else {
+        Style::default().fg(theme.border_normal)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Line::from(vec![
+            Span::styled(list.icon, Style::default().fg(theme.primary)),
+            Span::styled(
+                list.title,
+                Style::default()
+                    .fg(theme.foreground)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(list.hint, Style::default().fg(theme.muted)),
+        ]))
+        .border_style(border_style);
+
+    if list.rows.is_empty() {
+        let empty = Paragraph::new(list.empty)
+            .style(Style::default().fg(theme.muted))
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(empty, area);
+        app.layout.tabs.clear();
+        return;
+    }
+
+    let count = list.rows.len();
+    let widget = List::new(list.rows)
+        .block(block)
+        .highlight_style(Style::default())
+        .highlight_symbol("");
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(list.selected));
+    f.render_stateful_widget(widget, area, &mut state);
+    app.layout.tabs = list_hit_regions(area, state.offset(), vec![1; count]);
+}
+
 fn draw_main_content(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
     match &app.state {
         AppState::Loading => {
-            draw_loading(f, area, theme);
+            draw_loading(f, area, spinner(app), theme);
         }
         AppState::Ready => {
             draw_repository_content(f, area, app, theme);
@@ -297,25 +619,40 @@ fn draw_repository_content(f: &mut Frame, area: Rect, app: &mut App, theme: &The
     use ratatui::widgets::ListState;
 
     if let Some(current_tab) = app.current_tab() {
-        let section_title = current_tab.title.clone();
         let section_index = current_tab.section_index;
+        let section_title = match &app.readme_content {
+            Some(readme) => readme.breadcrumb(section_index),
+            None => current_tab.title.clone(),
+        };
 
         // Get selected index from list state
         let selected_index = current_tab.list_state.selected_index;
+        let active_tag = app.tag_browser.active.as_deref();
 
         // Get section data
         let section_data = if let Some(ref readme) = app.readme_content {
-            readme
-                .sections
-                .get(section_index)
-                .map(|section| (&section.entries, section.raw_content.clone()))
+            readme.sections.get(section_index).map(|section| {
+                (
+                    &section.entries,
+                    section.raw_content.clone(),
+                    &section.blocks,
+                )
+            })
         } else {
             None
         };
 
-        if let Some((entries, raw_content)) = section_data {
+        if let Some((entries, raw_content, blocks)) = section_data {
             let has_entries = !entries.is_empty();
             let entry_count = entries.len();
+            // Row of the URL line within each rendered item, for clickable links
+            let link_rows: Vec<u16> = entries
+                .iter()
+                .map(|entry| {
+                    1 + u16::from(!entry.description.is_empty()) + u16::from(!entry.tags.is_empty())
+                })
+                .collect();
+            let urls: Vec<String> = entries.iter().map(|entry| entry.url.clone()).collect();
 
             if has_entries {
                 // Create List items from repository entries
@@ -356,6 +693,52 @@ fn draw_repository_content(f: &mut Frame, area: Rect, app: &mut App, theme: &The
                             ])
                         };
 
+                        let mut title_line = title_line;
+                        if app.new_entries.contains(&duplicates::url_key(&entry.url)) {
+                            title_line.spans.push(Span::raw(" "));
+                            title_line.spans.push(Span::styled(
+                                " NEW ",
+                                Style::default()
+                                    .fg(theme.success)
+                                    .add_modifier(Modifier::REVERSED | Modifier::BOLD),
+                            ));
+                        }
+                        let link_status = app
+                            .link_status
+                            .get(&entry.url)
+                            .filter(|status| !status.is_ok());
+                        if let Some(status) = link_status {
+                            let color = match status {
+                                LinkStatus::NotFound | LinkStatus::Failed { .. } => theme.error,
+                                _ => theme.warning,
+                            };
+                            title_line.spans.push(Span::raw(" "));
+                            title_line.spans.push(Span::styled(
+                                format!(" {} ", status.badge()),
+                                Style::default()
+                                    .fg(color)
+                                    .add_modifier(Modifier::REVERSED | Modifier::BOLD),
+                            ));
+                        }
+                        // Mark entries listed elsewhere too
+                        if let Some(group) = app.duplicates.group_of(section_index, idx) {
+                            let others: Vec<String> = group
+                                .other_sections(section_index)
+                                .into_iter()
+                                .filter_map(|other| {
+                                    let readme = app.readme_content.as_ref()?;
+                                    Some(readme.sections.get(other)?.title.clone())
+                                })
+                                .collect();
+                            let note = if others.is_empty() {
+                                " ⧉ listed more than once here".to_string()
+                            } else {
+                                format!(" ⧉ also in {}", others.join(", "))
+                            };
+                            title_line
+                                .spans
+                                .push(Span::styled(note, Style::default().fg(theme.warning)));
+                        }
                         let mut lines = vec![title_line];
 
                         // Add enhanced description with markdown-style formatting
@@ -395,13 +778,17 @@ fn draw_repository_content(f: &mut Frame, area: Rect, app: &mut App, theme: &The
                                 .enumerate()
                                 .flat_map(|(i, tag)| {
                                     let badge_color = get_tag_color(tag, theme);
+                                    // The tag being browsed stands out on every entry carrying it
+                                    let modifier = if active_tag == Some(tag.as_str()) {
+                                        Modifier::BOLD | Modifier::REVERSED
+                                    } else {
+                                        Modifier::BOLD
+                                    };
                                     let mut spans = vec![
                                         Span::styled("[", Style::default().fg(badge_color)),
                                         Span::styled(
                                             tag,
-                                            Style::default()
-                                                .fg(badge_color)
-                                                .add_modifier(Modifier::BOLD),
+                                            Style::default().fg(badge_color).add_modifier(modifier),
                                         ),
                                         Span::styled("]", Style::default().fg(badge_color)),
                                     ];
@@ -428,46 +815,46 @@ fn draw_repository_content(f: &mut Frame, area: Rect, app: &mut App, theme: &The
                         }
 
                         // Add enhanced URL with better formatting
-                        let formatted_url = format_github_url(&entry.url);
-                        let url_line = if is_selected {
-                            Line::from(vec![
-                                Span::styled("    ", Style::default()),
-                                Span::styled("🔗 ", Style::default().fg(theme.primary)),
-                                Span::styled(
-                                    formatted_url,
-                                    Style::default()
-                                        .fg(theme.primary)
-                                        .add_modifier(Modifier::UNDERLINED),
-                                ),
-                            ])
+                        let formatted_url = links::display(&entry.url);
+                        let url_style = if is_selected {
+                            Style::default()
+                                .fg(theme.primary)
+                                .add_modifier(Modifier::UNDERLINED)
                         } else {
-                            Line::from(vec![
-                                Span::styled("      ", Style::default()),
-                                Span::styled("🔗 ", Style::default().fg(theme.muted)),
-                                Span::styled(
-                                    formatted_url,
-                                    Style::default()
-                                        .fg(theme.primary)
-                                        .add_modifier(Modifier::DIM),
-                                ),
-                            ])
+                            Style::default()
+                                .fg(theme.primary)
+                                .add_modifier(Modifier::DIM)
                         };
-                        lines.push(url_line);
+                        let mut url_spans = url_prefix(is_selected, theme).to_vec();
+                        url_spans.push(Span::styled(formatted_url, url_style));
+                        lines.push(Line::from(url_spans));
 
                         // Add enhanced separator and metadata for selected items
                         if is_selected {
-                            // Add repository stats if available (placeholder for future enhancement)
+                            let mut kind = format!(" {} ", entry.kind.label());
+                            if entry.links.len() > 1 {
+                                kind.push_str(&format!("· {} links ", entry.links.len()));
+                            }
                             lines.push(Line::from(vec![
                                 Span::styled("    ", Style::default()),
                                 Span::styled("└─", Style::default().fg(theme.accent)),
                                 Span::styled(
-                                    " GitHub Repository ",
+                                    kind,
                                     Style::default()
                                         .fg(theme.accent)
                                         .add_modifier(Modifier::DIM),
                                 ),
                                 Span::styled("⭐", Style::default().fg(theme.warning)),
                             ]));
+                            if let Some(to) = link_status.and_then(LinkStatus::new_location) {
+                                lines.push(Line::from(vec![
+                                    Span::styled("      ↪ ", Style::default().fg(theme.warning)),
+                                    Span::styled(
+                                        to.to_string(),
+                                        Style::default().fg(theme.warning),
+                                    ),
+                                ]));
+                            }
                         }
 
                         // Add enhanced spacing between entries
@@ -480,6 +867,7 @@ fn draw_repository_content(f: &mut Frame, area: Rect, app: &mut App, theme: &The
                 // Create ratatui ListState
                 let mut ratatui_state = ListState::default();
                 ratatui_state.select(selected_index);
+                let heights: Vec<u16> = items.iter().map(|item| item.height() as u16).collect();
 
                 // Enhanced border style based on focus with better visual feedback
                 let border_style = if app.focus_area == FocusArea::Content {
@@ -513,9 +901,82 @@ fn draw_repository_content(f: &mut Frame, area: Rect, app: &mut App, theme: &The
                     .direction(ratatui::widgets::ListDirection::TopToBottom);
 
                 f.render_stateful_widget(list, area, &mut ratatui_state);
+
+                let entry_regions = list_hit_regions(area, ratatui_state.offset(), heights);
+                app.layout.links = entry_regions
+                    .iter()
+                    .filter_map(|region| {
+                        let row = region.area.y + link_rows[region.index];
+                        (row < region.area.bottom()).then_some(HitRegion {
+                            area: Rect {
+                                y: row,
+                                height: 1,
+                                ..region.area
+                            },
+                            index: region.index,
+                        })
+                    })
+                    .collect();
+                // Narrow each link row down to the URL text, after the "🔗 " prefix
+                app.layout.hyperlinks = app
+                    .layout
+                    .links
+                    .iter()
+                    .map(|region| {
+                        let url = &urls[region.index];
+                        let prefix = url_prefix(selected_index == Some(region.index), theme)
+                            .iter()
+                            .map(Span::width)
+                            .sum::<usize>() as u16;
+                        let width = Span::raw(links::display(url)).width() as u16;
+                        let area = Rect {
+                            x: region.area.x + prefix,
+                            width: width.min(region.area.width.saturating_sub(prefix)),
+                            ..region.area
+                        };
+                        (area, url.clone())
+                    })
+                    .collect();
+                app.layout.entries = entry_regions;
             } else {
-                // No entries - show raw content or empty section
-                if !raw_content.trim().is_empty()
+                // No entries - show the section as a document, raw content or empty section
+                let document = markdown_lines(blocks, theme);
+                if !document.is_empty() {
+                    let border_style = if app.focus_area == FocusArea::Content {
+                        Style::default().fg(theme.success)
+                    } else {
+                        Style::default().fg(theme.primary)
+                    };
+
+                    // Keep the last page in view, counting rows the lines wrap onto
+                    let paragraph = Paragraph::new(document)
+                        .style(Style::default().fg(theme.foreground))
+                        .wrap(Wrap { trim: false });
+                    let rows = paragraph.line_count(area.width.saturating_sub(2));
+                    let max_scroll = rows.saturating_sub(area.height.saturating_sub(2) as usize);
+                    let scroll = match app.tabs.get_mut(app.current_tab) {
+                        Some(tab) => {
+                            tab.scroll_offset = tab.scroll_offset.min(max_scroll);
+                            tab.scroll_offset
+                        }
+                        None => 0,
+                    };
+                    let position = (scroll * 100)
+                        .checked_div(max_scroll)
+                        .map(|percent| format!(" {percent}% "))
+                        .unwrap_or_default();
+
+                    let paragraph = paragraph
+                        .block(
+                            Block::default()
+                                .borders(Borders::ALL)
+                                .title(format!("📄 {section_title}"))
+                                .title_bottom(Line::from(position).right_aligned())
+                                .border_style(border_style),
+                        )
+                        .scroll((scroll.min(u16::MAX as usize) as u16, 0));
+                    f.render_widget(paragraph, area);
+                } else if !raw_content.trim().is_empty()
                     && !raw_content.starts_with("•")
                     && !raw_content.contains("URL:")
                 {
@@ -590,7 +1051,22 @@ fn draw_repository_content(f: &mut Frame, area: Rect, app: &mut App, theme: &The
     }
 }
 
-fn draw_loading(f: &mut Frame, area: Rect, theme: &ThemeColors) {
+/// Indent and icon in front of an entry's URL, measured to place its hyperlink
+fn url_prefix(is_selected: bool, theme: &ThemeColors) -> [Span<'static>; 2] {
+    if is_selected {
+        [
+            Span::styled("    ", Style::default()),
+            Span::styled("🔗 ", Style::default().fg(theme.primary)),
+        ]
+    } else {
+        [
+            Span::styled("      ", Style::default()),
+            Span::styled("🔗 ", Style::default().fg(theme.muted)),
+        ]
+    }
+}
+
+fn draw_loading(f: &mut Frame, area: Rect, spinner: &str, theme: &ThemeColors) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -609,26 +1085,32 @@ fn draw_loading(f: &mut Frame, area: Rect, theme: &ThemeColors) {
         ])
         .split(chunks[1]);
 
-    let loading = Paragraph::new(Line::from(vec![
-        Span::styled(
-            "⏳ ",
-            Style::default()
-                .fg(theme.warning)
-                .add_modifier(Modifier::SLOW_BLINK),
-        ),
-        Span::styled(
-            "Loading README content...\n\n",
-            Style::default()
-                .fg(theme.warning)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::styled(
+    let loading = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{spinner} "),
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Loading README content...",
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(Span::styled(
             "✨ Fetching awesome resources from GitHub ✨",
             Style::default()
                 .fg(theme.secondary)
                 .add_modifier(Modifier::DIM),
-        ),
-    ]))
+        )),
+        Line::from(Span::styled(
+            "ESC to cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ])
     .alignment(Alignment::Center)
     .block(
         Block::default()
@@ -710,7 +1192,30 @@ fn draw_error(f: &mut Frame, area: Rect, error: &str, theme: &ThemeColors) {
 }
 
 fn draw_footer(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
-    let help_text = if app.search_mode {
+    let key_style = Style::default()
+        .fg(theme.secondary)
+        .add_modifier(Modifier::BOLD);
+
+    let help_text = if app.yank_pending {
+        Line::from(vec![
+            Span::styled("Yank: ", Style::default().fg(theme.accent)),
+            Span::styled("u", key_style),
+            Span::styled(": URL │ ", Style::default().fg(theme.muted)),
+            Span::styled("r", key_style),
+            Span::styled(": owner/repo │ ", Style::default().fg(theme.muted)),
+            Span::styled("m", key_style),
+            Span::styled(": Markdown link │ ", Style::default().fg(theme.muted)),
+            Span::styled("c", key_style),
+            Span::styled(": git clone │ ", Style::default().fg(theme.muted)),
+            Span::styled("ESC", key_style),
+            Span::styled(": Cancel", Style::default().fg(theme.muted)),
+        ])
+    } else if let Some(message) = &app.status_message {
+        Line::from(Span::styled(
+            message.as_str(),
+            Style::default().fg(theme.success),
+        ))
+    } else if app.search_mode {
         Line::from(vec![
             Span::styled(
                 "ESC",
@@ -770,6 +1275,10 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(": Open URL │ ", Style::default().fg(theme.muted)),
+            Span::styled("y", key_style),
+            Span::styled(": Yank │ ", Style::default().fg(theme.muted)),
+            Span::styled("c", key_style),
+            Span::styled(": Clone │ ", Style::default().fg(theme.muted)),
             Span::styled(
                 "R",
                 Style::default()
@@ -777,6 +1286,14 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(": Reload │ ", Style::default().fg(theme.muted)),
+            Span::styled("#", key_style),
+            Span::styled(
+                match &app.tag_browser.active {
+                    Some(tag) => format!(": Tags (n/N #{tag}) │ "),
+                    None => ": Tags │ ".to_string(),
+                },
+                Style::default().fg(theme.muted),
+            ),
             Span::styled("T", theme_key_style),
             Span::styled(
                 if app.is_theme_applied() {
@@ -800,8 +1317,9 @@ fn draw_footer(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
     f.render_widget(footer, area);
 }
 
-fn draw_search_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
+fn draw_search_popup(f: &mut Frame, app: &mut App, theme: &ThemeColors) {
     let popup_area = centered_rect(80, 60, f.area());
+    app.layout.popup = Some(popup_area);
 
     f.render_widget(Clear, popup_area);
 
@@ -857,6 +1375,11 @@ fn draw_search_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
                         }
                     })
                     .unwrap_or(&result.line_content);
+                let location = app
+                    .readme_content
+                    .as_ref()
+                    .map(|readme| readme.breadcrumb(result.section_index))
+                    .unwrap_or_default();
 
                 ListItem::new(Line::from(vec![
                     Span::styled(format!("{}. ", i + 1), Style::default().fg(theme.secondary)),
@@ -864,6 +1387,7 @@ fn draw_search_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
                         display_text.chars().take(60).collect::<String>(),
                         Style::default().fg(theme.foreground),
                     ),
+                    Span::styled(format!("  {location}"), Style::default().fg(theme.muted)),
                 ]))
             })
             .collect();
@@ -880,12 +1404,118 @@ fn draw_search_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
             )
             .highlight_style(Style::default().fg(theme.warning))
             .highlight_symbol("▶ ");
+        let visible_results = app.search_results.len().min(10);
         f.render_stateful_widget(results_list, chunks[1], &mut search_state);
+        app.layout.popup_items =
+            list_hit_regions(chunks[1], search_state.offset(), vec![1; visible_results]);
+    }
+}
+
+/// Log of a clone, pull or install, plus the install script while it awaits confirmation
+fn draw_command_panel(f: &mut Frame, app: &mut App, theme: &ThemeColors) {
+    let spinner = spinner(app);
+    let Some(panel) = app.command_panel.as_ref() else {
+        return;
+    };
+
+    let popup_area = centered_rect(80, 70, f.area());
+    f.render_widget(Clear, popup_area);
+
+    let script = match &panel.state {
+        CommandPanelState::ConfirmInstall(script) => Some(script),
+        _ => None,
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(if script.is_some() {
+            [Constraint::Percentage(35), Constraint::Min(0)]
+        } else {
+            [Constraint::Min(0), Constraint::Length(0)]
+        })
+        .split(popup_area);
+
+    let (status, status_color) = match &panel.state {
+        CommandPanelState::Running(action) => {
+            (format!("{spinner} {}", action.label()), theme.warning)
+        }
+        CommandPanelState::ConfirmPull => ("y: git pull │ n: Close".to_string(), theme.secondary),
+        CommandPanelState::ConfirmInstall(_) => {
+            ("y: Run install.sh │ n: Skip".to_string(), theme.error)
+        }
+        CommandPanelState::Finished => ("Enter: Close".to_string(), theme.muted),
+    };
+
+    // Follow the end of the log unless the user scrolled up
+    let visible_rows = chunks[0].height.saturating_sub(2) as usize;
+    let end = panel.log.len().saturating_sub(panel.scroll);
+    let start = end.saturating_sub(visible_rows);
+    let lines: Vec<Line> = panel.log[start..end]
+        .iter()
+        .map(|line| {
+            let color = if line.starts_with('✗') {
+                theme.error
+            } else if line.starts_with('✓') {
+                theme.success
+            } else if line.starts_with('$') {
+                theme.accent
+            } else {
+                theme.foreground
+            };
+            Line::from(Span::styled(line.as_str(), Style::default().fg(color)))
+        })
+        .collect();
+
+    let log = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(vec![
+                Span::styled("⬇ ", Style::default().fg(theme.accent)),
+                Span::styled(
+                    panel.name.as_str(),
+                    Style::default()
+                        .fg(theme.foreground)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(" → {} ", panel.checkout.display()),
+                    Style::default().fg(theme.muted),
+                ),
+            ]))
+            .title_bottom(Line::from(Span::styled(
+                format!(" {status} "),
+                Style::default().fg(status_color),
+            )))
+            .border_style(Style::default().fg(theme.border_focused)),
+    );
+    f.render_widget(log, chunks[0]);
+
+    if let Some(script) = script {
+        let script_view = Paragraph::new(script.as_str())
+            .style(Style::default().fg(theme.foreground))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Span::styled(
+                        " install.sh — review before running ",
+                        Style::default()
+                            .fg(theme.warning)
+                            .add_modifier(Modifier::BOLD),
+                    ))
+                    .title_bottom(Line::from(Span::styled(
+                        format!(" {status} "),
+                        Style::default().fg(status_color),
+                    )))
+                    .border_style(Style::default().fg(theme.warning)),
+            );
+        f.render_widget(script_view, chunks[1]);
     }
+
+    app.layout.popup = Some(popup_area);
 }
 
-fn draw_theme_browser_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
+fn draw_theme_browser_popup(f: &mut Frame, app: &mut App, theme: &ThemeColors) {
     let popup_area = centered_rect(85, 70, f.area());
+    app.layout.popup = Some(popup_area);
 
     f.render_widget(Clear, popup_area);
 
@@ -912,14 +1542,22 @@ fn draw_theme_browser_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
     let title_text = "🎨 Hybrid Multi-Panel Theme Preview";
 
     let preview_info = match app.preview_state {
-        crate::models::PreviewState::Loading => " - Loading theme preview...",
-        crate::models::PreviewState::Applied(_) => " - Multi-panel preview active! ESC to restore",
-        crate::models::PreviewState::Error => " - Error loading theme",
+        crate::models::PreviewState::Loading(_) => " - Loading theme preview... ESC to cancel",
+        _ if app.theme_browser.pinned_theme.is_some() => {
+            " - Comparing: Enter to preview, Tab diff table, v flip split, p unpin"
+        }
+        crate::models::PreviewState::Applied(_) if app.theme_browser.palette_editor.is_some() => {
+            " - Editing the palette: j/k swatch, ESC when done, then s to keep"
+        }
+        crate::models::PreviewState::Applied(_) => {
+            " - Previewing across the interface! s to keep, e to edit, p to pin, ESC to restore"
+        }
+        crate::models::PreviewState::Error(_) => " - No colors to preview",
         _ => {
             if app.theme_browser.search_mode {
                 " - Type to filter themes, j/k navigate, Enter to apply, ESC to clear"
             } else {
-                " - Navigate with j/k, Enter for preview, / to search, ESC to close"
+                " - Navigate with j/k, Enter for preview, / to search, d for terminal colors"
             }
         }
     };
@@ -933,6 +1571,21 @@ fn draw_theme_browser_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(preview_info, Style::default().fg(theme.muted)),
+        Span::styled(
+            if app.color_depth == ColorDepth::TrueColor {
+                String::new()
+            } else {
+                format!(" │ ≈ {} (approximated)", app.color_depth.label())
+            },
+            Style::default().fg(theme.warning),
+        ),
+        Span::styled(
+            app.theme_browser
+                .simulation
+                .map(|deficiency| format!(" │ 👁 Simulating {}", deficiency.label()))
+                .unwrap_or_default(),
+            Style::default().fg(theme.warning),
+        ),
     ]))
     .block(
         Block::default()
@@ -992,7 +1645,7 @@ fn draw_theme_browser_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
 
     // Theme content
     if app.theme_browser.loading {
-        let loading = Paragraph::new("🔄 Loading themes...")
+        let loading = Paragraph::new(format!("{} Loading themes...", spinner(app)))
             .style(Style::default().fg(theme.warning))
             .alignment(Alignment::Center)
             .block(
@@ -1020,15 +1673,112 @@ fn draw_theme_browser_popup(f: &mut Frame, app: &App, theme: &ThemeColors) {
     }
 }
 
-fn draw_aur_theme_content(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
-    if app.theme_entries.is_empty() {
-        let empty_text = Paragraph::new("No themes found in README")
-            .style(Style::default().fg(theme.muted))
-            .alignment(Alignment::Center)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Empty")
+/// The theme builder: editable slots on the left, the usual four-panel preview on the right
+fn draw_theme_builder_popup(f: &mut Frame, app: &mut App, theme: &ThemeColors) {
+    let popup_area = centered_rect(85, 70, f.area());
+    app.layout.popup = Some(popup_area);
+    let Some(builder) = app.builder.as_ref() else {
+        return;
+    };
+
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Min(0),    // Slots and preview
+        ])
+        .split(popup_area);
+
+    let info = if app.tasks.is_running(TaskKind::ImagePalette) {
+        format!(" - {} Reading image colors...", spinner(app))
+    } else {
+        " - j/k slot, i from image, n rename, w write Omarchy theme, ESC back".to_string()
+    };
+    let title = Paragraph::new(Line::from(vec![
+        Span::styled("🛠 ", Style::default().fg(theme.secondary)),
+        Span::styled(
+            format!("Theme Builder: {}", builder.theme.name),
+            Style::default()
+                .fg(theme.secondary)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(info, Style::default().fg(theme.muted)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.secondary)),
+    );
+    f.render_widget(title, chunks[0]);
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(32), Constraint::Min(0)])
+        .split(chunks[1]);
+
+    let items: Vec<ListItem> = builder
+        .theme
+        .colors
+        .slots()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, hex))| {
+            let is_selected = index == builder.selected;
+            let swatch = parse_hex_color(hex).unwrap_or(theme.background);
+            let name_style = if is_selected {
+                Style::default()
+                    .fg(theme.foreground)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+            ListItem::new(Line::from(vec![
+                if is_selected {
+                    Span::styled("▶ ", Style::default().fg(theme.success))
+                } else {
+                    Span::raw("  ")
+                },
+                Span::styled("██ ", Style::default().fg(swatch)),
+                Span::styled(format!("{name:<15}"), name_style),
+                Span::styled(hex.to_string(), Style::default().fg(theme.muted)),
+            ]))
+        })
+        .collect();
+    let slots = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("🎨 Palette")
+                .border_style(Style::default().fg(theme.border_focused)),
+        )
+        .highlight_style(Style::default())
+        .highlight_symbol("");
+    // Selecting keeps the edited slot scrolled into view on short terminals
+    let mut slot_state =
+        ratatui::widgets::ListState::default().with_selected(Some(builder.selected));
+    f.render_stateful_widget(slots, body[0], &mut slot_state);
+
+    draw_hybrid_multi_panel_preview(
+        f,
+        body[1],
+        &builder.theme,
+        theme,
+        app.current_sample(),
+        Some(builder),
+    );
+}
+
+fn draw_aur_theme_content(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
+    if app.theme_entries.is_empty() {
+        let empty_text = Paragraph::new("No themes found in README")
+            .style(Style::default().fg(theme.muted))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Empty")
                     .border_style(Style::default().fg(theme.muted)),
             );
         f.render_widget(empty_text, area);
@@ -1048,15 +1798,263 @@ fn draw_aur_theme_content(f: &mut Frame, area: Rect, app: &App, theme: &ThemeCol
     draw_theme_selector(f, main_chunks[0], app, theme);
 
     // Draw multi-panel preview on the right
-    if let Some(current_theme) = get_current_preview_theme(app) {
-        draw_hybrid_multi_panel_preview(f, main_chunks[1], current_theme, theme);
+    let mut placement = None;
+    if app.theme_browser.pinned_theme.is_some() {
+        draw_theme_comparison(f, main_chunks[1], app, theme);
+    } else if let crate::models::PreviewState::Loading(ref name) = app.preview_state {
+        draw_preview_loading(f, main_chunks[1], name, spinner(app), theme);
+    } else if let crate::models::PreviewState::Error(ref message) = app.preview_state {
+        draw_preview_error(f, main_chunks[1], message, theme);
+    } else if let Some(current_theme) = get_current_preview_theme(app) {
+        let shown = simulated(app, current_theme);
+        let mut preview_area = main_chunks[1];
+        if has_wallpaper_panel(app, &current_theme.name) {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+                .split(preview_area);
+            preview_area = chunks[0];
+            placement = draw_wallpaper_panel(f, chunks[1], app, &current_theme.name, theme);
+        }
+        draw_hybrid_multi_panel_preview(
+            f,
+            preview_area,
+            &shown,
+            theme,
+            app.current_sample(),
+            app.theme_browser.palette_editor.as_ref(),
+        );
     } else {
         // Show instructions when no theme is selected
         draw_preview_instructions(f, main_chunks[1], theme);
     }
+    app.layout.wallpaper = placement;
+}
+
+/// Whether the preview has a wallpaper to show, or one on its way
+fn has_wallpaper_panel(app: &App, name: &str) -> bool {
+    match app.wallpapers.get(name) {
+        Some(wallpaper) => wallpaper.is_some(),
+        None => app.tasks.is_running(TaskKind::Wallpaper),
+    }
+}
+
+/// Draw the wallpaper with half blocks, or return where a graphics protocol should draw it
+fn draw_wallpaper_panel(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    name: &str,
+    theme: &ThemeColors,
+) -> Option<(Rect, String)> {
+    let Some(Some(wallpaper)) = app.wallpapers.get(name) else {
+        let loading = Paragraph::new(format!("{} Loading wallpaper...", spinner(app)))
+            .style(Style::default().fg(theme.muted))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("🖼 Wallpaper")
+                    .border_style(Style::default().fg(theme.muted)),
+            );
+        f.render_widget(loading, area);
+        return None;
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("🖼 Wallpaper · {}", wallpaper.name))
+        .border_style(Style::default().fg(theme.primary));
+    let image_area = wallpaper.fit(block.inner(area));
+    f.render_widget(block, area);
+
+    if app.graphics == GraphicsProtocol::HalfBlocks {
+        let lines = wallpaper.half_blocks(image_area.width, image_area.height);
+        f.render_widget(Paragraph::new(lines), image_area);
+        None
+    } else {
+        Some((image_area, name.to_string()))
+    }
+}
+
+/// Pinned theme next to the current preview, or their slot-by-slot diff
+fn draw_theme_comparison(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+    let Some(pinned) = app.theme_browser.pinned_theme.as_deref() else {
+        return;
+    };
+    let current = get_current_preview_theme(app);
+
+    if app.theme_browser.compare_diff {
+        match current {
+            Some(current) => draw_theme_diff_table(f, area, pinned, current, theme),
+            None => draw_compare_placeholder(f, area, app, theme),
+        }
+        return;
+    }
+
+    let halves = Layout::default()
+        .direction(app.theme_browser.compare_split)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let slots = [(Some(pinned), "📌 "), (current, "👁 ")];
+    for ((slot_theme, marker), half) in slots.into_iter().zip(halves.iter()) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(*half);
+
+        match slot_theme {
+            Some(slot_theme) => {
+                let label = Paragraph::new(Line::from(vec![
+                    Span::styled(marker, Style::default().fg(theme.accent)),
+                    Span::styled(
+                        slot_theme.name.as_str(),
+                        Style::default()
+                            .fg(theme.foreground)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ]));
+                f.render_widget(label, chunks[0]);
+                draw_hybrid_multi_panel_preview(
+                    f,
+                    chunks[1],
+                    &simulated(app, slot_theme),
+                    theme,
+                    app.current_sample(),
+                    None,
+                );
+            }
+            None => draw_compare_placeholder(f, *half, app, theme),
+        }
+    }
+}
+
+/// The theme as it appears with the selected color-vision deficiency
+fn simulated<'a>(
+    app: &App,
+    preview: &'a crate::models::Theme,
+) -> std::borrow::Cow<'a, crate::models::Theme> {
+    match app.theme_browser.simulation {
+        Some(deficiency) => std::borrow::Cow::Owned(deficiency.simulate_theme(preview)),
+        None => std::borrow::Cow::Borrowed(preview),
+    }
+}
+
+/// WCAG grade of a theme in the selector, once its colors are known
+fn contrast_badge(app: &App, name: &str, theme: &ThemeColors) -> Option<Span<'static>> {
+    let report = app.theme_contrast(name)?;
+    let grade = report.grade();
+    let color = match grade {
+        ContrastGrade::Aaa => theme.success,
+        ContrastGrade::Aa => theme.secondary,
+        ContrastGrade::Fail => theme.error,
+    };
+    Some(Span::styled(
+        format!(" [{}]", grade.label()),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// Shown in place of the second theme until one is previewed
+fn draw_compare_placeholder(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+    if let crate::models::PreviewState::Loading(ref name) = app.preview_state {
+        draw_preview_loading(f, area, name, spinner(app), theme);
+        return;
+    }
+
+    let hint = Paragraph::new("Press Enter on another theme to compare it with the pinned one")
+        .style(Style::default().fg(theme.muted))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Compare ")
+                .border_style(Style::default().fg(theme.border_normal)),
+        );
+    f.render_widget(hint, area);
+}
+
+fn draw_theme_diff_table(
+    f: &mut Frame,
+    area: Rect,
+    pinned: &crate::models::Theme,
+    current: &crate::models::Theme,
+    theme: &ThemeColors,
+) {
+    let swatch = |hex: &str| -> Vec<Span<'static>> {
+        let color = parse_hex_color(hex).unwrap_or(theme.muted);
+        vec![
+            Span::styled("██ ", Style::default().fg(color)),
+            Span::styled(hex.to_string(), Style::default().fg(theme.foreground)),
+        ]
+    };
+
+    let rows: Vec<Row> = diff_themes(pinned, current)
+        .into_iter()
+        .map(|diff| {
+            let channels = diff
+                .channels
+                .map(|[r, g, b]| format!("{r:+4} {g:+4} {b:+4}"))
+                .unwrap_or_else(|| "—".to_string());
+            let (delta, delta_color) = match diff.delta_e {
+                Some(delta) if delta < 0.5 => ("same".to_string(), theme.muted),
+                Some(delta) if delta < 10.0 => (format!("{delta:.1}"), theme.success),
+                Some(delta) if delta < 30.0 => (format!("{delta:.1}"), theme.warning),
+                Some(delta) => (format!("{delta:.1}"), theme.error),
+                None => ("?".to_string(), theme.muted),
+            };
+
+            Row::new(vec![
+                Cell::from(diff.slot).style(Style::default().fg(theme.secondary)),
+                Cell::from(Line::from(swatch(&diff.left))),
+                Cell::from(Line::from(swatch(&diff.right))),
+                Cell::from(channels).style(Style::default().fg(theme.muted)),
+                Cell::from(delta).style(Style::default().fg(delta_color)),
+            ])
+        })
+        .collect();
+
+    let header = Row::new(vec![
+        "Slot".to_string(),
+        format!("📌 {}", pinned.name),
+        format!("👁 {}", current.name),
+        "  ΔR   ΔG   ΔB".to_string(),
+        "ΔE".to_string(),
+    ])
+    .style(
+        Style::default()
+            .fg(theme.foreground)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(15),
+            Constraint::Min(12),
+            Constraint::Min(12),
+            Constraint::Length(15),
+            Constraint::Length(6),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Span::styled(
+                " Color diff (Tab for previews) ",
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .border_style(Style::default().fg(theme.border_focused)),
+    );
+    f.render_widget(table, area);
 }
 
-fn draw_theme_selector(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors) {
+fn draw_theme_selector(f: &mut Frame, area: Rect, app: &mut App, theme: &ThemeColors) {
     let (items, selected_index) = if app.theme_browser.search_mode
         && !app.theme_browser.filtered_themes.is_empty()
     {
@@ -1098,6 +2096,7 @@ fn draw_theme_selector(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors
                         if is_applied {
                             spans.push(Span::styled(" ✨", Style::default().fg(theme.success)));
                         }
+                        spans.extend(contrast_badge(app, &theme_entry.name, theme));
 
                         ListItem::new(Line::from(spans))
                     })
@@ -1120,11 +2119,11 @@ fn draw_theme_selector(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors
         ]))];
         (no_matches, None)
     } else {
-        // Show all themes
-        let all_items: Vec<ListItem> = app
-                .theme_entries
+        // Show all themes, in contrast order when sorting
+        let visible = app.visible_theme_indices();
+        let all_items: Vec<ListItem> = visible
                 .iter()
-                .enumerate()
+                .map(|&i| (i, &app.theme_entries[i]))
                 .map(|(i, theme_entry)| {
                     let is_selected = app.theme_browser.selected_index == Some(i);
                     let is_applied = matches!(app.preview_state,
@@ -1157,14 +2156,20 @@ fn draw_theme_selector(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors
                     if is_applied {
                         spans.push(Span::styled(" ✨", Style::default().fg(theme.success)));
                     }
+                    spans.extend(contrast_badge(app, &theme_entry.name, theme));
 
                     ListItem::new(Line::from(spans))
                 })
                 .collect();
 
-        (all_items, app.theme_browser.selected_index)
+        let selected_row = app
+            .theme_browser
+            .selected_index
+            .and_then(|selected| visible.iter().position(|&i| i == selected));
+        (all_items, selected_row)
     };
 
+    let item_count = items.len();
     let mut list_state = ratatui::widgets::ListState::default();
     list_state.select(selected_index);
 
@@ -1175,21 +2180,41 @@ fn draw_theme_selector(f: &mut Frame, area: Rect, app: &App, theme: &ThemeColors
                 app.theme_browser.filtered_themes.len(),
                 app.theme_entries.len()
             )
+        } else if app.theme_browser.min_contrast.is_some() {
+            format!(
+                " ({}/{})",
+                app.visible_theme_indices().len(),
+                app.theme_entries.len()
+            )
         } else {
             format!(" ({})", app.theme_entries.len())
         };
+    let mut list_flags = String::new();
+    if let Some(ratio) = app.theme_browser.min_contrast {
+        list_flags.push_str(&format!(" ≥{ratio}:1"));
+    }
+    if app.theme_browser.sort_by_contrast {
+        list_flags.push_str(" ↓contrast");
+    }
 
     let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("🎨 Themes{theme_count}"))
+                .title(format!("🎨 Themes{theme_count}{list_flags}"))
                 .border_style(Style::default().fg(theme.primary)),
         )
         .highlight_style(Style::default())
         .highlight_symbol("");
 
+    // The "no matches" placeholder row is not selectable
+    let selectable_rows = if selected_index.is_some() {
+        item_count
+    } else {
+        0
+    };
     f.render_stateful_widget(list, area, &mut list_state);
+    app.layout.popup_items = list_hit_regions(area, list_state.offset(), vec![1; selectable_rows]);
 }
 
 fn draw_preview_instructions(f: &mut Frame, area: Rect, theme: &ThemeColors) {
@@ -1231,6 +2256,54 @@ fn draw_preview_instructions(f: &mut Frame, area: Rect, theme: &ThemeColors) {
                 Style::default().fg(theme.muted),
             ),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  r",
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Refetch the theme, skipping the cache",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  a",
+                Style::default()
+                    .fg(theme.secondary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Toggle live preview while moving",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  n",
+                Style::default()
+                    .fg(theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Build a new theme from this one or an image",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  e",
+                Style::default()
+                    .fg(theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Edit the previewed palette with sliders",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  ESC",
@@ -1240,6 +2313,66 @@ fn draw_preview_instructions(f: &mut Frame, area: Rect, theme: &ThemeColors) {
             ),
             Span::styled(" - Close and restore", Style::default().fg(theme.muted)),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  s",
+                Style::default()
+                    .fg(theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Keep as the app's color scheme",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  d",
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Back to terminal colors",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  x",
+                Style::default()
+                    .fg(theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Export for Alacritty, kitty, foot, Ghostty, WezTerm, Xresources, base16",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  b/f/o",
+                Style::default()
+                    .fg(theme.secondary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Simulate color blindness / filter / sort by contrast",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  l",
+                Style::default()
+                    .fg(theme.secondary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " - Cycle the code sample's language",
+                Style::default().fg(theme.muted),
+            ),
+        ]),
         Line::from(""),
         Line::from(vec![
             Span::styled(
@@ -1295,6 +2428,63 @@ fn draw_preview_instructions(f: &mut Frame, area: Rect, theme: &ThemeColors) {
     f.render_widget(instructions, area);
 }
 
+fn draw_preview_loading(f: &mut Frame, area: Rect, name: &str, spinner: &str, theme: &ThemeColors) {
+    let loading = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(format!("{spinner} "), Style::default().fg(theme.warning)),
+            Span::styled("Fetching colors for ", Style::default().fg(theme.muted)),
+            Span::styled(
+                name,
+                Style::default()
+                    .fg(theme.foreground)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Keep browsing with j/k • ESC to cancel",
+            Style::default().fg(theme.muted),
+        )),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("⏳ Loading Preview")
+            .border_style(Style::default().fg(theme.warning)),
+    );
+
+    f.render_widget(loading, area);
+}
+
+fn draw_preview_error(f: &mut Frame, area: Rect, message: &str, theme: &ThemeColors) {
+    let error = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            message,
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Keep browsing with j/k",
+            Style::default().fg(theme.muted),
+        )),
+    ])
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true })
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("🚫 No Colors Found")
+            .border_style(Style::default().fg(theme.error)),
+    );
+
+    f.render_widget(error, area);
+}
+
 fn get_current_preview_theme(app: &App) -> Option<&crate::models::Theme> {
     match &app.preview_state {
         crate::models::PreviewState::Applied(theme) => Some(theme.as_ref()),
@@ -1307,6 +2497,8 @@ fn draw_hybrid_multi_panel_preview(
     area: Rect,
     theme_colors: &crate::models::Theme,
     ui_theme: &ThemeColors,
+    sample: &CodeSample,
+    editor: Option<&ThemeBuilder>,
 ) {
     // Create 4-panel layout
     let main_chunks = Layout::default()
@@ -1338,8 +2530,11 @@ fn draw_hybrid_multi_panel_preview(
     // Draw each panel with the applied theme
     draw_terminal_preview_panel(f, top_chunks[0], theme_colors, ui_theme);
     draw_application_preview_panel(f, top_chunks[1], theme_colors, ui_theme);
-    draw_editor_preview_panel(f, bottom_chunks[0], theme_colors, ui_theme);
-    draw_palette_preview_panel(f, bottom_chunks[1], theme_colors, ui_theme);
+    draw_editor_preview_panel(f, bottom_chunks[0], theme_colors, ui_theme, sample);
+    match editor {
+        Some(editor) => draw_palette_editor(f, bottom_chunks[1], editor, ui_theme),
+        None => draw_palette_preview_panel(f, bottom_chunks[1], theme_colors, ui_theme),
+    }
 }
 
 fn draw_terminal_preview_panel(
@@ -1567,117 +2762,61 @@ fn draw_editor_preview_panel(
     area: Rect,
     theme: &crate::models::Theme,
     ui_theme: &ThemeColors,
+    sample: &CodeSample,
 ) {
     let bg_color = parse_hex_color(&theme.colors.background).unwrap_or(ui_theme.background);
     let fg_color = parse_hex_color(&theme.colors.foreground).unwrap_or(ui_theme.foreground);
-    let keyword_color = parse_hex_color(&theme.colors.bright.magenta).unwrap_or(ui_theme.accent);
-    let string_color = parse_hex_color(&theme.colors.normal.green).unwrap_or(ui_theme.success);
-    let comment_color = parse_hex_color(&theme.colors.normal.black).unwrap_or(ui_theme.muted);
-    let function_color = parse_hex_color(&theme.colors.bright.blue).unwrap_or(ui_theme.primary);
-    let number_color = parse_hex_color(&theme.colors.normal.cyan).unwrap_or(ui_theme.secondary);
+    let gutter_color = parse_hex_color(&theme.colors.bright.black).unwrap_or(ui_theme.muted);
 
-    let editor_content = vec![
-        Line::from(vec![
-            Span::styled("1 ", Style::default().fg(comment_color)),
-            Span::styled(
-                "use ",
-                Style::default()
-                    .fg(keyword_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("std::collections::HashMap;", Style::default().fg(fg_color)),
-        ]),
-        Line::from(vec![Span::styled("2 ", Style::default().fg(comment_color))]),
-        Line::from(vec![
-            Span::styled("3 ", Style::default().fg(comment_color)),
-            Span::styled(
-                "// Theme preview implementation",
-                Style::default()
-                    .fg(comment_color)
-                    .add_modifier(Modifier::ITALIC),
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("4 ", Style::default().fg(comment_color)),
-            Span::styled(
-                "pub fn ",
-                Style::default()
-                    .fg(keyword_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "apply_theme",
-                Style::default()
-                    .fg(function_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("(theme: &", Style::default().fg(fg_color)),
-            Span::styled("Theme", Style::default().fg(keyword_color)),
-            Span::styled(") {", Style::default().fg(fg_color)),
-        ]),
-        Line::from(vec![
-            Span::styled("5 ", Style::default().fg(comment_color)),
-            Span::styled("    ", Style::default()),
-            Span::styled(
-                "let ",
-                Style::default()
-                    .fg(keyword_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("colors = theme.colors;", Style::default().fg(fg_color)),
-        ]),
-        Line::from(vec![
-            Span::styled("6 ", Style::default().fg(comment_color)),
-            Span::styled("    ", Style::default()),
-            Span::styled(
-                "println!",
-                Style::default()
-                    .fg(function_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("(", Style::default().fg(fg_color)),
-            Span::styled("\"Theme: {}\"", Style::default().fg(string_color)),
-            Span::styled(", theme.name);", Style::default().fg(fg_color)),
-        ]),
-        Line::from(vec![
-            Span::styled("7 ", Style::default().fg(comment_color)),
-            Span::styled("}", Style::default().fg(fg_color)),
-        ]),
-        Line::from(vec![Span::styled("8 ", Style::default().fg(comment_color))]),
-        Line::from(vec![
-            Span::styled("9 ", Style::default().fg(comment_color)),
-            Span::styled(
-                "const ",
-                Style::default()
-                    .fg(keyword_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled("MAX_THEMES", Style::default().fg(fg_color)),
-            Span::styled(": ", Style::default().fg(fg_color)),
-            Span::styled("usize ", Style::default().fg(keyword_color)),
-            Span::styled("= ", Style::default().fg(fg_color)),
-            Span::styled(
-                "42",
-                Style::default()
-                    .fg(number_color)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(";", Style::default().fg(fg_color)),
-        ]),
-    ];
+    let lines = highlight(&sample.source, sample.language);
+    let gutter_width = lines.len().to_string().len();
+    let editor_content: Vec<Line> =
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, tokens)| {
+                let mut spans = vec![Span::styled(
+                    format!("{:>gutter_width$} ", i + 1),
+                    Style::default().fg(gutter_color),
+                )];
+                spans.extend(tokens.into_iter().map(|token| {
+                    Span::styled(token.text, token_style(token.kind, theme, fg_color))
+                }));
+                Line::from(spans)
+            })
+            .collect();
 
     let editor_panel = Paragraph::new(editor_content)
         .style(Style::default().bg(bg_color).fg(fg_color))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("📝 Code Editor")
+                .title(format!("📝 Code Editor · {} (l)", sample.title))
                 .border_style(Style::default().fg(ui_theme.primary)),
         );
 
     f.render_widget(editor_panel, area);
 }
 
+/// Map token classes onto the palette the way terminal-derived Neovim colorschemes do
+fn token_style(kind: TokenKind, theme: &crate::models::Theme, fg_color: Color) -> Style {
+    let colors = &theme.colors;
+    let (hex, modifier) = match kind {
+        TokenKind::Keyword => (&colors.normal.magenta, Modifier::BOLD),
+        TokenKind::Type => (&colors.normal.yellow, Modifier::empty()),
+        TokenKind::Function => (&colors.bright.blue, Modifier::BOLD),
+        TokenKind::String => (&colors.normal.green, Modifier::empty()),
+        TokenKind::Number => (&colors.normal.cyan, Modifier::empty()),
+        TokenKind::Constant => (&colors.bright.cyan, Modifier::empty()),
+        TokenKind::Comment => (&colors.bright.black, Modifier::ITALIC),
+        TokenKind::Property | TokenKind::Variable => (&colors.normal.red, Modifier::empty()),
+        TokenKind::Operator | TokenKind::Plain => (&colors.foreground, Modifier::empty()),
+    };
+    Style::default()
+        .fg(parse_hex_color(hex).unwrap_or(fg_color))
+        .add_modifier(modifier)
+}
+
 fn draw_palette_preview_panel(
     f: &mut Frame,
     area: Rect,
@@ -1687,6 +2826,8 @@ fn draw_palette_preview_panel(
     let bg_color = parse_hex_color(&theme.colors.background).unwrap_or(ui_theme.background);
     let fg_color = parse_hex_color(&theme.colors.foreground).unwrap_or(ui_theme.foreground);
 
+    let contrast = |hex: &str| contrast_span(hex, &theme.colors.background, ui_theme);
+
     let palette_content = vec![
         Line::from(vec![Span::styled(
             "Color Palette",
@@ -1714,6 +2855,7 @@ fn draw_palette_preview_panel(
                 &theme.colors.foreground,
                 Style::default().fg(ui_theme.muted),
             ),
+            contrast(&theme.colors.foreground),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -1727,6 +2869,7 @@ fn draw_palette_preview_panel(
                 Style::default().fg(ui_theme.muted),
             ),
             Span::styled(" RED", Style::default().fg(ui_theme.muted)),
+            contrast(&theme.colors.normal.red),
         ]),
         Line::from(vec![
             Span::styled(
@@ -1739,6 +2882,7 @@ fn draw_palette_preview_panel(
                 Style::default().fg(ui_theme.muted),
             ),
             Span::styled(" GREEN", Style::default().fg(ui_theme.muted)),
+            contrast(&theme.colors.normal.green),
         ]),
         Line::from(vec![
             Span::styled(
@@ -1751,6 +2895,7 @@ fn draw_palette_preview_panel(
                 Style::default().fg(ui_theme.muted),
             ),
             Span::styled(" BLUE", Style::default().fg(ui_theme.muted)),
+            contrast(&theme.colors.normal.blue),
         ]),
         Line::from(vec![
             Span::styled(
@@ -1763,6 +2908,7 @@ fn draw_palette_preview_panel(
                 Style::default().fg(ui_theme.muted),
             ),
             Span::styled(" YELLOW", Style::default().fg(ui_theme.muted)),
+            contrast(&theme.colors.normal.yellow),
         ]),
         Line::from(vec![
             Span::styled(
@@ -1775,6 +2921,7 @@ fn draw_palette_preview_panel(
                 Style::default().fg(ui_theme.muted),
             ),
             Span::styled(" MAGENTA", Style::default().fg(ui_theme.muted)),
+            contrast(&theme.colors.normal.magenta),
         ]),
         Line::from(vec![
             Span::styled(
@@ -1787,6 +2934,7 @@ fn draw_palette_preview_panel(
                 Style::default().fg(ui_theme.muted),
             ),
             Span::styled(" CYAN", Style::default().fg(ui_theme.muted)),
+            contrast(&theme.colors.normal.cyan),
         ]),
     ];
 
@@ -1802,6 +2950,151 @@ fn draw_palette_preview_panel(
     f.render_widget(palette_panel, area);
 }
 
+/// Contrast of a palette color against the background, colored by WCAG level
+/// The palette panel with the focused swatch editable through sliders
+fn draw_palette_editor(f: &mut Frame, area: Rect, editor: &ThemeBuilder, ui_theme: &ThemeColors) {
+    let theme = &editor.theme;
+    let bg_color = parse_hex_color(&theme.colors.background).unwrap_or(ui_theme.background);
+    let fg_color = parse_hex_color(&theme.colors.foreground).unwrap_or(ui_theme.foreground);
+    let (name, hex) = editor.selected_slot();
+    let color = editor.selected_color();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "🎨 Editing {}{}",
+            theme.name,
+            if editor.is_modified() { " *" } else { "" }
+        ))
+        .border_style(Style::default().fg(ui_theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(Block::default().style(Style::default().bg(bg_color)), area);
+    f.render_widget(block, area);
+
+    // Slider bars fill whatever is left of the line after the label and value
+    let bar_width = usize::from(inner.width.saturating_sub(12)).max(1);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                name,
+                Style::default().fg(fg_color).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    " {}/{}",
+                    editor.selected + 1,
+                    crate::models::ThemeColors::SLOT_COUNT
+                ),
+                Style::default().fg(ui_theme.muted),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("████ ", Style::default().fg(to_color(color))),
+            Span::styled(hex.to_string(), Style::default().fg(fg_color)),
+            contrast_span(hex, &theme.colors.background, ui_theme),
+        ]),
+        Line::from(
+            theme
+                .colors
+                .slots()
+                .into_iter()
+                .map(|(_, hex)| {
+                    Span::styled(
+                        "█",
+                        Style::default().fg(parse_hex_color(hex).unwrap_or(bg_color)),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Line::from(Span::styled(
+            format!("{}▲", " ".repeat(editor.selected)),
+            Style::default().fg(fg_color),
+        )),
+    ];
+
+    for channel in editor.channel.group() {
+        let focused = channel == editor.channel;
+        let value = channel.value(color);
+        let marker = ((value / channel.max()) * (bar_width - 1) as f64).round() as usize;
+        let mut spans = vec![Span::styled(
+            format!("{} {} ", if focused { "▶" } else { " " }, channel.label()),
+            if focused {
+                Style::default().fg(fg_color).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(ui_theme.muted)
+            },
+        )];
+        spans.extend((0..bar_width).map(|i| {
+            let step = channel.max() * i as f64 / (bar_width - 1).max(1) as f64;
+            let shade = to_color(channel.with_value(color, step));
+            if i == marker.min(bar_width - 1) {
+                Span::styled("┃", Style::default().fg(fg_color).bg(shade))
+            } else {
+                Span::styled(" ", Style::default().bg(shade))
+            }
+        }));
+        let unit = match channel {
+            Channel::Hue => "°",
+            Channel::Saturation | Channel::Lightness => "%",
+            _ => "",
+        };
+        spans.push(Span::styled(
+            format!(" {:>3.0}{unit}", value),
+            Style::default().fg(ui_theme.muted),
+        ));
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(match &editor.input {
+        Some(input) => Line::from(vec![
+            Span::styled(
+                format!("{}: ", input.label()),
+                Style::default()
+                    .fg(ui_theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(input.text().to_string(), Style::default().fg(ui_theme.warning)),
+            Span::styled(
+                "▌",
+                Style::default()
+                    .fg(ui_theme.warning)
+                    .add_modifier(Modifier::SLOW_BLINK),
+            ),
+            Span::styled(" Enter apply, ESC cancel", Style::default().fg(ui_theme.muted)),
+        ]),
+        None => Line::from(Span::styled(
+            "h/l adjust (H/L ×10), Tab slider, m HSL/RGB, # hex, u/U undo/redo, w save, x alacritty.toml",
+            Style::default().fg(ui_theme.muted),
+        )),
+    });
+
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
+fn to_color(color: Rgb) -> Color {
+    Color::Rgb(color.r, color.g, color.b)
+}
+
+fn contrast_span(hex: &str, background: &str, ui_theme: &ThemeColors) -> Span<'static> {
+    let Some(ratio) = Rgb::from_hex(hex)
+        .zip(Rgb::from_hex(background))
+        .map(|(color, background)| color.contrast_ratio(background))
+    else {
+        return Span::raw("");
+    };
+
+    let color = if ratio >= AA_TEXT {
+        ui_theme.success
+    } else if ratio >= AA_LARGE {
+        ui_theme.warning
+    } else {
+        ui_theme.error
+    };
+    Span::styled(format!(" {ratio:.1}:1"), Style::default().fg(color))
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -1824,16 +3117,201 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 
 // Helper function to parse hex colors to ratatui Color
 fn parse_hex_color(hex: &str) -> Option<Color> {
-    let hex = hex.strip_prefix('#').unwrap_or(hex);
-    if hex.len() != 6 {
-        return None;
+    Rgb::from_hex(hex).map(Color::from)
+}
+
+/// Render markdown blocks as styled lines, a blank line between top level blocks
+fn markdown_lines(blocks: &[MarkdownBlock], theme: &ThemeColors) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        if index > 0 {
+            lines.push(Line::default());
+        }
+        lines.extend(markdown_block_lines(block, theme));
     }
+    lines
+}
 
-    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+fn markdown_block_lines(block: &MarkdownBlock, theme: &ThemeColors) -> Vec<Line<'static>> {
+    match block {
+        MarkdownBlock::Paragraph(inlines) => inline_lines(inlines, theme),
+        MarkdownBlock::Heading { level, text } => {
+            let mut style = Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD);
+            if *level <= 3 {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            vec![Line::from(Span::styled(text.clone(), style))]
+        }
+        MarkdownBlock::Code { language, code } => {
+            let language = language
+                .as_deref()
+                .map(Language::from_name)
+                .unwrap_or(Language::PlainText);
+            let gutter = Span::styled("│ ", Style::default().fg(theme.muted));
+            highlight(code, language)
+                .into_iter()
+                .map(|tokens| {
+                    let mut spans = vec![gutter.clone()];
+                    spans.extend(tokens.into_iter().map(|token| {
+                        Span::styled(token.text.to_string(), code_token_style(token.kind, theme))
+                    }));
+                    Line::from(spans)
+                })
+                .collect()
+        }
+        MarkdownBlock::List { start, items } => {
+            let mut lines = Vec::new();
+            for (index, item) in items.iter().enumerate() {
+                let marker = match start {
+                    Some(start) => format!("{}. ", start + index as u64),
+                    None => "• ".to_string(),
+                };
+                let indent = " ".repeat(Span::raw(marker.as_str()).width());
+                let item_lines = item
+                    .iter()
+                    .flat_map(|block| markdown_block_lines(block, theme));
+                for (row, line) in item_lines.enumerate() {
+                    let prefix = if row == 0 {
+                        Span::styled(marker.clone(), Style::default().fg(theme.primary))
+                    } else {
+                        Span::raw(indent.clone())
+                    };
+                    lines.push(prefixed(prefix, line));
+                }
+            }
+            lines
+        }
+        MarkdownBlock::Quote(blocks) => markdown_lines(blocks, theme)
+            .into_iter()
+            .map(|line| {
+                let line = line.style(Style::default().add_modifier(Modifier::ITALIC));
+                prefixed(Span::styled("▌ ", Style::default().fg(theme.muted)), line)
+            })
+            .collect(),
+        MarkdownBlock::Table { header, rows } => table_lines(header, rows, theme),
+        MarkdownBlock::Rule => vec![Line::from(Span::styled(
+            "─".repeat(40),
+            Style::default().fg(theme.muted),
+        ))],
+    }
+}
 
-    Some(Color::Rgb(r, g, b))
+fn prefixed(prefix: Span<'static>, line: Line<'static>) -> Line<'static> {
+    let mut spans = vec![prefix];
+    spans.extend(line.spans);
+    Line::from(spans).style(line.style)
+}
+
+/// Inline runs as lines, split at hard breaks
+fn inline_lines(inlines: &[Inline], theme: &ThemeColors) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::default()];
+    for inline in inlines {
+        if inline.text == "\n" {
+            lines.push(Line::default());
+            continue;
+        }
+        let text = if inline.style.image {
+            format!("🖼 {}", inline.text)
+        } else {
+            inline.text.clone()
+        };
+        if let Some(line) = lines.last_mut() {
+            line.spans
+                .push(Span::styled(text, inline_style(inline, theme)));
+        }
+    }
+    lines
+}
+
+fn inline_style(inline: &Inline, theme: &ThemeColors) -> Style {
+    let mut style = Style::default();
+    if inline.style.bold {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    if inline.style.italic {
+        style = style.add_modifier(Modifier::ITALIC);
+    }
+    if inline.style.strikethrough {
+        style = style.add_modifier(Modifier::CROSSED_OUT);
+    }
+    if inline.style.code {
+        style = style.fg(theme.warning);
+    }
+    if inline.style.link.is_some() {
+        style = style.fg(theme.primary).add_modifier(Modifier::UNDERLINED);
+    }
+    style
+}
+
+/// A table with columns padded to their widest cell
+fn table_lines(
+    header: &[Vec<Inline>],
+    rows: &[Vec<Vec<Inline>>],
+    theme: &ThemeColors,
+) -> Vec<Line<'static>> {
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain([header.len()])
+        .max()
+        .unwrap_or(0);
+    let mut widths = vec![0; columns];
+    for row in rows.iter().chain([&header.to_vec()]) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(Span::raw(plain_text(cell)).width());
+        }
+    }
+
+    let separator = Span::styled(" │ ", Style::default().fg(theme.muted));
+    let row_line = |row: &[Vec<Inline>], bold: bool| {
+        let mut spans = Vec::new();
+        for (column, width) in widths.iter().enumerate() {
+            if column > 0 {
+                spans.push(separator.clone());
+            }
+            let cell = row.get(column).map(Vec::as_slice).unwrap_or_default();
+            for inline in cell {
+                let mut style = inline_style(inline, theme);
+                if bold {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                spans.push(Span::styled(inline.text.clone(), style));
+            }
+            spans.push(Span::raw(
+                " ".repeat(width - Span::raw(plain_text(cell)).width()),
+            ));
+        }
+        Line::from(spans)
+    };
+
+    let mut lines = vec![row_line(header, true)];
+    let rule: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+    lines.push(Line::from(Span::styled(
+        rule.join("─┼─"),
+        Style::default().fg(theme.muted),
+    )));
+    lines.extend(rows.iter().map(|row| row_line(row, false)));
+    lines
+}
+
+/// Style for code block tokens, from the interface palette
+fn code_token_style(kind: TokenKind, theme: &ThemeColors) -> Style {
+    match kind {
+        TokenKind::Keyword => Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
+        TokenKind::Type => Style::default().fg(theme.warning),
+        TokenKind::Function => Style::default().fg(theme.primary),
+        TokenKind::String => Style::default().fg(theme.success),
+        TokenKind::Number | TokenKind::Constant => Style::default().fg(theme.secondary),
+        TokenKind::Comment => Style::default()
+            .fg(theme.muted)
+            .add_modifier(Modifier::ITALIC),
+        TokenKind::Property | TokenKind::Variable => Style::default().fg(theme.error),
+        TokenKind::Operator | TokenKind::Plain => Style::default().fg(theme.foreground),
+    }
 }
 
 /// Format markdown text with basic styling hints
@@ -1854,7 +3332,7 @@ fn get_tag_color(tag: &str, theme: &ThemeColors) -> Color {
         "go" | "golang" => Color::Indexed(6), // ANSI cyan
         "java" => Color::Indexed(1),   // ANSI red
         "cpp" | "c++" => Color::Indexed(4), // ANSI blue
-        "tool" | "cli" => theme.primary,
+        "tool" | "cli" | "command-line" => theme.primary,
         "library" => theme.secondary,
         "framework" => theme.accent,
         "web" => Color::Indexed(2), // ANSI green
@@ -1863,18 +3341,3 @@ fn get_tag_color(tag: &str, theme: &ThemeColors) -> Color {
         _ => theme.muted,
     }
 }
-
-/// Format GitHub URL to show only repository name
-fn format_github_url(url: &str) -> String {
-    if let Some(repo_part) = url.strip_prefix("https://github.com/") {
-        // Extract just the owner/repo part
-        let parts: Vec<&str> = repo_part.split('/').collect();
-        if parts.len() >= 2 {
-            format!("{}/{}", parts[0], parts[1])
-        } else {
-            repo_part.to_string()
-        }
-    } else {
-        url.to_string()
-    }
-}
@@ -0,0 +1,391 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, RgbImage};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::io::Cursor;
+
+/// Directory of an Omarchy theme holding its wallpapers
+pub const BACKGROUNDS_DIR: &str = "backgrounds";
+
+/// Extensions of wallpapers that can be decoded
+pub const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// Longest side kept after decoding; plenty for a preview panel at graphics resolution
+const THUMBNAIL_SIZE: u32 = 480;
+
+/// Terminal cells are roughly twice as tall as they are wide
+const CELL_ASPECT: u32 = 2;
+
+/// Largest payload per Kitty graphics escape, as the protocol requires
+const KITTY_CHUNK: usize = 4096;
+
+/// A theme's wallpaper, downscaled once when decoded
+#[derive(Debug, Clone)]
+pub struct Wallpaper {
+    /// File name inside `backgrounds/`
+    pub name: String,
+    pub image: RgbImage,
+}
+
+impl Wallpaper {
+    /// Decode a PNG or JPEG and shrink it to thumbnail size; CPU heavy, so run it off the UI loop
+    pub fn decode(name: String, bytes: &[u8]) -> Result<Self> {
+        let mut image =
+            image::load_from_memory(bytes).with_context(|| format!("Failed to decode {name}"))?;
+        if image.width().max(image.height()) > THUMBNAIL_SIZE {
+            image = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+        }
+        Ok(Self {
+            name,
+            image: image.to_rgb8(),
+        })
+    }
+
+    /// The largest area inside `area` that keeps the image's aspect ratio, centered
+    pub fn fit(&self, area: Rect) -> Rect {
+        let (width, height) = self.image.dimensions();
+        if area.is_empty() || width == 0 || height == 0 {
+            return Rect::default();
+        }
+
+        let columns = u32::from(area.width);
+        let rows = (columns * height).div_ceil(width * CELL_ASPECT);
+        let (columns, rows) = if rows <= u32::from(area.height) {
+            (columns, rows)
+        } else {
+            let rows = u32::from(area.height);
+            ((rows * CELL_ASPECT * width).div_ceil(height), rows)
+        };
+        let (columns, rows) = (columns.max(1) as u16, rows.max(1) as u16);
+
+        Rect::new(
+            area.x + (area.width - columns) / 2,
+            area.y + (area.height - rows) / 2,
+            columns,
+            rows,
+        )
+    }
+
+    /// Area-average when shrinking so colors do not bleed across edges, interpolate when growing
+    fn scaled(&self, width: u32, height: u32) -> RgbImage {
+        let (image_width, image_height) = self.image.dimensions();
+        if width <= image_width && height <= image_height {
+            imageops::thumbnail(&self.image, width, height)
+        } else {
+            imageops::resize(&self.image, width, height, FilterType::Triangle)
+        }
+    }
+
+    /// Upper half blocks colored with two pixels per cell; works in any truecolor terminal
+    pub fn half_blocks(&self, columns: u16, rows: u16) -> Vec<Line<'static>> {
+        if columns == 0 || rows == 0 {
+            return Vec::new();
+        }
+        let pixels = self.scaled(u32::from(columns), u32::from(rows) * 2);
+        let color = |x: u32, y: u32| {
+            let [r, g, b] = pixels.get_pixel(x, y).0;
+            Color::Rgb(r, g, b)
+        };
+
+        (0..u32::from(rows))
+            .map(|row| {
+                Line::from(
+                    (0..u32::from(columns))
+                        .map(|x| {
+                            Span::styled(
+                                "▀",
+                                Style::default()
+                                    .fg(color(x, row * 2))
+                                    .bg(color(x, row * 2 + 1)),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    }
+
+    /// Kitty graphics escapes that draw the image scaled into `columns`×`rows` cells at the cursor
+    pub fn kitty_sequence(&self, columns: u16, rows: u16) -> Result<Vec<u8>> {
+        let mut png = Vec::new();
+        self.image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+        let payload = STANDARD.encode(png);
+
+        let mut out = String::new();
+        let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK).collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(i + 1 < chunks.len());
+            // `q=2` silences replies, `C=1` leaves the cursor where ratatui expects it
+            if i == 0 {
+                let _ = write!(
+                    out,
+                    "\x1b_Gf=100,a=T,q=2,C=1,c={columns},r={rows},m={more};"
+                );
+            } else {
+                let _ = write!(out, "\x1b_Gm={more};");
+            }
+            out.push_str(std::str::from_utf8(chunk)?);
+            out.push_str("\x1b\\");
+        }
+        Ok(out.into_bytes())
+    }
+
+    /// A sixel image of `width`×`height` pixels, quantized to a 6×6×6 color cube
+    pub fn sixel_sequence(&self, width: u32, height: u32) -> Vec<u8> {
+        let pixels = self.scaled(width, height);
+        let level = |value: u8| (usize::from(value) * 5 + 127) / 255;
+        let index = |x: u32, y: u32| {
+            let [r, g, b] = pixels.get_pixel(x, y).0;
+            level(r) * 36 + level(g) * 6 + level(b)
+        };
+
+        let mut out = format!("\x1bPq\"1;1;{width};{height}");
+        let mut defined = [false; 216];
+
+        for band in (0..height).step_by(6) {
+            // One row of sixels per color that appears in this band of six pixel rows
+            let mut rows: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+            for y in band..(band + 6).min(height) {
+                for x in 0..width {
+                    rows.entry(index(x, y))
+                        .or_insert_with(|| vec![0; width as usize])[x as usize] |= 1 << (y - band);
+                }
+            }
+
+            for (color, bits) in rows {
+                if !defined[color] {
+                    defined[color] = true;
+                    let (r, g, b) = (color / 36, color / 6 % 6, color % 6);
+                    let _ = write!(out, "#{color};2;{};{};{}", r * 20, g * 20, b * 20);
+                }
+                let _ = write!(out, "#{color}");
+                encode_sixel_run(&mut out, &bits);
+                out.push('$');
+            }
+            out.push('-');
+        }
+
+        out.push_str("\x1b\\");
+        out.into_bytes()
+    }
+}
+
+/// Append sixel characters, collapsing repeats into `!<count><char>`
+fn encode_sixel_run(out: &mut String, bits: &[u8]) {
+    let mut i = 0;
+    while i < bits.len() {
+        let run = bits[i..].iter().take_while(|&&b| b == bits[i]).count();
+        let symbol = char::from(63 + bits[i]);
+        if run > 3 {
+            let _ = write!(out, "!{run}{symbol}");
+        } else {
+            out.extend(std::iter::repeat_n(symbol, run));
+        }
+        i += run;
+    }
+}
+
+/// Decoded wallpapers by theme name, evicting the least recently used beyond `capacity`.
+///
+/// `None` records a theme without a `backgrounds/` image so it is not fetched again.
+#[derive(Debug)]
+pub struct WallpaperCache {
+    capacity: usize,
+    entries: VecDeque<(String, Option<Wallpaper>)>,
+}
+
+impl Default for WallpaperCache {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl WallpaperCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Option<&Wallpaper>> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, wallpaper)| wallpaper.as_ref())
+    }
+
+    /// Mark an entry as recently used; returns whether it is cached
+    pub fn touch(&mut self, name: &str) -> bool {
+        match self.entries.iter().position(|(key, _)| key == name) {
+            Some(position) => {
+                if let Some(entry) = self.entries.remove(position) {
+                    self.entries.push_back(entry);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn insert(&mut self, name: String, wallpaper: Option<Wallpaper>) {
+        self.entries.retain(|(key, _)| *key != name);
+        while self.entries.len() >= self.capacity.max(1) {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((name, wallpaper));
+    }
+}
+
+/// Pick the wallpaper to preview from a `backgrounds/` listing, first by name
+pub fn pick_background<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    names
+        .into_iter()
+        .filter(|name| {
+            name.rsplit_once('.').is_some_and(|(_, ext)| {
+                IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+            })
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUADRANTS: &[u8] = include_bytes!("../tests/fixtures/quadrants.png");
+
+    fn quadrants() -> Wallpaper {
+        Wallpaper::decode("quadrants.png".to_string(), QUADRANTS).unwrap()
+    }
+
+    #[test]
+    fn test_half_blocks_sample_both_pixel_rows() {
+        let wallpaper = quadrants();
+        assert_eq!(wallpaper.image.dimensions(), (8, 4));
+
+        let lines = wallpaper.half_blocks(2, 1);
+        let cells: Vec<_> = lines[0]
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style.fg, span.style.bg))
+            .collect();
+        assert_eq!(
+            cells,
+            vec![
+                (
+                    "▀",
+                    Some(Color::Rgb(255, 0, 0)),
+                    Some(Color::Rgb(0, 255, 0))
+                ),
+                (
+                    "▀",
+                    Some(Color::Rgb(0, 0, 255)),
+                    Some(Color::Rgb(255, 255, 255))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fit_keeps_aspect_ratio() {
+        let wallpaper = quadrants();
+        // 8×4 pixels is 2:1, which is 40×10 cells with cells twice as tall as wide
+        assert_eq!(
+            wallpaper.fit(Rect::new(0, 0, 40, 20)),
+            Rect::new(0, 5, 40, 10)
+        );
+        assert_eq!(
+            wallpaper.fit(Rect::new(10, 0, 40, 5)),
+            Rect::new(20, 0, 20, 5)
+        );
+    }
+
+    #[test]
+    fn test_graphics_protocol_encodings() {
+        let wallpaper = quadrants();
+
+        let sixel = String::from_utf8(wallpaper.sixel_sequence(8, 4)).unwrap();
+        assert!(sixel.starts_with("\x1bPq\"1;1;8;4"));
+        assert!(sixel.ends_with("-\x1b\\"));
+        // Pure red is the last red level of the cube and fills the top two rows of the left half
+        assert!(sixel.contains("#180;2;100;0;0#180!4B!4?$"));
+
+        let kitty = String::from_utf8(wallpaper.kitty_sequence(10, 5).unwrap()).unwrap();
+        assert!(kitty.starts_with("\x1b_Gf=100,a=T,q=2,C=1,c=10,r=5,m=0;"));
+        let payload = kitty
+            .trim_start_matches(|c| c != ';')
+            .trim_start_matches(';')
+            .trim_end_matches("\x1b\\");
+        let png = STANDARD.decode(payload).unwrap();
+        assert_eq!(
+            Wallpaper::decode("again".into(), &png).unwrap().image,
+            wallpaper.image
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupt_images() {
+        let error = Wallpaper::decode("broken.png".to_string(), &QUADRANTS[..20]).unwrap_err();
+        assert!(error.to_string().contains("broken.png"));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = WallpaperCache::new(2);
+        cache.insert("a".into(), Some(quadrants()));
+        cache.insert("b".into(), None);
+        assert!(cache.touch("a"));
+        cache.insert("c".into(), None);
+
+        assert!(cache.get("a").unwrap().is_some());
+        assert_eq!(cache.get("b").map(|w| w.is_none()), None);
+        assert!(cache.get("c").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_theme_preview_draws_cached_wallpaper() {
+        use crate::models::{PreviewState, ThemeApplicator, ThemeEntry};
+        use crate::tasks::TaskManager;
+        use crate::{App, HttpClient};
+
+        let (tasks, _messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        let theme = ThemeApplicator::create_default_theme();
+        app.theme_entries.push(ThemeEntry {
+            name: theme.name.clone(),
+            url: "https://github.com/user/omarchy-default-theme".to_string(),
+            description: String::new(),
+        });
+        app.wallpapers.insert(theme.name.clone(), Some(quadrants()));
+        app.preview_state = PreviewState::Applied(Box::new(theme));
+        app.theme_browser_mode = true;
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 50)).unwrap();
+        terminal.draw(|f| crate::ui::draw(f, &mut app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let red_blocks = buffer
+            .content()
+            .iter()
+            .filter(|cell| cell.symbol() == "▀" && cell.fg == Color::Rgb(255, 0, 0))
+            .count();
+        assert!(red_blocks > 0);
+        // Half blocks need no graphics overlay
+        assert!(app.layout.wallpaper.is_none());
+    }
+
+    #[test]
+    fn test_pick_background_prefers_first_image() {
+        let names = ["README.md", "2-forest.JPG", "1-ocean.png", "notes.txt"];
+        assert_eq!(pick_background(names), Some("1-ocean.png"));
+        assert_eq!(pick_background(["preview.webp"]), None);
+    }
+}
@@ -0,0 +1,462 @@
+use crate::links;
+use crate::tasks::{AppMessage, TaskSender};
+use anyhow::{Context, Result, anyhow};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::oneshot;
+
+/// Environment variable overriding where repositories are cloned
+pub const WORKSPACE_ENV: &str = "AWSOMARCHY_WORKSPACE";
+
+/// Name of the install script offered after cloning
+pub const INSTALL_SCRIPT: &str = "install.sh";
+
+/// Directory that repositories are cloned into
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+}
+
+/// What already exists at the checkout path of a repository
+#[derive(Debug, Clone, PartialEq)]
+pub enum Checkout {
+    Missing,
+    Existing,
+    /// Something that is not a git checkout is in the way
+    Occupied,
+}
+
+/// Commands run on behalf of the user, with their output streamed to the log panel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GitAction {
+    Clone,
+    Pull,
+    Install,
+}
+
+impl GitAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GitAction::Clone => "git clone",
+            GitAction::Pull => "git pull",
+            GitAction::Install => INSTALL_SCRIPT,
+        }
+    }
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        let root = std::env::var_os(WORKSPACE_ENV)
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join("src")))
+            .unwrap_or_else(|| PathBuf::from("."));
+        Self::new(root)
+    }
+}
+
+impl Workspace {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Where a repository URL is checked out: `owner/repo` for forge links, so
+    /// same-named repositories of different owners stay apart, else the last path segment
+    pub fn checkout_path(&self, url: &str) -> Option<PathBuf> {
+        let path = links::repo_path(url).unwrap_or_else(|| {
+            url.trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(".git")
+                .to_string()
+        });
+
+        // Refuse anything that could escape the workspace
+        let mut checkout = self.root.clone();
+        for name in path.split('/') {
+            if name.is_empty() || name == "." || name == ".." || name.contains('\\') {
+                return None;
+            }
+            checkout.push(name);
+        }
+        Some(checkout)
+    }
+
+    pub fn checkout_state(path: &Path) -> Checkout {
+        if path.join(".git").exists() {
+            Checkout::Existing
+        } else if path.exists() {
+            Checkout::Occupied
+        } else {
+            Checkout::Missing
+        }
+    }
+
+    /// The install script of a checkout, if it ships one
+    pub fn install_script(path: &Path) -> Option<PathBuf> {
+        let script = path.join(INSTALL_SCRIPT);
+        script.is_file().then_some(script)
+    }
+}
+
+/// What to clone for a URL: the repository itself for forge links into a branch or
+/// folder, anything else as it is
+pub fn clone_url(url: &str) -> String {
+    match (links::host(url), links::repo_path(url)) {
+        (Some(host), Some(path)) => format!("https://{host}/{path}"),
+        _ => url.to_string(),
+    }
+}
+
+/// Build the command for an action on the checkout at `path`
+pub fn command(action: GitAction, url: &str, path: &Path) -> Command {
+    let mut command = match action {
+        GitAction::Clone => {
+            let mut command = Command::new("git");
+            command.arg("clone").arg("--").arg(url).arg(path);
+            command
+        }
+        GitAction::Pull => {
+            let mut command = Command::new("git");
+            command.arg("-C").arg(path).args(["pull", "--ff-only"]);
+            command
+        }
+        GitAction::Install => {
+            let mut command = Command::new("bash");
+            command.arg(INSTALL_SCRIPT).current_dir(path);
+            command
+        }
+    };
+    // Never block on a credential prompt the TUI cannot show
+    command.env("GIT_TERMINAL_PROMPT", "0");
+    command
+}
+
+/// Run an action, sending every output line to the UI as it arrives
+pub async fn run(action: GitAction, url: &str, path: &Path, sender: &TaskSender) -> Result<()> {
+    if action == GitAction::Clone {
+        clone(url, path, sender).await
+    } else {
+        execute(action, url, path, sender, std::future::pending()).await
+    }
+}
+
+/// Clone next to the checkout and move it into place once complete, so a clone that
+/// fails or is cancelled never leaves a partial checkout in the way of the next one
+async fn clone(url: &str, path: &Path, sender: &TaskSender) -> Result<()> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(anyhow!("Cannot clone into {}", path.display()));
+    };
+    tokio::fs::create_dir_all(parent)
+        .await
+        .with_context(|| format!("Failed to create {}", parent.display()))?;
+
+    let staging = parent.join(format!(".{}.partial", name.to_string_lossy()));
+    // Left behind if an earlier clone was cut short before its cleanup ran
+    let _ = tokio::fs::remove_dir_all(&staging).await;
+
+    // The clone runs on a task of its own, so that when this one is aborted it can
+    // still stop git, wait for it to exit and only then remove what it wrote
+    let (_cancel, cancelled) = oneshot::channel::<()>();
+    let (url, path, sender) = (url.to_string(), path.to_path_buf(), sender.clone());
+    let clone = tokio::spawn(async move {
+        let cancelled = async {
+            let _ = cancelled.await;
+        };
+        let result = match execute(GitAction::Clone, &url, &staging, &sender, cancelled).await {
+            Ok(()) => tokio::fs::rename(&staging, &path)
+                .await
+                .with_context(|| format!("Failed to move the clone to {}", path.display())),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            let _ = tokio::fs::remove_dir_all(&staging).await;
+        }
+        result
+    });
+    clone.await?
+}
+
+/// Run a command until it exits or `cancelled` completes, which kills it
+async fn execute(
+    action: GitAction,
+    url: &str,
+    path: &Path,
+    sender: &TaskSender,
+    cancelled: impl Future<Output = ()>,
+) -> Result<()> {
+    let mut child = command(action, url, path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start {}", action.label()))?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .map(|out| forward_lines(out, sender.clone()));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|err| forward_lines(err, sender.clone()));
+
+    let status = tokio::select! {
+        status = child.wait() => Some(status?),
+        () = cancelled => {
+            child.kill().await?;
+            None
+        }
+    };
+    // The pipes close once every process git started has exited too, so draining
+    // them both keeps all output and outlasts anything still writing to `path`
+    for forwarder in [stdout, stderr].into_iter().flatten() {
+        let _ = forwarder.await;
+    }
+
+    match status {
+        Some(status) if status.success() => Ok(()),
+        Some(status) => Err(anyhow!("{} failed ({status})", action.label())),
+        None => Err(anyhow!("{} cancelled", action.label())),
+    }
+}
+
+fn forward_lines<R>(reader: R, sender: TaskSender) -> tokio::task::JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if sender.send(AppMessage::CommandOutput(line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command as StdCommand;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = StdCommand::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(["-c", "init.defaultBranch=main"])
+            .args(args)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// A bare "remote" plus a working copy used to push commits into it
+    fn bare_remote(dir: &Path) -> (String, PathBuf) {
+        let remote = dir.join("tool.git");
+        let upstream = dir.join("upstream");
+        std::fs::create_dir_all(&upstream).unwrap();
+        git(dir, &["init", "--bare", "tool.git"]);
+        git(&upstream, &["init"]);
+        std::fs::write(upstream.join(INSTALL_SCRIPT), "echo installed\n").unwrap();
+        git(&upstream, &["add", "."]);
+        git(&upstream, &["commit", "-m", "initial"]);
+        git(&upstream, &["push", remote.to_str().unwrap(), "main"]);
+        (remote.to_string_lossy().into_owned(), upstream)
+    }
+
+    #[test]
+    fn test_checkout_path_uses_owner_and_repository() {
+        let workspace = Workspace::new(PathBuf::from("/work"));
+        assert_eq!(workspace.checkout_path("https://github.com/user/.."), None);
+        assert_eq!(workspace.checkout_path("https://example.com/.."), None);
+
+        // Same-named repositories of two owners get checkouts of their own
+        assert_eq!(
+            workspace.checkout_path("https://github.com/user/tool.git/"),
+            Some(PathBuf::from("/work/user/tool"))
+        );
+        assert_ne!(
+            workspace.checkout_path("https://github.com/other/tool"),
+            workspace.checkout_path("https://github.com/user/tool")
+        );
+        assert_eq!(
+            workspace.checkout_path("/srv/git/tool.git"),
+            Some(PathBuf::from("/work/tool"))
+        );
+    }
+
+    #[test]
+    fn test_tree_links_clone_the_repository() {
+        let workspace = Workspace::new(PathBuf::from("/work"));
+        let tree = "https://github.com/user/tool/tree/main/themes/nord";
+        assert_eq!(clone_url(tree), "https://github.com/user/tool");
+        assert_eq!(
+            workspace.checkout_path(tree),
+            Some(PathBuf::from("/work/user/tool"))
+        );
+        assert_eq!(clone_url("/srv/git/tool.git"), "/srv/git/tool.git");
+    }
+
+    #[tokio::test]
+    async fn test_clone_pull_and_install_against_bare_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let (remote, upstream) = bare_remote(dir.path());
+        let workspace = Workspace::new(dir.path().join("workspace"));
+        let checkout = workspace.checkout_path(&remote).unwrap();
+        let (sender, mut receiver) = unbounded_channel();
+        let sender = TaskSender::new(0, sender);
+
+        // A cancelled clone's leftovers do not get in the way
+        let partial = checkout.with_file_name(".tool.partial");
+        std::fs::create_dir_all(partial.join(".git")).unwrap();
+        assert_eq!(Workspace::checkout_state(&checkout), Checkout::Missing);
+        run(GitAction::Clone, &remote, &checkout, &sender)
+            .await
+            .unwrap();
+        assert_eq!(Workspace::checkout_state(&checkout), Checkout::Existing);
+        assert!(!partial.exists());
+        assert!(Workspace::install_script(&checkout).is_some());
+
+        std::fs::write(upstream.join("README.md"), "updated\n").unwrap();
+        git(&upstream, &["add", "."]);
+        git(&upstream, &["commit", "-m", "update"]);
+        git(&upstream, &["push", &remote, "main"]);
+        run(GitAction::Pull, &remote, &checkout, &sender)
+            .await
+            .unwrap();
+        assert!(checkout.join("README.md").exists());
+
+        run(GitAction::Install, &remote, &checkout, &sender)
+            .await
+            .unwrap();
+        drop(sender);
+        let mut output = Vec::new();
+        while let Some(crate::tasks::TaskMessage {
+            message: AppMessage::CommandOutput(line),
+            ..
+        }) = receiver.recv().await
+        {
+            output.push(line);
+        }
+        assert!(output.iter().any(|line| line == "installed"));
+    }
+
+    #[tokio::test]
+    async fn test_clone_from_app_shows_install_script_before_running() {
+        use crate::links::LinkKind;
+        use crate::models::{CommandPanelState, ReadmeContent, RepositoryEntry, Section};
+        use crate::tasks::{TaskKind, TaskManager};
+        use crate::{App, HttpClient};
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let dir = tempfile::tempdir().unwrap();
+        let (remote, _upstream) = bare_remote(dir.path());
+        let (tasks, mut messages) = TaskManager::channel();
+        let mut app = App::new(HttpClient::new(), tasks);
+        app.workspace = Workspace::new(dir.path().join("workspace"));
+
+        let mut section = Section::new("Tools".to_string());
+        section.entries.push(RepositoryEntry {
+            title: "tool".to_string(),
+            url: remote,
+            description: String::new(),
+            tags: Vec::new(),
+            kind: LinkKind::GitHub,
+            links: Vec::new(),
+        });
+        section.entry_count = 1;
+        app.handle_message(AppMessage::Readme(Ok(ReadmeContent {
+            sections: vec![section],
+            ..ReadmeContent::default()
+        })));
+
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        app.handle_key_event(key('c')).await.unwrap();
+        while app.tasks.is_running(TaskKind::Command) {
+            app.handle_task_message(messages.recv().await.unwrap());
+        }
+        let panel = app.command_panel.as_ref().unwrap();
+        assert!(matches!(
+            &panel.state,
+            CommandPanelState::ConfirmInstall(script) if script.contains("echo installed")
+        ));
+
+        // Skipping the install and cloning again offers a pull instead
+        app.handle_key_event(key('n')).await.unwrap();
+        app.handle_key_event(key('q')).await.unwrap();
+        assert!(app.command_panel.is_none());
+        app.handle_key_event(key('c')).await.unwrap();
+        assert_eq!(
+            app.command_panel.as_ref().unwrap().state,
+            CommandPanelState::ConfirmPull
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_command_is_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(INSTALL_SCRIPT), "exec sleep 30\n").unwrap();
+        let (sender, _receiver) = unbounded_channel();
+        let sender = TaskSender::new(0, sender);
+
+        let started = std::time::Instant::now();
+        let cancelled = tokio::time::sleep(std::time::Duration::from_millis(100));
+        let result = execute(GitAction::Install, "", dir.path(), &sender, cancelled).await;
+        assert!(result.unwrap_err().to_string().contains("cancelled"));
+        assert!(started.elapsed().as_secs() < 10);
+    }
+
+    #[tokio::test]
+    async fn test_aborted_clone_leaves_no_partial_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let (remote, _upstream) = bare_remote(dir.path());
+        let checkout = dir.path().join("workspace").join("tool");
+        let partial = checkout.with_file_name(".tool.partial");
+        let (sender, _receiver) = unbounded_channel();
+        let sender = TaskSender::new(0, sender);
+
+        let clone = tokio::spawn({
+            let checkout = checkout.clone();
+            async move { run(GitAction::Clone, &remote, &checkout, &sender).await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        clone.abort();
+
+        // Cleanup finishes in the background once git has exited
+        for _ in 0..100 {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            if !partial.exists() {
+                break;
+            }
+        }
+        assert!(!partial.exists());
+        assert_ne!(Workspace::checkout_state(&checkout), Checkout::Occupied);
+    }
+
+    #[tokio::test]
+    async fn test_failed_clone_reports_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.git");
+        let (sender, _receiver) = unbounded_channel();
+        let sender = TaskSender::new(0, sender);
+
+        let checkout = dir.path().join("checkout");
+        let result = run(
+            GitAction::Clone,
+            missing.to_str().unwrap(),
+            &checkout,
+            &sender,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(Workspace::checkout_state(&checkout), Checkout::Missing);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}